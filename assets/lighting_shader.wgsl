@@ -0,0 +1,30 @@
+#import bevy_sprite::mesh2d_vertex_output::VertexOutput
+
+struct Light {
+    position: vec2<f32>,
+    radius: f32,
+    intensity: f32,
+    color: vec4<f32>,
+}
+
+// `light_count` of the lights are used, see `MAX_LIGHTS` in lighting.rs
+struct Lighting {
+    ambient: vec4<f32>,
+    light_count: u32,
+    lights: array<Light, 32>,
+}
+
+@group(2) @binding(0) var<uniform> lighting: Lighting;
+
+// the result multiplies what is already drawn, so white leaves the scene unlit
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    var light = lighting.ambient.rgb;
+    for (var i: u32 = 0u; i < lighting.light_count; i += 1u) {
+        let point = lighting.lights[i];
+        let distance = length(in.world_position.xy - point.position);
+        let falloff = 1.0 - smoothstep(0.0, point.radius, distance);
+        light += point.color.rgb * point.intensity * falloff * falloff;
+    }
+    return vec4(min(light, vec3(1.0)), 1.0);
+}
@@ -1,6 +1,18 @@
 #import bevy_render::globals::Globals
 #import bevy_sprite::mesh2d_vertex_output::VertexOutput
 
+struct Ripple {
+    position: vec2<f32>,
+    radius: f32,
+    strength: f32,
+}
+
+// `count` of the ripples are used, see `MAX_RIPPLES` in water.rs
+struct Ripples {
+    count: u32,
+    ripples: array<Ripple, 16>,
+}
+
 @group(0) @binding(1) var<uniform> globals: Globals;
 
 @group(2) @binding(0) var<uniform> radial_scale: f32;
@@ -9,9 +21,21 @@
 @group(2) @binding(3) var<uniform> speed: f32;
 @group(2) @binding(4) var<uniform> intensity: f32;
 @group(2) @binding(5) var<uniform> color_offset: vec3<f32>;
+@group(2) @binding(6) var<uniform> foam_color: vec3<f32>;
+@group(2) @binding(7) var<uniform> foam_intensity: f32;
+@group(2) @binding(8) var mask_texture: texture_2d<f32>;
+@group(2) @binding(9) var mask_sampler: sampler;
+@group(2) @binding(10) var<uniform> ripples: Ripples;
+
+// pixels across the ring of a ripple
+const RIPPLE_WIDTH: f32 = 3.0;
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // red is water, green is water along a sandy shore, one texel per tile
+    let mask = textureSample(mask_texture, mask_sampler, in.uv);
+    let alpha = smoothstep(0.45, 0.55, mask.r);
+
     var uv = in.uv;
     uv -= vec2<f32>(0.5, 0.5);
     var uv_len = length(uv);
@@ -22,5 +46,19 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         let delta = globals.time * speed;
         uv_len += intensity * sin(angle * i * i + delta) * sin(uv_len * i * i + delta);
     }
-    return vec4(color_offset - uv_len, 1.0);
-}
\ No newline at end of file
+    var color = color_offset - uv_len;
+
+    // strongest right at the shore, where the water fades out
+    let shore = mask.g * (1.0 - smoothstep(0.5, 0.85, mask.r));
+    let wobble = 0.75 + 0.25 * sin(globals.time * 2.0 + (in.world_position.x + in.world_position.y) * 0.05);
+    color = mix(color, foam_color, clamp(shore * wobble * foam_intensity, 0.0, 1.0));
+
+    for (var i: u32 = 0u; i < ripples.count; i += 1u) {
+        let ripple = ripples.ripples[i];
+        let distance = length(in.world_position.xy - ripple.position);
+        let ring = 1.0 - smoothstep(0.0, RIPPLE_WIDTH, abs(distance - ripple.radius));
+        color = mix(color, foam_color, ring * ripple.strength * 0.5);
+    }
+
+    return vec4(color, alpha);
+}
@@ -1,64 +1,299 @@
+use crate::sprite_manifest::{SheetManifest, SpriteClip};
 use crate::state::GameState;
+use crate::world::damage::{DamageEvent, DamagePhase};
 use bevy::prelude::*;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
-#[derive(Component, Default)]
+/// How a clip goes on after its last frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    /// Starts over from the first frame.
+    #[default]
+    Loop,
+    /// Stays on the last frame and sends [`AnimationFinished`] once it was shown.
+    Once,
+    /// Plays backwards to the first frame, then forwards again.
+    PingPong,
+}
+
+/// What an entity is doing, as far as its animation is concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Run,
+    Attack,
+    Hurt,
+    Death,
+}
+
+/// Plays the clip of an entity's current [`AnimationState`] on its sprite.
+/// States without a clip of their own play the idle clip.
+#[derive(Component, Debug, Clone)]
 #[require(Sprite)]
-pub struct AnimationIndices {
-    pub first: usize,
-    pub last: usize,
+pub struct Animator {
+    clips: HashMap<AnimationState, SpriteClip>,
+    state: AnimationState,
+    /// Frame of the current clip, counted from its first.
+    frame: usize,
+    /// Whether a ping-pong clip is on its way back.
+    reverse: bool,
+    timer: Timer,
+    finished: bool,
+    /// A finish that happened outside of [`Animator::tick`], reported by the next tick.
+    unreported_finish: bool,
 }
 
-#[derive(Component, Deref, DerefMut, Default)]
-#[require(AnimationIndices, Sprite)]
-pub struct AnimationTimer(pub Timer);
+/// Sent when a clip played [`PlayMode::Once`] was shown to the end,
+/// or right away when an entity without a death clip dies.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub state: AnimationState,
+}
 
 pub struct AnimatorPlugin;
 
-impl AnimationIndices {
-    pub fn new(first: usize, last: usize) -> Self {
-        Self { first, last }
+impl AnimationState {
+    pub const ALL: [AnimationState; 5] = [
+        AnimationState::Idle,
+        AnimationState::Run,
+        AnimationState::Attack,
+        AnimationState::Hurt,
+        AnimationState::Death,
+    ];
+
+    /// The suffix of the state's clip in the sprite manifest, e.g. `player_run`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimationState::Idle => "idle",
+            AnimationState::Run => "run",
+            AnimationState::Attack => "attack",
+            AnimationState::Hurt => "hurt",
+            AnimationState::Death => "death",
+        }
+    }
+
+    /// States that play once and then give way to what the entity is doing.
+    pub fn is_one_shot(&self) -> bool {
+        matches!(
+            self,
+            AnimationState::Attack | AnimationState::Hurt | AnimationState::Death
+        )
+    }
+
+    /// Higher states interrupt lower ones, but not the other way around.
+    fn priority(&self) -> u8 {
+        match self {
+            AnimationState::Idle | AnimationState::Run => 0,
+            AnimationState::Attack => 1,
+            AnimationState::Hurt => 2,
+            AnimationState::Death => 3,
+        }
     }
+}
 
-    pub fn from_length(first: usize, length: usize) -> Self {
+impl Animator {
+    /// An animator that only plays `idle`.
+    pub fn new(idle: SpriteClip) -> Self {
         Self {
-            first,
-            last: first + length - 1,
+            clips: HashMap::from([(AnimationState::Idle, idle)]),
+            state: AnimationState::Idle,
+            frame: 0,
+            reverse: false,
+            timer: Timer::from_seconds(idle.frame_duration, TimerMode::Repeating),
+            finished: false,
+            unreported_finish: false,
+        }
+    }
+
+    /// The clips named `<prefix>_<state>` in the manifest, e.g. `player_idle` and `player_run`.
+    pub fn from_manifest(manifest: &SheetManifest, prefix: &str) -> Self {
+        let mut animator = Self::new(manifest.clip(&format!("{prefix}_idle")));
+        for state in AnimationState::ALL {
+            if let Some(clip) = manifest.clips.get(&format!("{prefix}_{}", state.name())) {
+                animator = animator.with_clip(state, *clip);
+            }
+        }
+        animator
+    }
+
+    pub fn with_clip(mut self, state: AnimationState, clip: SpriteClip) -> Self {
+        self.clips.insert(state, clip);
+        if state == self.state {
+            self.restart();
+        }
+        self
+    }
+
+    pub fn state(&self) -> AnimationState {
+        self.state
+    }
+
+    pub fn has_clip(&self, state: AnimationState) -> bool {
+        self.clips.contains_key(&state)
+    }
+
+    pub fn clip(&self) -> SpriteClip {
+        self.clips
+            .get(&self.state)
+            .or_else(|| self.clips.get(&AnimationState::Idle))
+            .copied()
+            .expect("an animator always has an idle clip")
+    }
+
+    /// The atlas index of the frame being shown.
+    pub fn index(&self) -> usize {
+        let clip = self.clip();
+        // a state without a clip may have taken over in the middle of a longer one
+        clip.first + self.frame.min(clip.length - 1)
+    }
+
+    /// Whether a clip played once reached its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Switches to `state`, unless a one-shot state that outranks it is still playing.
+    /// Death is never left, and one-shot states without a clip are skipped, except death,
+    /// which then finishes right away on the frame being shown.
+    /// Returns whether the state changed.
+    pub fn request(&mut self, state: AnimationState) -> bool {
+        if self.state == AnimationState::Death {
+            return false;
+        }
+        let busy = self.state.is_one_shot() && !self.finished;
+        if busy && state.priority() < self.state.priority() {
+            return false;
+        }
+        if state.is_one_shot() && !self.has_clip(state) {
+            if state == AnimationState::Death {
+                self.state = state;
+                self.finished = true;
+                self.unreported_finish = true;
+                return true;
+            }
+            return false;
+        }
+        if state == self.state && !state.is_one_shot() {
+            return false;
+        }
+        self.state = state;
+        self.restart();
+        true
+    }
+
+    fn restart(&mut self) {
+        let frame_duration = self.clip().frame_duration;
+        self.frame = 0;
+        self.reverse = false;
+        self.finished = false;
+        self.unreported_finish = false;
+        self.timer = Timer::from_seconds(frame_duration, TimerMode::Repeating);
+    }
+
+    /// Advances the clip by `delta`. Returns whether a clip played once just finished.
+    pub fn tick(&mut self, delta: Duration) -> bool {
+        if self.finished {
+            return std::mem::take(&mut self.unreported_finish);
+        }
+        self.timer.tick(delta);
+        for _ in 0..self.timer.times_finished_this_tick() {
+            if self.advance() {
+                return true;
+            }
         }
+        false
     }
 
-    #[inline]
-    pub fn with_first(&mut self, first: usize) {
-        let diff = self.last - self.first;
-        self.first = first;
-        self.last = first + diff;
+    fn advance(&mut self) -> bool {
+        let clip = self.clip();
+        let last = clip.length - 1;
+        match clip.mode {
+            PlayMode::Loop => {
+                self.frame = if self.frame >= last {
+                    0
+                } else {
+                    self.frame + 1
+                }
+            }
+            PlayMode::Once => {
+                if self.frame >= last {
+                    self.finished = true;
+                    return true;
+                }
+                self.frame += 1;
+            }
+            PlayMode::PingPong if last == 0 => {}
+            PlayMode::PingPong => {
+                if (self.reverse && self.frame == 0) || (!self.reverse && self.frame >= last) {
+                    self.reverse = !self.reverse;
+                }
+                if self.reverse {
+                    self.frame -= 1;
+                } else {
+                    self.frame += 1;
+                }
+            }
+        }
+        false
     }
 
-    #[inline]
-    pub fn with_length(&mut self, length: usize) {
-        self.last = self.first + length - 1;
+    /// Once a one-shot state is done, falls back to idle so movement can take over.
+    fn settle(&mut self) {
+        if self.finished && self.state.is_one_shot() && self.state != AnimationState::Death {
+            self.state = AnimationState::Idle;
+            self.restart();
+        }
     }
 }
 
 impl Plugin for AnimatorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, animate.run_if(in_state(GameState::Running)));
+        app.add_event::<AnimationFinished>().add_systems(
+            Update,
+            (play_hurt_animations.after(DamagePhase::After), animate)
+                .chain()
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn play_hurt_animations(
+    mut damage_events: EventReader<DamageEvent>,
+    mut animator_query: Query<&mut Animator>,
+) {
+    for event in damage_events.read() {
+        if !event.apply {
+            continue;
+        }
+        if let Ok(mut animator) = animator_query.get_mut(event.target) {
+            animator.request(AnimationState::Hurt);
+        }
     }
 }
 
 fn animate(
     time: Res<Time>,
-    mut query: Query<(&AnimationIndices, &mut AnimationTimer, &mut Sprite)>,
+    mut query: Query<(Entity, &mut Animator, &mut Sprite)>,
+    mut finished_events: EventWriter<AnimationFinished>,
 ) {
-    for (indices, mut timer, mut sprite) in &mut query {
-        timer.tick(time.delta());
+    for (entity, mut animator, mut sprite) in &mut query {
+        animator.settle();
+        if animator.tick(time.delta()) {
+            finished_events.send(AnimationFinished {
+                entity,
+                state: animator.state(),
+            });
+        }
 
-        if timer.just_finished() {
-            if let Some(atlas) = &mut sprite.texture_atlas {
-                atlas.index = if atlas.index < indices.first || atlas.index >= indices.last {
-                    indices.first
-                } else {
-                    atlas.index + 1
-                }
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            let index = animator.index();
+            if atlas.index != index {
+                atlas.index = index;
             }
         }
     }
@@ -0,0 +1,178 @@
+use crate::config::*;
+use crate::world::damage::{DamageEvent, DamagePhase};
+use crate::world::dying::Dying;
+use crate::world::gun::ShotFired;
+use crate::world::player::Player;
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use bevy_button_released_plugin::OnButtonReleased;
+
+pub mod music;
+
+/// Names of the sounds played by the game, see `[audio.sounds]` in the config.
+pub mod sounds {
+    pub const SHOT: &str = "shot";
+    pub const HIT: &str = "hit";
+    pub const ENEMY_DEATH: &str = "enemy_death";
+    pub const PLAYER_HURT: &str = "player_hurt";
+    pub const BUTTON: &str = "button";
+}
+
+/// Plays sound effects for gameplay events and music for the current state. Sounds
+/// come from the registry in `[audio.sounds]` and play on the volume bus they name.
+#[derive(Default)]
+pub struct SoundPlugin;
+
+/// Asks for a sound of the registry to be played, by name.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct PlaySound(pub String);
+
+/// The loaded sounds of the registry.
+#[derive(Resource, Debug, Default)]
+pub struct SoundLibrary(pub HashMap<String, Handle<AudioSource>>);
+
+/// A playing effect or UI sound, counted for voice limiting.
+#[derive(Component, Debug)]
+pub struct SoundVoice(pub String);
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SoundSet;
+
+impl PlaySound {
+    pub fn new(name: &str) -> Self {
+        Self(name.to_string())
+    }
+}
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PlaySound>()
+            .init_resource::<SoundLibrary>()
+            .add_systems(Startup, load_sounds)
+            .add_systems(
+                Update,
+                (
+                    (
+                        shot_sounds,
+                        damage_sounds.after(DamagePhase::After),
+                        death_sounds,
+                    )
+                        .before(SoundSet),
+                    play_sounds.in_set(SoundSet),
+                    apply_config_changes.before(SoundSet),
+                ),
+            )
+            .add_observer(button_sound)
+            .add_plugins(music::MusicPlugin);
+    }
+}
+
+impl SoundLibrary {
+    /// Starts loading every sound of the registry that has a file.
+    pub fn load(asset_server: &AssetServer, audio: &AudioConfig) -> Self {
+        Self(
+            audio
+                .sounds
+                .iter()
+                .filter(|(_, sound)| !sound.path.is_empty())
+                .map(|(name, sound)| (name.clone(), asset_server.load(&sound.path)))
+                .collect(),
+        )
+    }
+}
+
+fn load_sounds(mut commands: Commands, asset_server: Res<AssetServer>, config: Res<GameConfig>) {
+    commands.insert_resource(SoundLibrary::load(&asset_server, &config.audio));
+}
+
+fn shot_sounds(mut shot_events: EventReader<ShotFired>, mut sound_events: EventWriter<PlaySound>) {
+    for _ in shot_events.read() {
+        sound_events.send(PlaySound::new(sounds::SHOT));
+    }
+}
+
+/// A hit for every damage that was applied, a different one when the player is hurt.
+fn damage_sounds(
+    mut damage_events: EventReader<DamageEvent>,
+    player_query: Query<(), With<Player>>,
+    mut sound_events: EventWriter<PlaySound>,
+) {
+    for event in damage_events.read() {
+        if !event.apply {
+            continue;
+        }
+        let sound = if player_query.contains(event.target) {
+            sounds::PLAYER_HURT
+        } else {
+            sounds::HIT
+        };
+        sound_events.send(PlaySound::new(sound));
+    }
+}
+
+fn death_sounds(death_query: Query<(), Added<Dying>>, mut sound_events: EventWriter<PlaySound>) {
+    for _ in death_query.iter() {
+        sound_events.send(PlaySound::new(sounds::ENEMY_DEATH));
+    }
+}
+
+fn button_sound(_trigger: Trigger<OnButtonReleased>, mut sound_events: EventWriter<PlaySound>) {
+    sound_events.send(PlaySound::new(sounds::BUTTON));
+}
+
+/// Plays the requested sounds, dropping those over the voice limit of the sound or
+/// of all sounds, so a hundred hits in one frame do not play a hundred times.
+fn play_sounds(
+    mut commands: Commands,
+    mut sound_events: EventReader<PlaySound>,
+    voice_query: Query<&SoundVoice>,
+    library: Res<SoundLibrary>,
+    config: Res<GameConfig>,
+) {
+    let mut total = voice_query.iter().count();
+    let mut playing: HashMap<&str, usize> = HashMap::new();
+    for voice in voice_query.iter() {
+        *playing.entry(voice.0.as_str()).or_default() += 1;
+    }
+
+    for PlaySound(name) in sound_events.read() {
+        let (Some(sound), Some(handle)) = (config.audio.sounds.get(name), library.0.get(name))
+        else {
+            continue;
+        };
+        let voices = playing.entry(name.as_str()).or_default();
+        if *voices >= sound.max_voices || total >= config.audio.max_voices {
+            continue;
+        }
+        *voices += 1;
+        total += 1;
+        commands.spawn((
+            SoundVoice(name.clone()),
+            AudioPlayer(handle.clone()),
+            PlaybackSettings::DESPAWN.with_volume(Volume::new(config.audio.sound_volume(sound))),
+        ));
+    }
+}
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    voice_query: Query<(&SoundVoice, &AudioSink)>,
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<SoundLibrary>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    if changed.contains("audio.sounds") {
+        *library = SoundLibrary::load(&asset_server, &config.audio);
+    }
+    if changed.contains("audio") {
+        for (voice, sink) in voice_query.iter() {
+            if let Some(sound) = config.audio.sounds.get(&voice.0) {
+                sink.set_volume(config.audio.sound_volume(sound));
+            }
+        }
+    }
+}
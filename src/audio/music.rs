@@ -0,0 +1,137 @@
+use crate::audio::{SoundLibrary, SoundSet};
+use crate::config::{ConfigChanged, GameConfig};
+use crate::state::{AppState, GameState};
+use bevy::audio::{AudioSinkPlayback, Volume};
+use bevy::prelude::*;
+
+/// Loops the track `[audio.music]` names for the current state.
+#[derive(Default)]
+pub struct MusicPlugin;
+
+/// The looping music, with the name of its sound.
+#[derive(Component, Debug)]
+pub struct MusicTrack(pub String);
+
+/// Music paused while the game is, to resume where it left off.
+#[derive(Component, Debug)]
+pub struct SuspendedMusic;
+
+/// The sound the music should be playing. `None` before the first state is known,
+/// so that a change of track always starts it over.
+#[derive(Resource, Debug, Default)]
+pub struct CurrentMusic(pub Option<String>);
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CurrentMusic>().add_systems(
+            Update,
+            // after the registry is reloaded
+            (apply_config_changes, play_state_music)
+                .chain()
+                .after(SoundSet),
+        );
+    }
+}
+
+/// The name of the track for the current state, empty for silence.
+fn state_track<'a>(
+    config: &'a GameConfig,
+    app_state: &AppState,
+    game_state: Option<&GameState>,
+) -> &'a str {
+    let music = &config.audio.music;
+    match (app_state, game_state) {
+        (AppState::Loading, _) => "",
+        (AppState::MainMenu, _) => &music.main_menu,
+        (AppState::InGame, Some(GameState::Paused)) => &music.paused,
+        (AppState::InGame, Some(GameState::GameOver)) => &music.game_over,
+        (AppState::InGame, _) => &music.running,
+    }
+}
+
+/// Switches to the track of the current state. The game's track is suspended while
+/// the game is paused instead of stopped, any other is stopped.
+fn play_state_music(
+    mut commands: Commands,
+    mut track_query: Query<(
+        Entity,
+        &MusicTrack,
+        &mut PlaybackSettings,
+        Option<&AudioSink>,
+        Has<SuspendedMusic>,
+    )>,
+    mut current: ResMut<CurrentMusic>,
+    app_state: Res<State<AppState>>,
+    game_state: Option<Res<State<GameState>>>,
+    library: Res<SoundLibrary>,
+    config: Res<GameConfig>,
+) {
+    let game_state = game_state.as_ref().map(|state| state.get());
+    let track = state_track(&config, app_state.get(), game_state);
+    // a state sharing the track with the previous one keeps it playing
+    if current.0.as_deref() == Some(track) {
+        return;
+    }
+    current.0 = Some(track.to_string());
+    let paused = game_state == Some(&GameState::Paused);
+
+    let mut resumed = false;
+    for (entity, music, mut settings, sink, suspended) in track_query.iter_mut() {
+        if suspended && music.0 == track {
+            // the sink only exists once the file is loaded, until then the settings
+            // decide whether it starts paused
+            match sink {
+                Some(sink) => sink.play(),
+                None => settings.paused = false,
+            }
+            commands.entity(entity).remove::<SuspendedMusic>();
+            resumed = true;
+        } else if paused && !suspended {
+            match sink {
+                Some(sink) => sink.pause(),
+                None => settings.paused = true,
+            }
+            commands.entity(entity).insert(SuspendedMusic);
+        } else {
+            commands.entity(entity).despawn();
+        }
+    }
+    if resumed {
+        return;
+    }
+    let (Some(sound), Some(handle)) = (config.audio.sounds.get(track), library.0.get(track)) else {
+        return;
+    };
+    commands.spawn((
+        MusicTrack(track.to_string()),
+        AudioPlayer(handle.clone()),
+        PlaybackSettings::LOOP.with_volume(Volume::new(config.audio.sound_volume(sound))),
+    ));
+}
+
+fn apply_config_changes(
+    mut commands: Commands,
+    mut changed_events: EventReader<ConfigChanged>,
+    track_query: Query<(Entity, &MusicTrack, Option<&AudioSink>)>,
+    mut current: ResMut<CurrentMusic>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    // a changed file or track starts over, suspended music included
+    if changed.contains("audio.music") || changed.contains("audio.sounds") {
+        for (entity, _, _) in track_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        current.0 = None;
+        return;
+    }
+    if changed.contains("audio") {
+        for (_, track, sink) in track_query.iter() {
+            if let (Some(sound), Some(sink)) = (config.audio.sounds.get(&track.0), sink) {
+                sink.set_volume(config.audio.sound_volume(sound));
+            }
+        }
+    }
+}
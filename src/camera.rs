@@ -1,8 +1,12 @@
+use crate::config::GameConfig;
 use crate::input::CursorPosition;
 use crate::state::GameState;
+use crate::world::map::MapBounds;
 use crate::world::player::Player;
+use avian2d::prelude::LinearVelocity;
 use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
 use bevy::prelude::*;
+use noise::{NoiseFn, Perlin};
 
 #[derive(Component)]
 // #[require(InGame)]
@@ -15,6 +19,16 @@ pub struct CameraZoom;
 #[derive(Resource, Default)]
 pub struct ZoomScale(pub f32);
 
+/// Shakes the camera with the square of its trauma, see `[feedback]` in the config.
+#[derive(Component, Debug, Clone)]
+pub struct CameraShake {
+    trauma: f32,
+    noise: Perlin,
+    /// The shake applied this frame, taken back before the camera follows the player.
+    offset: Vec2,
+    angle: f32,
+}
+
 pub struct SmoothCameraPlugin;
 
 pub const INITIAL_CAMERA_SCALE: f32 = 0.32;
@@ -30,10 +44,38 @@ impl SmoothCamera {
             Msaa::Off,
             SmoothCamera,
             CameraZoom,
+            CameraShake::default(),
         )
     }
 }
 
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            noise: Perlin::new(0),
+            offset: Vec2::ZERO,
+            angle: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Adds to the trauma, which is at most 1.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Smooth noise in -1..=1 at `time`, a different one for each `channel`.
+    fn sample(&self, time: f32, channel: f64) -> f32 {
+        self.noise.get([f64::from(time), channel]) as f32
+    }
+}
+
 impl Plugin for SmoothCameraPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ZoomScale(INITIAL_CAMERA_SCALE))
@@ -42,7 +84,7 @@ impl Plugin for SmoothCameraPlugin {
             .add_systems(
                 Update,
                 (
-                    camera_following_player,
+                    (remove_camera_shake, camera_following_player, shake_camera).chain(),
                     scroll_offset_from_events,
                     do_camera_zoom,
                 )
@@ -51,40 +93,122 @@ impl Plugin for SmoothCameraPlugin {
     }
 }
 
-const FOLLOW_SPEED: f32 = 0.01;
-
 fn spawn_camera(mut commands: Commands) {
     commands.spawn(SmoothCamera::new());
 }
 
-fn reset_camera_scale(mut zoom_scale: ResMut<ZoomScale>) {
-    zoom_scale.0 = INITIAL_CAMERA_SCALE;
+fn reset_camera_scale(mut zoom_scale: ResMut<ZoomScale>, config: Res<GameConfig>) {
+    zoom_scale.0 = INITIAL_CAMERA_SCALE
+        .min(config.camera.zoom_max)
+        .max(config.camera.zoom_min);
+}
+
+/// How much of the remaining distance to cover in `delta` seconds, catching up at
+/// `speed` per second. Two half steps cover as much as a whole one, whatever the frame rate.
+pub fn smoothing(speed: f32, delta: f32) -> f32 {
+    1.0 - (-speed * delta).exp()
+}
+
+/// Moves `center` so that a view of `half_size` around it stays inside `bounds`.
+/// Along an axis where the view is larger than the bounds, it is centered on them.
+pub fn clamp_to_bounds(center: Vec2, half_size: Vec2, bounds: Rect) -> Vec2 {
+    let min = bounds.min + half_size;
+    let max = bounds.max - half_size;
+    let clamp = |value: f32, min: f32, max: f32, middle: f32| {
+        if min > max {
+            middle
+        } else {
+            value.clamp(min, max)
+        }
+    };
+    let middle = bounds.center();
+    Vec2::new(
+        clamp(center.x, min.x, max.x, middle.x),
+        clamp(center.y, min.y, max.y, middle.y),
+    )
 }
 
+/// Leans towards the cursor and ahead of the player's movement, but only once the
+/// player leaves the deadzone, and keeps the view inside the map. Runs on real time,
+/// so the camera does not lag behind through hit-stop.
 fn camera_following_player(
-    mut camera_transform: Single<&mut Transform, With<SmoothCamera>>,
-    player_query: Query<&GlobalTransform, (With<Player>, Without<SmoothCamera>)>,
+    camera: Single<(&mut Transform, &OrthographicProjection), With<SmoothCamera>>,
+    player_query: Query<
+        (&GlobalTransform, Option<&LinearVelocity>),
+        (With<Player>, Without<SmoothCamera>),
+    >,
     cursor_position: Res<CursorPosition>,
+    map_bounds: Option<Res<MapBounds>>,
+    time: Res<Time<Real>>,
+    config: Res<GameConfig>,
 ) {
-    let Ok(player_transform) = player_query.get_single() else {
+    let Ok((player_transform, velocity)) = player_query.get_single() else {
         return;
     };
+    let (mut camera_transform, projection) = camera.into_inner();
+    let camera = &config.camera;
     let player_pos = player_transform.translation().truncate();
-    let offset = (cursor_position.0 - player_pos).normalize_or_zero() * 50.0;
-    let cam_pos = player_pos + offset;
-    camera_transform.translation = camera_transform
-        .translation
-        .lerp(cam_pos.extend(0.0), FOLLOW_SPEED);
+    let offset = (cursor_position.0 - player_pos).normalize_or_zero() * camera.cursor_offset;
+    let look_ahead = velocity.map_or(Vec2::ZERO, |velocity| velocity.0 * camera.look_ahead);
+    let target = player_pos + offset + look_ahead;
+
+    let cam_pos = camera_transform.translation.truncate();
+    let away = target - cam_pos;
+    let beyond_deadzone = (away.length() - camera.deadzone).max(0.0);
+    let target = cam_pos + away.normalize_or_zero() * beyond_deadzone;
+    let mut cam_pos = cam_pos.lerp(target, smoothing(camera.follow_speed, time.delta_secs()));
+    if let Some(map_bounds) = map_bounds {
+        cam_pos = clamp_to_bounds(cam_pos, projection.area.half_size(), map_bounds.0);
+    }
+    camera_transform.translation = cam_pos.extend(camera_transform.translation.z);
+}
+
+fn remove_camera_shake(camera: Single<(&mut Transform, &mut CameraShake)>) {
+    let (mut transform, mut shake) = camera.into_inner();
+    transform.translation -= shake.offset.extend(0.0);
+    transform.rotation = Quat::IDENTITY;
+    shake.offset = Vec2::ZERO;
+    shake.angle = 0.0;
 }
 
-const ZOOM_SPEED: f32 = 0.01;
+/// Moves and turns the camera by noise scaled with the trauma. Runs on real time,
+/// so the camera keeps shaking through hit-stop.
+fn shake_camera(
+    camera: Single<(&mut Transform, &mut CameraShake)>,
+    time: Res<Time<Real>>,
+    config: Res<GameConfig>,
+) {
+    let (mut transform, mut shake) = camera.into_inner();
+    let feedback = &config.feedback;
+    if feedback.reduce_motion {
+        shake.trauma = 0.0;
+        return;
+    }
+    shake.trauma = (shake.trauma - feedback.trauma_decay * time.delta_secs()).max(0.0);
+    if shake.trauma <= 0.0 {
+        return;
+    }
+    let amount = shake.trauma * shake.trauma;
+    let t = time.elapsed_secs() * feedback.shake_frequency;
+    let offset = Vec2::new(shake.sample(t, 0.0), shake.sample(t, 10.0));
+    shake.offset = offset * amount * feedback.shake_offset;
+    shake.angle = shake.sample(t, 20.0) * amount * feedback.shake_angle.to_radians();
+    transform.translation += shake.offset.extend(0.0);
+    transform.rotation = Quat::from_rotation_z(shake.angle);
+}
 
 fn do_camera_zoom(
     mut query: Query<&mut OrthographicProjection, With<CameraZoom>>,
     zoom_scale: Res<ZoomScale>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
 ) {
+    let camera = &config.camera;
+    // the limits may have changed since the last scroll
+    let target = zoom_scale.0.min(camera.zoom_max).max(camera.zoom_min);
+    let t = smoothing(camera.zoom_speed, time.delta_secs());
     for mut proj in &mut query {
-        proj.scale = proj.scale.lerp(zoom_scale.0, ZOOM_SPEED);
+        proj.scale = proj.scale.lerp(target, t);
     }
 }
 
@@ -96,6 +220,7 @@ const ZOOM_SENSITIVITY: f32 = 0.001;
 fn scroll_offset_from_events(
     mut scroll_events: EventReader<MouseWheel>,
     mut zoom_scale: ResMut<ZoomScale>,
+    config: Res<GameConfig>,
 ) {
     let scroll_offset = scroll_events
         .read()
@@ -104,5 +229,8 @@ fn scroll_offset_from_events(
             MouseScrollUnit::Line => ev.y * PIXELS_PER_LINE,
         })
         .sum::<f32>();
-    zoom_scale.0 *= 1. - scroll_offset * ZOOM_SENSITIVITY;
+    let camera = &config.camera;
+    zoom_scale.0 = (zoom_scale.0 * (1. - scroll_offset * ZOOM_SENSITIVITY))
+        .min(camera.zoom_max)
+        .max(camera.zoom_min);
 }
@@ -0,0 +1,150 @@
+use crate::config::*;
+use crate::state::AppState;
+use std::path::PathBuf;
+
+/// Command-line options of the game binary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cli {
+    pub config_path: PathBuf,
+    pub seed: Option<u64>,
+    /// The state to enter once assets are loaded.
+    pub start_state: AppState,
+    pub window_size: Option<(f32, f32)>,
+    pub debug: bool,
+    /// Simulate this many seconds without a window, then print stats and exit.
+    pub headless: Option<f32>,
+    /// Let the built-in bot play.
+    pub bot: bool,
+    /// `key=value` pairs overriding the config file, keys being dotted paths.
+    pub overrides: Vec<(String, String)>,
+    pub replay: Option<PathBuf>,
+    /// Record the runs to this file.
+    pub record: Option<PathBuf>,
+    pub help: bool,
+}
+
+impl Cli {
+    pub const USAGE: &'static str = "\
+Usage: bevy-2d-shooter [options]
+
+Options:
+    --config <path>       Config file to use [default: config.toml]
+    --seed <number>       Seed of the first run
+    --state <menu|game>   Start in the main menu or straight in the game [default: menu]
+    --window <WxH>        Window size, e.g. 1920x1080
+    --debug               Show the debug overlay
+    --headless <seconds>  Simulate without a window, then print stats and exit
+    --bot                 Let the built-in bot play
+    --set <key=value>     Override a config value, e.g. --set enemy.speed=60
+    --replay <path>       Play back a recorded run
+    --record <path>       Record every run to a replay file, overwriting it
+    -h, --help            Print this help";
+
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("missing value for `{}`", name))
+            };
+            match arg.as_str() {
+                "--config" => cli.config_path = value(&arg)?.into(),
+                "--seed" => {
+                    let seed = value(&arg)?;
+                    cli.seed = Some(seed.parse().map_err(|_| format!("invalid seed `{seed}`"))?);
+                }
+                "--state" => {
+                    cli.start_state = match value(&arg)?.as_str() {
+                        "menu" => AppState::MainMenu,
+                        "game" => AppState::InGame,
+                        other => return Err(format!("unknown state `{other}`")),
+                    };
+                }
+                "--window" => {
+                    let size = value(&arg)?;
+                    cli.window_size = Some(
+                        parse_window_size(&size)
+                            .ok_or_else(|| format!("invalid window size `{size}`"))?,
+                    );
+                }
+                "--debug" => cli.debug = true,
+                "--headless" => {
+                    let seconds = value(&arg)?;
+                    cli.headless = Some(
+                        seconds
+                            .parse()
+                            .ok()
+                            .filter(|seconds: &f32| *seconds > 0.0)
+                            .ok_or_else(|| format!("invalid duration `{seconds}`"))?,
+                    );
+                }
+                "--bot" => cli.bot = true,
+                "--set" => {
+                    let pair = value(&arg)?;
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| format!("expected `key=value`, got `{pair}`"))?;
+                    cli.overrides
+                        .push((key.trim().to_string(), value.trim().to_string()));
+                }
+                "--replay" => cli.replay = Some(value(&arg)?.into()),
+                "--record" => cli.record = Some(value(&arg)?.into()),
+                "-h" | "--help" => cli.help = true,
+                other => return Err(format!("unknown option `{other}`")),
+            }
+        }
+        Ok(cli)
+    }
+
+    /// The config sources named on the command line. Options like `--window` become
+    /// overrides too, so that they are applied again on every reload.
+    pub fn config_source(&self) -> ConfigSource {
+        let mut overrides = Vec::new();
+        let mut set = |key: &str, value: String| overrides.push((key.to_string(), value));
+        if let Some((width, height)) = self.window_size {
+            set("basic.window_width", width.to_string());
+            set("basic.window_height", height.to_string());
+        }
+        if self.debug {
+            set("basic.debug", "true".to_string());
+        }
+        if let Some(path) = &self.record {
+            set("replay.record", "true".to_string());
+            // quoted, so that a path like `1` is not taken as a number
+            let path = toml::Value::String(path.to_string_lossy().into_owned());
+            set("replay.path", path.to_string());
+        }
+        // `--set` wins over the other options
+        overrides.extend(self.overrides.iter().cloned());
+        ConfigSource {
+            path: self.config_path.clone(),
+            overrides,
+        }
+    }
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self {
+            config_path: ConfigSource::default().path,
+            seed: None,
+            start_state: AppState::MainMenu,
+            window_size: None,
+            debug: false,
+            headless: None,
+            bot: false,
+            overrides: Vec::new(),
+            replay: None,
+            record: None,
+            help: false,
+        }
+    }
+}
+
+fn parse_window_size(size: &str) -> Option<(f32, f32)> {
+    let (width, height) = size.split_once('x')?;
+    let width: f32 = width.parse().ok()?;
+    let height: f32 = height.parse().ok()?;
+    (width > 0.0 && height > 0.0).then_some((width, height))
+}
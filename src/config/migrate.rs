@@ -0,0 +1,30 @@
+use toml::{Table, Value};
+
+/// Version of the config schema written by this build. Bump it together with a new
+/// entry in [`MIGRATIONS`] whenever a key is renamed, moved or changes meaning.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// `MIGRATIONS[n - 1]` upgrades a table from version `n` to `n + 1`.
+/// Files written before versioning was introduced have no `version` and count as 1.
+const MIGRATIONS: [fn(&mut Table); CONFIG_VERSION as usize - 1] = [];
+
+/// Upgrades a parsed config file to [`CONFIG_VERSION`], returning the version it had.
+pub fn migrate(table: &mut Table) -> Result<u32, String> {
+    let version = match table.get("version") {
+        None => 1,
+        Some(Value::Integer(version)) if *version >= 1 => {
+            u32::try_from(*version).map_err(|_| format!("invalid config version `{version}`"))?
+        }
+        Some(other) => return Err(format!("invalid config version `{other}`")),
+    };
+    if version > CONFIG_VERSION {
+        return Err(format!(
+            "config version {version} is newer than the supported version {CONFIG_VERSION}"
+        ));
+    }
+    for migration in &MIGRATIONS[version as usize - 1..] {
+        migration(table);
+    }
+    table.insert("version".to_string(), Value::Integer(CONFIG_VERSION.into()));
+    Ok(version)
+}
@@ -0,0 +1,1161 @@
+use crate::input::Action;
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PresentMode, WindowMode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+mod migrate;
+mod parse;
+mod profile;
+mod reload;
+mod validate;
+
+pub use migrate::*;
+pub use parse::*;
+pub use profile::*;
+pub use reload::*;
+pub use validate::*;
+
+#[derive(Resource, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    /// Schema version of the file, see [`migrate`].
+    pub version: u32,
+    pub profile: ProfileConfig,
+    pub water: WaterConfig,
+    pub basic: BasicConfig,
+    pub world: WorldConfig,
+    pub map: MapConfig,
+    pub player: PlayerConfig,
+    pub enemy: EnemyConfig,
+    pub bullet: BulletConfig,
+    pub melee: MeleeConfig,
+    pub boss: BossConfig,
+    pub replay: ReplayConfig,
+    pub audio: AudioConfig,
+    pub camera: CameraConfig,
+    pub death: DeathConfig,
+    pub particles: ParticleConfig,
+    pub feedback: FeedbackConfig,
+    pub day_night: DayNightConfig,
+    pub lighting: LightingConfig,
+    pub controls: ControlsConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WaterConfig {
+    pub radial_scale: f32,
+    pub axial_scale: f32,
+    pub contrast: f32,
+    pub speed: f32,
+    pub intensity: f32,
+    pub color_offset: Vec3,
+    /// Color of the foam where water meets sand.
+    pub foam_color: Vec3,
+    /// How strongly the foam shows, 0 for none.
+    pub foam_intensity: f32,
+    /// Seconds a ripple takes to spread and fade.
+    pub ripple_lifetime: f32,
+    /// How far a ripple spreads, in pixels.
+    pub ripple_radius: f32,
+    /// Seconds between the ripples of something moving through water.
+    pub ripple_interval: f32,
+}
+
+impl Default for WaterConfig {
+    fn default() -> Self {
+        Self {
+            radial_scale: 10.0,
+            axial_scale: 4.0,
+            contrast: 8.0,
+            speed: 0.5,
+            intensity: 0.1,
+            color_offset: Vec3::new(-0.1, 0.3, 0.9),
+            foam_color: Vec3::new(0.9, 0.95, 1.0),
+            foam_intensity: 0.6,
+            ripple_lifetime: 0.8,
+            ripple_radius: 12.0,
+            ripple_interval: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BasicConfig {
+    pub tile_size: f32,
+    pub window_width: f32,
+    pub window_height: f32,
+    pub window_mode: WindowModeSetting,
+    pub vsync: bool,
+    pub debug: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub const ALL: [WindowModeSetting; 3] = [
+        WindowModeSetting::Windowed,
+        WindowModeSetting::Borderless,
+        WindowModeSetting::Fullscreen,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WindowModeSetting::Windowed => "windowed",
+            WindowModeSetting::Borderless => "borderless",
+            WindowModeSetting::Fullscreen => "fullscreen",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|m| *m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn window_mode(self) -> WindowMode {
+        match self {
+            WindowModeSetting::Windowed => WindowMode::Windowed,
+            WindowModeSetting::Borderless => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            }
+            WindowModeSetting::Fullscreen => WindowMode::Fullscreen(MonitorSelection::Current),
+        }
+    }
+}
+
+impl BasicConfig {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldConfig {
+    pub num_world_decorations: usize,
+    pub world_width: f32,
+    pub world_height: f32,
+    pub background_color: (u8, u8, u8),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MapConfig {
+    pub map_w: u32,
+    pub map_h: u32,
+    pub scale: f32,
+    pub grass_height: f32,
+    pub sand_height: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerConfig {
+    pub health: f32,
+    pub speed: f32,
+    pub damage_cooldown: f32,
+    pub collider_size: f32,
+    pub knockback_resistance: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnemyConfig {
+    pub health: f32,
+    pub speed: f32,
+    pub damage: f32,
+    pub damage_cooldown: f32,
+    pub follow_range: f32,
+    pub collider_size: f32,
+    pub knockback: f32,
+    pub knockback_resistance: f32,
+    pub spawn_dummy: bool,
+    pub spawn_waves: bool,
+    pub spawn_limit: usize,
+    /// Enemies spawned per wave, one wave every `spawn_interval` seconds.
+    pub spawn_rate_per_second: usize,
+    pub spawn_interval: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BulletConfig {
+    pub damage: f32,
+    pub speed: f32,
+    pub spawn_interval: f32,
+    pub lifetime: f32,
+    pub num_per_shot: usize,
+    pub knockback: f32,
+    pub explosion_radius: f32,
+    pub explosion_damage: f32,
+    pub explosion_falloff: f32,
+    pub explosion_knockback: f32,
+    pub modifiers: BulletModifierConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BulletModifierConfig {
+    pub pierce: usize,
+    pub ricochet: usize,
+    pub ricochet_range: f32,
+    pub homing_cone: f32,
+    pub homing_range: f32,
+    pub homing_turn_rate: f32,
+    pub split_count: usize,
+    pub split_spread: f32,
+    pub split_damage_ratio: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeleeConfig {
+    pub damage: f32,
+    pub range: f32,
+    pub arc: f32,
+    pub swing_duration: f32,
+    pub cooldown: f32,
+    pub knockback: f32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BossConfig {
+    pub enabled: bool,
+    pub health: f32,
+    pub speed: f32,
+    pub scale: f32,
+    pub first_spawn: f32,
+    pub spawn_interval: f32,
+    pub bullet_damage: f32,
+    pub bullet_speed: f32,
+    pub bullet_lifetime: f32,
+    pub phases: Vec<BossPhaseConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BossPhaseConfig {
+    /// The phase starts once health drops to this fraction of the maximum.
+    pub health_threshold: f32,
+    pub fire_interval: f32,
+    pub pattern: BossPattern,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum BossPattern {
+    /// Bullets evenly spaced in every direction.
+    RadialBurst { count: usize },
+    /// Rotating arms, advancing by `step` degrees every shot.
+    Spiral { arms: usize, step: f32 },
+    /// A fan of bullets aimed at the player, covering `spread` degrees.
+    AimedVolley { count: usize, spread: f32 },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplayConfig {
+    /// Record every run to `path`, overwriting the previous recording. Also turned on
+    /// by `--record <path>`.
+    pub record: bool,
+    pub path: String,
+}
+
+/// Volumes from 0 to 1. The volume of each bus is scaled by `master_volume`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+    pub ui_volume: f32,
+    /// Most effect and UI sounds playing at once, over all sounds.
+    pub max_voices: usize,
+    /// The sounds the game can play, by name, e.g.
+    ///
+    /// ```toml
+    /// [audio.sounds.shot]
+    /// path = "audio/shot.ogg"
+    /// volume = 0.5
+    /// max_voices = 4
+    /// ```
+    ///
+    /// The game plays `shot`, `hit`, `enemy_death`, `player_hurt` and `button`, and
+    /// the sounds `music` names. None are registered by default.
+    pub sounds: BTreeMap<String, SoundConfig>,
+    pub music: MusicConfig,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioBus {
+    #[default]
+    Sfx,
+    Ui,
+    Music,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundConfig {
+    /// Path of the file in `assets/`.
+    pub path: String,
+    pub bus: AudioBus,
+    /// Scales the volume of the bus.
+    pub volume: f32,
+    /// Most instances of the sound playing at once. Further ones are dropped.
+    pub max_voices: usize,
+}
+
+/// The sound looped in each state, by name in `sounds`. Empty for silence.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MusicConfig {
+    pub main_menu: String,
+    pub running: String,
+    pub paused: String,
+    pub game_over: String,
+}
+
+/// How the camera follows the player and zooms. The view is kept inside the map.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraConfig {
+    /// Limits of the mouse-wheel zoom, as the scale of the camera projection.
+    /// Smaller values zoom in.
+    pub zoom_min: f32,
+    pub zoom_max: f32,
+    /// How quickly the camera catches up with the player, per second.
+    pub follow_speed: f32,
+    /// How quickly the zoom catches up with the mouse wheel, per second.
+    pub zoom_speed: f32,
+    /// How far the player may move before the camera follows, in pixels.
+    pub deadzone: f32,
+    /// How far ahead of the player the camera looks, in seconds of its velocity.
+    pub look_ahead: f32,
+    /// How far the camera leans towards the cursor, in pixels.
+    pub cursor_offset: f32,
+}
+
+/// What is left of dead enemies. Without a `<name>_death` clip in the sprite manifest,
+/// an enemy shrinks and fades for `fade_duration` seconds instead.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeathConfig {
+    pub fade_duration: f32,
+    /// Seconds a corpse stays on the ground, 0 for no corpses.
+    pub corpse_lifetime: f32,
+    /// The oldest corpses are removed beyond this many.
+    pub max_corpses: usize,
+}
+
+/// Cosmetic particles for shots, hits, explosions and deaths.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleConfig {
+    pub enabled: bool,
+    /// Most particles alive at once, over all effects. Further ones are dropped.
+    pub max_particles: usize,
+    /// The effects the game can emit, by name.
+    pub effects: BTreeMap<String, ParticleEffectConfig>,
+}
+
+/// How the particles of an effect are emitted, move and change over their life.
+/// Ranges are `[min, max]`, each particle picks a random value in between.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParticleEffectConfig {
+    /// A sprite of the sprite manifest, or a clip whose frames play over the particle's life.
+    pub sprite: String,
+    /// Particles emitted at once by a burst.
+    pub count: usize,
+    /// Particles per second of a continuous emitter.
+    pub rate: f32,
+    pub lifetime: [f32; 2],
+    pub speed: [f32; 2],
+    /// Spread around the emitting direction, in degrees. 360 for all around.
+    pub cone: f32,
+    /// How quickly particles slow down, as a fraction of their speed per second.
+    pub drag: f32,
+    /// Colors as sRGB with alpha, blended from start to end over the particle's life.
+    pub color_start: [f32; 4],
+    pub color_end: [f32; 4],
+    pub scale_start: f32,
+    pub scale_end: f32,
+}
+
+/// Screen shake and hit-stop. Hits add trauma to the camera, which decays over time;
+/// the camera shakes with the square of it.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedbackConfig {
+    /// Turns screen shake and hit-stop off.
+    pub reduce_motion: bool,
+    /// How far the camera moves at full trauma, in pixels.
+    pub shake_offset: f32,
+    /// How far the camera turns at full trauma, in degrees.
+    pub shake_angle: f32,
+    /// How quickly the shake changes direction.
+    pub shake_frequency: f32,
+    /// Trauma lost per second.
+    pub trauma_decay: f32,
+    pub player_hit_trauma: f32,
+    pub explosion_trauma: f32,
+    pub boss_attack_trauma: f32,
+    /// Damage from which a hit slows the game down for a moment, 0 for never.
+    pub hit_stop_damage: f32,
+    /// Seconds of hit-stop, in real time.
+    pub hit_stop_duration: f32,
+    /// How fast game time passes during hit-stop, 0 to freeze it.
+    pub hit_stop_speed: f32,
+    /// Seconds after a hit-stop, in real time, before big hits can start another.
+    pub hit_stop_cooldown: f32,
+}
+
+/// The day and night cycle. Waves spawned at night are larger and faster.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayNightConfig {
+    /// Without the cycle, the time of day stays at `start_time`.
+    pub enabled: bool,
+    /// Seconds of a whole day and night.
+    pub day_length: f32,
+    /// The time of day a run starts at, as a fraction of the day: 0 is midnight, 0.5 noon.
+    pub start_time: f32,
+    /// Scales the size of enemy waves at midnight, and less so earlier and later at night.
+    pub night_spawn_multiplier: f32,
+    /// Scales the speed of enemies at midnight, and less so earlier and later at night.
+    pub night_speed_multiplier: f32,
+}
+
+/// A light around the player, or a short flash when `duration` is above 0.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightConfig {
+    /// sRGB color.
+    pub color: [f32; 3],
+    /// Distance in pixels at which the light is gone.
+    pub radius: f32,
+    pub intensity: f32,
+    /// Seconds a flash takes to fade.
+    pub duration: f32,
+}
+
+/// Darkens the scene with an ambient color that follows the time of day, lit up by
+/// point lights. Without it, the scene is drawn unlit.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LightingConfig {
+    pub enabled: bool,
+    /// sRGB ambient color at noon.
+    pub day_ambient: [f32; 3],
+    /// sRGB ambient color at midnight.
+    pub night_ambient: [f32; 3],
+    pub player_light: LightConfig,
+    pub muzzle_flash_light: LightConfig,
+    pub explosion_light: LightConfig,
+}
+
+/// A key or button that triggers an action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputBinding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    /// A button of any connected gamepad.
+    Gamepad(GamepadButton),
+}
+
+/// The bindings of each action. An action is triggered by any of its bindings.
+/// A gamepad's left stick always moves and its right stick always aims.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlsConfig {
+    pub move_up: Vec<InputBinding>,
+    pub move_down: Vec<InputBinding>,
+    pub move_left: Vec<InputBinding>,
+    pub move_right: Vec<InputBinding>,
+    pub fire: Vec<InputBinding>,
+    pub melee: Vec<InputBinding>,
+    pub pause: Vec<InputBinding>,
+    pub reload_config: Vec<InputBinding>,
+    /// Heals the player while `basic.debug` is on.
+    pub heal: Vec<InputBinding>,
+    /// Shows the effective config while `basic.debug` is on.
+    pub config_view: Vec<InputBinding>,
+    /// Stick deflections below this, from 0 to 1, are ignored.
+    pub stick_dead_zone: f32,
+}
+
+impl InputBinding {
+    /// A short name for menus, e.g. `W` or `Mouse Left`.
+    pub fn label(self) -> String {
+        match self {
+            InputBinding::Key(key) => {
+                let name = format!("{key:?}");
+                let name = name
+                    .strip_prefix("Key")
+                    .or_else(|| name.strip_prefix("Digit"))
+                    .unwrap_or(&name);
+                name.to_string()
+            }
+            InputBinding::Mouse(MouseButton::Other(button)) => format!("Mouse {button}"),
+            InputBinding::Mouse(button) => format!("Mouse {button:?}"),
+            InputBinding::Gamepad(GamepadButton::Other(button)) => format!("Pad {button}"),
+            InputBinding::Gamepad(button) => format!("Pad {button:?}"),
+        }
+    }
+}
+
+impl ControlsConfig {
+    /// The bindings of `action`.
+    pub fn bindings(&self, action: Action) -> &[InputBinding] {
+        match action {
+            Action::MoveUp => &self.move_up,
+            Action::MoveDown => &self.move_down,
+            Action::MoveLeft => &self.move_left,
+            Action::MoveRight => &self.move_right,
+            Action::Fire => &self.fire,
+            Action::Melee => &self.melee,
+            Action::Pause => &self.pause,
+            Action::ReloadConfig => &self.reload_config,
+            Action::Heal => &self.heal,
+            Action::ConfigView => &self.config_view,
+        }
+    }
+}
+
+/// Where the config comes from: a file, plus overrides given on the command line.
+#[derive(Resource, Debug, Clone)]
+pub struct ConfigSource {
+    pub path: PathBuf,
+    /// `key=value` pairs, keys being dotted paths like `enemy.speed`.
+    pub overrides: Vec<(String, String)>,
+}
+
+#[derive(Default)]
+pub struct ConfigPlugin;
+
+/// A config as loaded, with what went wrong and which layers it is made of.
+#[derive(Debug)]
+pub struct LoadedConfig {
+    pub config: GameConfig,
+    pub report: ConfigReport,
+    pub layers: ConfigLayers,
+}
+
+impl ConfigSource {
+    /// Loads the config, creating the file if it does not exist.
+    /// Invalid values fall back to their defaults and are listed in the report.
+    pub fn load(&self) -> Result<LoadedConfig, String> {
+        let (config, report) = get_config(&self.path);
+        self.finish(config, report)
+    }
+
+    /// Reads the file again. Unlike [`ConfigSource::load`], a missing file or any
+    /// error fails the whole reload, so the running game keeps its previous config.
+    pub fn reload(&self) -> Result<LoadedConfig, String> {
+        let contents = fs::read_to_string(&self.path)
+            .map_err(|err| format!("{}: {}", self.path.display(), err))?;
+        let (config, report) =
+            parse_config(&contents).map_err(|err| format!("{}: {}", self.path.display(), err))?;
+        let loaded = self.finish(config, report)?;
+        if loaded.report.has_errors() {
+            return Err(loaded.report.error_lines());
+        }
+        Ok(loaded)
+    }
+
+    /// Layers the presets, profile and overrides over the base file and validates
+    /// the result, resetting invalid values.
+    fn finish(
+        &self,
+        mut config: GameConfig,
+        mut report: ConfigReport,
+    ) -> Result<LoadedConfig, String> {
+        let layers = self.apply_layers(&mut config, &mut report)?;
+        let issues = config.validate();
+        for error in &issues.errors {
+            reset_to_default(&mut config, &error.path);
+        }
+        report.extend(issues);
+        Ok(LoadedConfig {
+            config,
+            report,
+            layers,
+        })
+    }
+}
+
+pub fn get_config(path: &Path) -> (GameConfig, ConfigReport) {
+    match read_or_create_config(path) {
+        Ok(loaded) => {
+            log::info!("Config loaded successfully");
+            loaded
+        }
+        Err(err) => {
+            log::error!("Error loading config: {}", err);
+            log::info!("Using default config");
+            let mut report = ConfigReport::default();
+            report.error("", format!("{}: {}", path.display(), err));
+            (GameConfig::default(), report)
+        }
+    }
+}
+
+fn read_or_create_config(path: &Path) -> Result<(GameConfig, ConfigReport), Box<dyn Error>> {
+    if path.exists() {
+        let contents = fs::read_to_string(path)?;
+        let loaded = parse_config(&contents)?;
+        log::info!("Config loaded from file");
+        Ok(loaded)
+    } else {
+        log::info!("Config file not found, creating default config");
+        let default_config = GameConfig::default();
+        let toml_string = toml::to_string(&default_config)?;
+
+        let mut file = File::create(path)?;
+        file.write_all(toml_string.as_bytes())?;
+
+        Ok((default_config, ConfigReport::default()))
+    }
+}
+
+/// Sets the value at the dotted path `key`, e.g. `enemy.speed`, parsing `value` as TOML.
+/// Words that are not valid TOML are taken as strings.
+pub fn apply_override(config: &mut GameConfig, key: &str, value: &str) -> Result<(), String> {
+    let mut root = toml::Value::try_from(&*config).map_err(|err| err.to_string())?;
+    let parts: Vec<&str> = key.split('.').collect();
+    let target = parse::value_at_mut(&mut root, &parts)
+        .ok_or_else(|| format!("unknown config key `{key}`"))?;
+
+    let parsed = format!("value = {value}")
+        .parse::<toml::Table>()
+        .ok()
+        .and_then(|mut table| table.remove("value"));
+    let is_float = target.is_float();
+    *target = match parsed {
+        // `60` is as good as `60.0` for a float
+        Some(toml::Value::Integer(integer)) if is_float => toml::Value::Float(integer as f64),
+        Some(parsed) => parsed,
+        None => toml::Value::String(value.to_string()),
+    };
+
+    *config = root
+        .try_into()
+        .map_err(|err| format!("invalid value for `{key}`: {err}"))?;
+    Ok(())
+}
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ConfigSource>()
+            .init_resource::<ConfigLayers>();
+        reload::build(app);
+    }
+}
+
+impl Default for ConfigSource {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("config.toml"),
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            profile: ProfileConfig::default(),
+            water: WaterConfig::default(),
+            basic: BasicConfig::default(),
+            world: WorldConfig::default(),
+            map: MapConfig::default(),
+            player: PlayerConfig::default(),
+            enemy: EnemyConfig::default(),
+            bullet: BulletConfig::default(),
+            melee: MeleeConfig::default(),
+            boss: BossConfig::default(),
+            replay: ReplayConfig::default(),
+            audio: AudioConfig::default(),
+            camera: CameraConfig::default(),
+            death: DeathConfig::default(),
+            particles: ParticleConfig::default(),
+            feedback: FeedbackConfig::default(),
+            day_night: DayNightConfig::default(),
+            lighting: LightingConfig::default(),
+            controls: ControlsConfig::default(),
+        }
+    }
+}
+
+impl Default for BasicConfig {
+    fn default() -> Self {
+        Self {
+            tile_size: 16.0,
+            window_width: 1280.0,
+            window_height: 720.0,
+            window_mode: WindowModeSetting::Windowed,
+            vsync: false,
+            debug: false,
+        }
+    }
+}
+
+impl Default for WorldConfig {
+    fn default() -> Self {
+        Self {
+            num_world_decorations: 500,
+            world_width: 3000.0,
+            world_height: 2500.0,
+            background_color: (197, 204, 184),
+        }
+    }
+}
+
+impl Default for MapConfig {
+    fn default() -> Self {
+        Self {
+            map_w: 128,
+            map_h: 96,
+            scale: 30.0,
+            grass_height: 1.0,
+            sand_height: 0.2,
+        }
+    }
+}
+
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            health: 100.0,
+            speed: 80.0,
+            damage_cooldown: 0.3,
+            collider_size: 5.0,
+            knockback_resistance: 0.0,
+        }
+    }
+}
+
+impl Default for EnemyConfig {
+    fn default() -> Self {
+        Self {
+            health: 100.0,
+            speed: 40.0,
+            damage: 20.0,
+            damage_cooldown: 0.15,
+            follow_range: 1000.0,
+            collider_size: 6.0,
+            knockback: 150.0,
+            knockback_resistance: 0.0,
+            spawn_dummy: false,
+            spawn_waves: true,
+            spawn_limit: 20000,
+            spawn_rate_per_second: 500,
+            spawn_interval: 1.0,
+        }
+    }
+}
+
+impl Default for BulletConfig {
+    fn default() -> Self {
+        Self {
+            damage: 20.0,
+            speed: 600.0,
+            spawn_interval: 0.1,
+            lifetime: 0.5,
+            num_per_shot: 10,
+            knockback: 60.0,
+            explosion_radius: 0.0,
+            explosion_damage: 40.0,
+            explosion_falloff: 0.5,
+            explosion_knockback: 200.0,
+            modifiers: BulletModifierConfig::default(),
+        }
+    }
+}
+
+impl Default for BulletModifierConfig {
+    fn default() -> Self {
+        Self {
+            pierce: 0,
+            ricochet: 0,
+            ricochet_range: 120.0,
+            homing_cone: 0.0,
+            homing_range: 100.0,
+            homing_turn_rate: 360.0,
+            split_count: 0,
+            split_spread: 60.0,
+            split_damage_ratio: 0.5,
+        }
+    }
+}
+
+impl Default for MeleeConfig {
+    fn default() -> Self {
+        Self {
+            damage: 50.0,
+            range: 24.0,
+            arc: 120.0,
+            swing_duration: 0.15,
+            cooldown: 0.5,
+            knockback: 120.0,
+        }
+    }
+}
+
+impl Default for BossConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            health: 5000.0,
+            speed: 25.0,
+            scale: 3.0,
+            first_spawn: 60.0,
+            spawn_interval: 120.0,
+            bullet_damage: 10.0,
+            bullet_speed: 120.0,
+            bullet_lifetime: 4.0,
+            phases: vec![
+                BossPhaseConfig {
+                    health_threshold: 1.0,
+                    fire_interval: 1.5,
+                    pattern: BossPattern::AimedVolley {
+                        count: 5,
+                        spread: 40.0,
+                    },
+                },
+                BossPhaseConfig {
+                    health_threshold: 0.66,
+                    fire_interval: 1.0,
+                    pattern: BossPattern::RadialBurst { count: 24 },
+                },
+                BossPhaseConfig {
+                    health_threshold: 0.33,
+                    fire_interval: 0.1,
+                    pattern: BossPattern::Spiral {
+                        arms: 4,
+                        step: 12.0,
+                    },
+                },
+            ],
+        }
+    }
+}
+
+impl Default for AudioConfig {
+    fn default() -> Self {
+        // no sound files ship with the game, they are registered in the config file
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.6,
+            sfx_volume: 0.8,
+            ui_volume: 0.8,
+            max_voices: 32,
+            sounds: BTreeMap::new(),
+            music: MusicConfig::default(),
+        }
+    }
+}
+
+impl Default for SoundConfig {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            bus: AudioBus::Sfx,
+            volume: 1.0,
+            max_voices: 4,
+        }
+    }
+}
+
+impl AudioConfig {
+    /// The volume of `bus`, master volume included.
+    pub fn bus_volume(&self, bus: AudioBus) -> f32 {
+        let volume = match bus {
+            AudioBus::Sfx => self.sfx_volume,
+            AudioBus::Ui => self.ui_volume,
+            AudioBus::Music => self.music_volume,
+        };
+        self.master_volume * volume
+    }
+
+    /// The volume `sound` plays at.
+    pub fn sound_volume(&self, sound: &SoundConfig) -> f32 {
+        self.bus_volume(sound.bus) * sound.volume
+    }
+}
+
+impl Default for CameraConfig {
+    fn default() -> Self {
+        Self {
+            zoom_min: 0.1,
+            zoom_max: 1.0,
+            follow_speed: 0.6,
+            zoom_speed: 0.6,
+            deadzone: 8.0,
+            look_ahead: 0.25,
+            cursor_offset: 50.0,
+        }
+    }
+}
+
+impl Default for DeathConfig {
+    fn default() -> Self {
+        Self {
+            fade_duration: 0.4,
+            corpse_lifetime: 8.0,
+            max_corpses: 40,
+        }
+    }
+}
+
+impl Default for ParticleConfig {
+    fn default() -> Self {
+        let effect =
+            |count, lifetime, speed, cone, color_start, color_end, scale| ParticleEffectConfig {
+                count,
+                lifetime,
+                speed,
+                cone,
+                color_start,
+                color_end,
+                scale_start: scale,
+                ..default()
+            };
+        let effects = [
+            (
+                "muzzle_flash",
+                ParticleEffectConfig {
+                    drag: 8.0,
+                    ..effect(
+                        4,
+                        [0.05, 0.12],
+                        [40.0, 120.0],
+                        30.0,
+                        [1.0, 0.95, 0.6, 1.0],
+                        [1.0, 0.5, 0.1, 0.0],
+                        1.2,
+                    )
+                },
+            ),
+            (
+                "bullet_hit",
+                effect(
+                    6,
+                    [0.15, 0.35],
+                    [30.0, 90.0],
+                    120.0,
+                    [1.0, 1.0, 1.0, 1.0],
+                    [0.9, 0.1, 0.1, 0.0],
+                    0.8,
+                ),
+            ),
+            (
+                "explosion",
+                effect(
+                    16,
+                    [0.3, 0.6],
+                    [40.0, 160.0],
+                    360.0,
+                    [1.0, 0.7, 0.2, 1.0],
+                    [0.3, 0.3, 0.3, 0.0],
+                    1.6,
+                ),
+            ),
+            (
+                "enemy_death",
+                effect(
+                    10,
+                    [0.3, 0.5],
+                    [20.0, 70.0],
+                    360.0,
+                    [0.7, 0.1, 0.1, 1.0],
+                    [0.4, 0.05, 0.05, 0.0],
+                    1.0,
+                ),
+            ),
+            (
+                "boss_bullet_trail",
+                ParticleEffectConfig {
+                    rate: 30.0,
+                    ..effect(
+                        0,
+                        [0.15, 0.25],
+                        [0.0, 10.0],
+                        360.0,
+                        [1.0, 0.3, 0.3, 0.8],
+                        [1.0, 0.3, 0.3, 0.0],
+                        1.0,
+                    )
+                },
+            ),
+        ];
+        Self {
+            enabled: true,
+            max_particles: 600,
+            effects: effects
+                .into_iter()
+                .map(|(name, effect)| (name.to_string(), effect))
+                .collect(),
+        }
+    }
+}
+
+impl Default for ParticleEffectConfig {
+    fn default() -> Self {
+        Self {
+            sprite: "bullet".to_string(),
+            count: 8,
+            rate: 0.0,
+            lifetime: [0.2, 0.4],
+            speed: [20.0, 60.0],
+            cone: 360.0,
+            drag: 3.0,
+            color_start: [1.0, 1.0, 1.0, 1.0],
+            color_end: [1.0, 1.0, 1.0, 0.0],
+            scale_start: 1.0,
+            scale_end: 0.2,
+        }
+    }
+}
+
+impl Default for FeedbackConfig {
+    fn default() -> Self {
+        Self {
+            reduce_motion: false,
+            shake_offset: 6.0,
+            shake_angle: 2.0,
+            shake_frequency: 20.0,
+            trauma_decay: 1.5,
+            player_hit_trauma: 0.4,
+            explosion_trauma: 0.5,
+            boss_attack_trauma: 0.15,
+            hit_stop_damage: 40.0,
+            hit_stop_duration: 0.08,
+            hit_stop_speed: 0.05,
+            hit_stop_cooldown: 0.5,
+        }
+    }
+}
+
+impl Default for DayNightConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            day_length: 240.0,
+            start_time: 0.35,
+            night_spawn_multiplier: 1.5,
+            night_speed_multiplier: 1.25,
+        }
+    }
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            color: [1.0, 1.0, 1.0],
+            radius: 80.0,
+            intensity: 1.0,
+            duration: 0.0,
+        }
+    }
+}
+
+impl Default for LightingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            day_ambient: [1.0, 1.0, 1.0],
+            night_ambient: [0.15, 0.17, 0.35],
+            player_light: LightConfig {
+                color: [1.0, 0.9, 0.7],
+                radius: 90.0,
+                intensity: 0.8,
+                duration: 0.0,
+            },
+            muzzle_flash_light: LightConfig {
+                color: [1.0, 0.8, 0.4],
+                radius: 50.0,
+                intensity: 0.9,
+                duration: 0.06,
+            },
+            explosion_light: LightConfig {
+                color: [1.0, 0.6, 0.2],
+                radius: 140.0,
+                intensity: 1.2,
+                duration: 0.3,
+            },
+        }
+    }
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        use InputBinding::*;
+        Self {
+            move_up: vec![
+                Key(KeyCode::KeyW),
+                Key(KeyCode::ArrowUp),
+                Gamepad(GamepadButton::DPadUp),
+            ],
+            move_down: vec![
+                Key(KeyCode::KeyS),
+                Key(KeyCode::ArrowDown),
+                Gamepad(GamepadButton::DPadDown),
+            ],
+            move_left: vec![
+                Key(KeyCode::KeyA),
+                Key(KeyCode::ArrowLeft),
+                Gamepad(GamepadButton::DPadLeft),
+            ],
+            move_right: vec![
+                Key(KeyCode::KeyD),
+                Key(KeyCode::ArrowRight),
+                Gamepad(GamepadButton::DPadRight),
+            ],
+            fire: vec![
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButton::RightTrigger2),
+            ],
+            melee: vec![
+                Key(KeyCode::KeyF),
+                Mouse(MouseButton::Right),
+                Gamepad(GamepadButton::RightTrigger),
+            ],
+            pause: vec![Key(KeyCode::Escape), Gamepad(GamepadButton::Start)],
+            reload_config: vec![Key(KeyCode::KeyR)],
+            heal: vec![Key(KeyCode::KeyH)],
+            config_view: vec![Key(KeyCode::F1)],
+            stick_dead_zone: 0.2,
+        }
+    }
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            record: false,
+            path: "last_run.replay".to_string(),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use crate::config::*;
+use toml::{Table, Value};
+
+/// Parses the contents of a config file, migrating it from older versions.
+///
+/// Missing values take their defaults, and entries of tables keyed by name are added
+/// to the default ones. A value that does not fit its field is reported as an error
+/// and replaced by its default, and unknown keys are reported as warnings, so a single
+/// typo does not throw away the rest of the file. Only a file that is not valid TOML,
+/// or is newer than this build, fails as a whole.
+pub fn parse_config(contents: &str) -> Result<(GameConfig, ConfigReport), String> {
+    let mut table: Table = contents
+        .parse()
+        .map_err(|err: toml::de::Error| err.to_string())?;
+    let mut report = ConfigReport::default();
+    let version = migrate(&mut table)?;
+    if version < CONFIG_VERSION {
+        report.warning(
+            "version",
+            format!("the file is for version {version} and was migrated to {CONFIG_VERSION}"),
+        );
+    }
+
+    // overlaid rather than deserialized, so that a table like `audio.sounds` adds to
+    // the default entries instead of replacing them
+    let mut config = GameConfig::default();
+    apply_overlay(&mut config, &keep_valid_values(&table, "", &mut report))?;
+    if let Ok(Value::Table(known)) = Value::try_from(&config) {
+        report_unknown_keys("", &table, &known, "", &mut report);
+    }
+    Ok((config, report))
+}
+
+/// The values of a (partial) config file that fit their field. The others are
+/// reported as errors, with `origin` naming where they came from if not the base file.
+pub(super) fn keep_valid_values(table: &Table, origin: &str, report: &mut ConfigReport) -> Table {
+    if Value::Table(table.clone()).try_into::<GameConfig>().is_ok() {
+        return table.clone();
+    }
+    // find the offending values by trying each one on its own
+    let mut valid = Table::new();
+    for (path, value) in leaves(table) {
+        let mut single = Table::new();
+        insert_at(&mut single, &path, value.clone());
+        match Value::Table(single).try_into::<GameConfig>() {
+            Ok(_) => insert_at(&mut valid, &path, value.clone()),
+            Err(err) if origin.is_empty() => report.error(path.join("."), err.message()),
+            Err(err) => report.error(path.join("."), format!("{} (in {origin})", err.message())),
+        }
+    }
+    valid
+}
+
+/// Replaces the value at the dotted path `key` with its default.
+/// Array elements the default does not have, like a fifth boss phase, reset the whole array.
+pub fn reset_to_default(config: &mut GameConfig, key: &str) {
+    let (Ok(mut root), Ok(defaults)) = (
+        Value::try_from(&*config),
+        Value::try_from(GameConfig::default()),
+    ) else {
+        return;
+    };
+    let parts: Vec<&str> = key.split('.').collect();
+    for len in (1..=parts.len()).rev() {
+        let Some(default) = value_at(&defaults, &parts[..len]) else {
+            continue;
+        };
+        if let Some(target) = value_at_mut(&mut root, &parts[..len]) {
+            *target = default.clone();
+        }
+        break;
+    }
+    if let Ok(reset) = root.try_into() {
+        *config = reset;
+    }
+}
+
+pub(super) fn value_at<'a>(root: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(root, |value, part| match value {
+        Value::Table(table) => table.get(*part),
+        Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+pub(super) fn value_at_mut<'a>(root: &'a mut Value, path: &[&str]) -> Option<&'a mut Value> {
+    path.iter().try_fold(root, |value, part| match value {
+        Value::Table(table) => table.get_mut(*part),
+        Value::Array(items) => items.get_mut(part.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Every value that is not a table, with its path. Arrays count as a single value.
+pub(super) fn leaves(table: &Table) -> Vec<(Vec<String>, &Value)> {
+    let mut found = Vec::new();
+    for (key, value) in table {
+        match value {
+            Value::Table(inner) => {
+                for (mut path, value) in leaves(inner) {
+                    path.insert(0, key.clone());
+                    found.push((path, value));
+                }
+            }
+            _ => found.push((vec![key.clone()], value)),
+        }
+    }
+    found
+}
+
+pub(super) fn insert_at(table: &mut Table, path: &[String], value: Value) {
+    let Some((last, parents)) = path.split_last() else {
+        return;
+    };
+    let mut target = table;
+    for part in parents {
+        let entry = target
+            .entry(part.clone())
+            .or_insert_with(|| Value::Table(Table::new()));
+        let Value::Table(inner) = entry else {
+            return;
+        };
+        target = inner;
+    }
+    target.insert(last.clone(), value);
+}
+
+pub(super) fn report_unknown_keys(
+    prefix: &str,
+    file: &Table,
+    known: &Table,
+    origin: &str,
+    report: &mut ConfigReport,
+) {
+    for (key, value) in file {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{prefix}.{key}")
+        };
+        match (value, known.get(key)) {
+            (_, None) if origin.is_empty() => report.warning(path, "unknown key, ignored"),
+            (_, None) => report.warning(path, format!("unknown key, ignored (in {origin})")),
+            (Value::Table(file), Some(Value::Table(known))) => {
+                report_unknown_keys(&path, file, known, origin, report)
+            }
+            _ => {}
+        }
+    }
+}
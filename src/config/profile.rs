@@ -0,0 +1,302 @@
+use crate::config::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+/// Which difficulty preset and user profile are layered over the base file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileConfig {
+    pub difficulty: Difficulty,
+    /// A file in `profiles/` next to the config file, without `.toml`. Empty for none.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+/// Values set by one layer of the config, e.g. the difficulty preset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLayer {
+    pub name: String,
+    /// Dotted paths of the values the layer sets.
+    pub keys: Vec<String>,
+}
+
+/// The layers applied over the base file, in order.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct ConfigLayers(pub Vec<ConfigLayer>);
+
+// presets scale the values of the base file, so that editing it still has an effect
+const EASY: &str = r#"
+[player]
+health = 1.5
+damage_cooldown = 2.5
+
+[enemy]
+health = 0.7
+speed = 0.8
+damage = 0.6
+spawn_rate_per_second = 0.5
+
+[bullet]
+damage = 1.25
+"#;
+
+const HARD: &str = r#"
+[player]
+health = 0.8
+
+[enemy]
+health = 1.3
+speed = 1.2
+damage = 1.25
+spawn_rate_per_second = 1.4
+spawn_interval = 0.8
+
+[bullet]
+damage = 0.9
+"#;
+
+const NIGHTMARE: &str = r#"
+[player]
+health = 0.6
+
+[enemy]
+health = 1.8
+speed = 1.5
+damage = 1.75
+follow_range = 2.0
+spawn_rate_per_second = 2.0
+spawn_interval = 0.6
+
+[bullet]
+damage = 0.75
+spawn_interval = 1.2
+"#;
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+        }
+    }
+
+    /// The next preset, wrapping around after the hardest.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|d| *d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    /// The factors the preset scales values of the base file by. `Normal` plays the
+    /// base file as is.
+    pub fn factors(self) -> Table {
+        let factors = match self {
+            Difficulty::Easy => EASY,
+            Difficulty::Normal => "",
+            Difficulty::Hard => HARD,
+            Difficulty::Nightmare => NIGHTMARE,
+        };
+        factors.parse().expect("built-in presets are valid TOML")
+    }
+}
+
+impl ConfigLayers {
+    /// The last layer that set `key`, or `None` if it comes from the base file.
+    pub fn origin(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .rev()
+            .find(|layer| {
+                layer.keys.iter().any(|layered| {
+                    // a layer setting `boss.phases` also sets `boss.phases.0.fire_interval`
+                    key.strip_prefix(layered.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+                })
+            })
+            .map(|layer| layer.name.as_str())
+    }
+
+    fn push(&mut self, name: impl Into<String>, keys: Vec<String>) {
+        if !keys.is_empty() {
+            self.0.push(ConfigLayer {
+                name: name.into(),
+                keys,
+            });
+        }
+    }
+}
+
+impl ConfigSource {
+    /// Where user profiles are looked up: `profiles/` next to the config file.
+    pub fn profile_dir(&self) -> PathBuf {
+        self.path.parent().unwrap_or(Path::new("")).join("profiles")
+    }
+
+    /// The names of all profiles in [`ConfigSource::profile_dir`], sorted.
+    pub fn profiles(&self) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(self.profile_dir()) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Writes a single value to the config file, keeping the rest of it as it is.
+    pub fn save_value(&self, key: &str, value: impl Into<Value>) -> Result<(), String> {
+        self.save_values([(key, value.into())])
+    }
+
+    /// Writes several values to the config file at once, keeping the rest of it as it is.
+    pub fn save_values<'a>(
+        &self,
+        values: impl IntoIterator<Item = (&'a str, Value)>,
+    ) -> Result<(), String> {
+        let mut table = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents
+                .parse::<Table>()
+                .map_err(|err| format!("{}: {}", self.path.display(), err))?,
+            Err(_) => Table::new(),
+        };
+        for (key, value) in values {
+            let path: Vec<String> = key.split('.').map(str::to_string).collect();
+            insert_at(&mut table, &path, value);
+        }
+        let contents = toml::to_string(&table).map_err(|err| err.to_string())?;
+        fs::write(&self.path, contents).map_err(|err| format!("{}: {}", self.path.display(), err))
+    }
+
+    /// Applies the difficulty preset, then the profile, then the command-line overrides.
+    pub(super) fn apply_layers(
+        &self,
+        config: &mut GameConfig,
+        report: &mut ConfigReport,
+    ) -> Result<ConfigLayers, String> {
+        // the overrides may pick the difficulty and profile
+        self.apply_overrides(config)?;
+
+        let mut layers = ConfigLayers::default();
+        let difficulty = config.profile.difficulty;
+        let keys = apply_factors(config, &difficulty.factors())?;
+        layers.push(difficulty.name(), keys);
+
+        let name = config.profile.name.clone();
+        if !name.is_empty() {
+            let origin = format!("profile `{name}`");
+            match self.read_profile(&name) {
+                Ok(mut table) => {
+                    // `profile` picks the layers, a profile can not change them
+                    table.remove("profile");
+                    let valid = keep_valid_values(&table, &origin, report);
+                    if let Ok(Value::Table(known)) = Value::try_from(&*config) {
+                        report_unknown_keys("", &table, &known, &origin, report);
+                    }
+                    let keys = apply_overlay(config, &valid)?;
+                    layers.push(origin, keys);
+                }
+                Err(err) => report.error("profile.name", err),
+            }
+        }
+
+        // and win over every layer
+        let keys = self.apply_overrides(config)?;
+        layers.push("command line", keys);
+        Ok(layers)
+    }
+
+    fn apply_overrides(&self, config: &mut GameConfig) -> Result<Vec<String>, String> {
+        for (key, value) in &self.overrides {
+            apply_override(config, key, value)?;
+        }
+        Ok(self.overrides.iter().map(|(key, _)| key.clone()).collect())
+    }
+
+    fn read_profile(&self, name: &str) -> Result<Table, String> {
+        let path = self.profile_dir().join(format!("{name}.toml"));
+        let contents =
+            fs::read_to_string(&path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut table: Table = contents
+            .parse()
+            .map_err(|err: toml::de::Error| format!("{}: {}", path.display(), err))?;
+        migrate(&mut table).map_err(|err| format!("{}: {}", path.display(), err))?;
+        // a profile only sets what it lists
+        table.remove("version");
+        Ok(table)
+    }
+}
+
+/// Multiplies every number of `config` that `factors` has a factor for, returning
+/// their dotted paths. Integers are rounded.
+pub fn apply_factors(config: &mut GameConfig, factors: &Table) -> Result<Vec<String>, String> {
+    let mut root = Value::try_from(&*config).map_err(|err| err.to_string())?;
+    let mut keys = Vec::new();
+    for (path, factor) in leaves(factors) {
+        let key = path.join(".");
+        let parts: Vec<&str> = path.iter().map(String::as_str).collect();
+        let factor = match factor {
+            Value::Float(factor) => *factor,
+            Value::Integer(factor) => *factor as f64,
+            _ => return Err(format!("the factor for `{key}` is not a number")),
+        };
+        match value_at_mut(&mut root, &parts) {
+            Some(Value::Float(value)) => *value *= factor,
+            Some(Value::Integer(value)) => *value = (*value as f64 * factor).round() as i64,
+            _ => return Err(format!("`{key}` is not a number")),
+        }
+        keys.push(key);
+    }
+    *config = root.try_into().map_err(|err| err.to_string())?;
+    Ok(keys)
+}
+
+/// Sets every value in `overlay` on `config`, returning their dotted paths.
+pub fn apply_overlay(config: &mut GameConfig, overlay: &Table) -> Result<Vec<String>, String> {
+    let mut root = Value::try_from(&*config).map_err(|err| err.to_string())?;
+    if let Value::Table(table) = &mut root {
+        merge(table, overlay);
+    }
+    *config = root.try_into().map_err(|err| err.to_string())?;
+    Ok(leaves(overlay)
+        .into_iter()
+        .map(|(path, _)| path.join("."))
+        .collect())
+}
+
+fn merge(target: &mut Table, overlay: &Table) {
+    for (key, value) in overlay {
+        match (target.get_mut(key), value) {
+            (Some(Value::Table(target)), Value::Table(overlay)) => merge(target, overlay),
+            // `60` is as good as `60.0` for a float
+            (Some(existing), Value::Integer(integer)) if existing.is_float() => {
+                *existing = Value::Float(*integer as f64);
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
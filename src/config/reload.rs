@@ -0,0 +1,265 @@
+use crate::config::*;
+use crate::input::{action_just_pressed, Action};
+use crate::replay::ReplayPlayer;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use std::fs;
+use std::time::SystemTime;
+
+/// How often the config file is checked for changes, in seconds.
+const POLL_INTERVAL: f32 = 0.5;
+
+/// Values that are only read when a run starts. `world.background_color` applies
+/// right away.
+const NEXT_RUN_KEYS: [&str; 6] = [
+    "basic.tile_size",
+    "map",
+    "world.num_world_decorations",
+    "world.world_width",
+    "world.world_height",
+    "replay",
+];
+
+/// Polls the modification time of the config file.
+#[derive(Resource)]
+pub struct ConfigWatcher {
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+/// Asks for the config file to be read again.
+#[derive(Event, Default)]
+pub struct ReloadConfig;
+
+/// Sent after a reload changed the config.
+#[derive(Event, Debug, Clone, Default)]
+pub struct ConfigChanged {
+    /// Dotted paths of the changed values, e.g. `enemy.speed` or `boss.phases.1.pattern`.
+    pub keys: Vec<String>,
+}
+
+/// Errors in the config file. Shown in-game until a reload succeeds.
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct ConfigError(pub Option<String>);
+
+impl ConfigChanged {
+    /// All the changes sent since `events` was last read, as one, or `None` if there
+    /// were none. Several reloads can happen in one frame, e.g. a settings save and the
+    /// file watcher, and each only lists its own keys.
+    pub fn read_merged(events: &mut EventReader<ConfigChanged>) -> Option<Self> {
+        let mut merged: Option<Self> = None;
+        for changed in events.read() {
+            let keys = &mut merged.get_or_insert_with(Self::default).keys;
+            for key in &changed.keys {
+                if !keys.contains(key) {
+                    keys.push(key.clone());
+                }
+            }
+        }
+        merged
+    }
+
+    /// Whether `key` changed, including any value nested in it or containing it.
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|changed| is_nested_in(changed, key) || is_nested_in(key, changed))
+    }
+}
+
+impl Default for ConfigWatcher {
+    fn default() -> Self {
+        Self {
+            modified: None,
+            timer: Timer::from_seconds(POLL_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+pub(super) fn build(app: &mut App) {
+    app.init_resource::<ConfigWatcher>()
+        .init_resource::<ConfigError>()
+        .add_event::<ReloadConfig>()
+        .add_event::<ConfigChanged>()
+        .add_systems(Startup, init_config_watcher)
+        .add_systems(
+            Update,
+            (
+                (
+                    watch_config,
+                    request_reload.run_if(action_just_pressed(Action::ReloadConfig)),
+                ),
+                // a replay must keep the config it was recorded with
+                reload_config.run_if(not(resource_exists::<ReplayPlayer>)),
+                apply_basic_changes,
+            )
+                .chain(),
+        );
+}
+
+/// The dotted paths of all values that differ between `old` and `new`.
+pub fn diff_configs(old: &GameConfig, new: &GameConfig) -> Vec<String> {
+    let (Ok(old), Ok(new)) = (toml::Value::try_from(old), toml::Value::try_from(new)) else {
+        return Vec::new();
+    };
+    let mut keys = Vec::new();
+    diff_values("", &old, &new, &mut keys);
+    keys
+}
+
+fn diff_values(path: &str, old: &toml::Value, new: &toml::Value, keys: &mut Vec<String>) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match (old, new) {
+        (toml::Value::Table(old), toml::Value::Table(new)) => {
+            for (key, old_value) in old {
+                match new.get(key) {
+                    Some(new_value) => diff_values(&child(key), old_value, new_value, keys),
+                    None => keys.push(child(key)),
+                }
+            }
+            for key in new.keys().filter(|key| !old.contains_key(*key)) {
+                keys.push(child(key));
+            }
+        }
+        (toml::Value::Array(old), toml::Value::Array(new)) if old.len() == new.len() => {
+            for (index, (old_value, new_value)) in old.iter().zip(new).enumerate() {
+                diff_values(&child(&index.to_string()), old_value, new_value, keys);
+            }
+        }
+        _ => {
+            if old != new {
+                keys.push(path.to_string());
+            }
+        }
+    }
+}
+
+fn is_nested_in(key: &str, parent: &str) -> bool {
+    key.strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn modified_time(source: &ConfigSource) -> Option<SystemTime> {
+    fs::metadata(&source.path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn init_config_watcher(mut watcher: ResMut<ConfigWatcher>, source: Res<ConfigSource>) {
+    watcher.modified = modified_time(&source);
+}
+
+fn watch_config(
+    mut watcher: ResMut<ConfigWatcher>,
+    source: Res<ConfigSource>,
+    time: Res<Time<Real>>,
+    mut reload_events: EventWriter<ReloadConfig>,
+) {
+    watcher.timer.tick(time.delta());
+    if !watcher.timer.just_finished() {
+        return;
+    }
+    let modified = modified_time(&source);
+    if modified == watcher.modified {
+        return;
+    }
+    watcher.modified = modified;
+    // a deleted file keeps the current config
+    if modified.is_some() {
+        reload_events.send_default();
+    }
+}
+
+fn request_reload(mut reload_events: EventWriter<ReloadConfig>) {
+    reload_events.send_default();
+}
+
+fn reload_config(
+    mut reload_events: EventReader<ReloadConfig>,
+    mut changed_events: EventWriter<ConfigChanged>,
+    mut watcher: ResMut<ConfigWatcher>,
+    source: Res<ConfigSource>,
+    mut config: ResMut<GameConfig>,
+    mut layers: ResMut<ConfigLayers>,
+    mut error: ResMut<ConfigError>,
+) {
+    if reload_events.read().count() == 0 {
+        return;
+    }
+    // the file is read now, so the watcher must not reload it again, e.g. after the
+    // settings saved it and asked for this reload
+    watcher.modified = modified_time(&source);
+    let new_config = match source.reload() {
+        Ok(loaded) => {
+            loaded.report.log();
+            layers.set_if_neq(loaded.layers);
+            loaded.config
+        }
+        Err(err) => {
+            log::error!("Error reloading config: {}", err);
+            error.set_if_neq(ConfigError(Some(format!(
+                "{err}\nThe previous config is kept."
+            ))));
+            return;
+        }
+    };
+    error.set_if_neq(ConfigError(None));
+
+    let keys = diff_configs(&config, &new_config);
+    if keys.is_empty() {
+        log::info!("Config reloaded, nothing changed");
+        return;
+    }
+    log::info!("Config reloaded, changed: {}", keys.join(", "));
+    for key in NEXT_RUN_KEYS {
+        if keys.iter().any(|changed| is_nested_in(changed, key)) {
+            log::info!("Changes to `{}` take effect on the next run", key);
+        }
+    }
+    *config = new_config;
+    changed_events.send(ConfigChanged { keys });
+}
+
+/// Applies changes to the window, background and debug drawing.
+fn apply_basic_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
+    clear_color: Option<ResMut<ClearColor>>,
+    gizmo_store: Option<ResMut<GizmoConfigStore>>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    if changed.contains("basic.window_width") || changed.contains("basic.window_height") {
+        for mut window in window_query.iter_mut() {
+            window
+                .resolution
+                .set(config.basic.window_width, config.basic.window_height);
+        }
+    }
+    if changed.contains("basic.window_mode") || changed.contains("basic.vsync") {
+        for mut window in window_query.iter_mut() {
+            window.mode = config.basic.window_mode.window_mode();
+            window.present_mode = config.basic.present_mode();
+        }
+    }
+    if changed.contains("world.background_color") {
+        if let Some(mut clear_color) = clear_color {
+            let (r, g, b) = config.world.background_color;
+            clear_color.0 = Color::srgb_u8(r, g, b);
+        }
+    }
+    if changed.contains("basic.debug") {
+        if let Some(mut gizmo_store) = gizmo_store {
+            let (gizmo_config, _) = gizmo_store.config_mut::<DefaultGizmoConfigGroup>();
+            gizmo_config.enabled = config.basic.debug;
+        }
+    }
+}
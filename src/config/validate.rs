@@ -0,0 +1,392 @@
+use crate::config::*;
+use std::fmt;
+
+/// A problem with a single config value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigIssue {
+    /// Dotted path of the value, e.g. `enemy.spawn_interval`. Empty for the whole file.
+    pub path: String,
+    pub message: String,
+}
+
+/// Everything found wrong while loading a config.
+/// Errors are values the game can not use, warnings are values it can but probably should not.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigReport {
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    pub fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.errors.push(ConfigIssue {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.warnings.push(ConfigIssue {
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+
+    pub fn extend(&mut self, other: ConfigReport) {
+        self.errors.extend(other.errors);
+        self.warnings.extend(other.warnings);
+    }
+
+    /// The errors alone, one per line.
+    pub fn error_lines(&self) -> String {
+        self.errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    pub fn log(&self) {
+        for error in &self.errors {
+            log::error!("Config error: {}", error);
+        }
+        for warning in &self.warnings {
+            log::warn!("Config warning: {}", warning);
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self.errors.iter().map(|issue| ("error", issue));
+        let warnings = self.warnings.iter().map(|issue| ("warning", issue));
+        for (index, (severity, issue)) in errors.chain(warnings).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {}", severity, issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl GameConfig {
+    /// Checks values that parse fine but break or degrade the game.
+    pub fn validate(&self) -> ConfigReport {
+        let mut check = Checker::default();
+
+        let basic = &self.basic;
+        check.positive("basic.tile_size", basic.tile_size);
+        check.positive("basic.window_width", basic.window_width);
+        check.positive("basic.window_height", basic.window_height);
+
+        check.positive("world.world_width", self.world.world_width);
+        check.positive("world.world_height", self.world.world_height);
+
+        let map = &self.map;
+        check.positive("map.map_w", map.map_w as f32);
+        check.positive("map.map_h", map.map_h as f32);
+        check.positive("map.scale", map.scale);
+        if map.grass_height <= map.sand_height {
+            check.report.warning(
+                "map.grass_height",
+                "should be above `map.sand_height`, otherwise there is no sand",
+            );
+        }
+
+        let player = &self.player;
+        check.positive("player.health", player.health);
+        check.non_negative("player.speed", player.speed);
+        check.non_negative("player.damage_cooldown", player.damage_cooldown);
+        check.positive("player.collider_size", player.collider_size);
+        check.fraction("player.knockback_resistance", player.knockback_resistance);
+
+        let enemy = &self.enemy;
+        check.positive("enemy.health", enemy.health);
+        check.non_negative("enemy.speed", enemy.speed);
+        check.non_negative("enemy.damage", enemy.damage);
+        check.non_negative("enemy.damage_cooldown", enemy.damage_cooldown);
+        check.non_negative("enemy.follow_range", enemy.follow_range);
+        check.positive("enemy.collider_size", enemy.collider_size);
+        check.non_negative("enemy.knockback", enemy.knockback);
+        check.fraction("enemy.knockback_resistance", enemy.knockback_resistance);
+        check.positive("enemy.spawn_interval", enemy.spawn_interval);
+        if enemy.spawn_waves && (enemy.spawn_limit == 0 || enemy.spawn_rate_per_second == 0) {
+            check.report.warning(
+                "enemy.spawn_waves",
+                "waves are enabled but `spawn_limit` or `spawn_rate_per_second` is 0",
+            );
+        }
+
+        let bullet = &self.bullet;
+        check.non_negative("bullet.damage", bullet.damage);
+        check.positive("bullet.speed", bullet.speed);
+        check.positive("bullet.spawn_interval", bullet.spawn_interval);
+        check.positive("bullet.lifetime", bullet.lifetime);
+        if bullet.num_per_shot == 0 {
+            check
+                .report
+                .warning("bullet.num_per_shot", "is 0, the gun fires nothing");
+        }
+        check.non_negative("bullet.knockback", bullet.knockback);
+        check.non_negative("bullet.explosion_radius", bullet.explosion_radius);
+        check.non_negative("bullet.explosion_damage", bullet.explosion_damage);
+        check.fraction("bullet.explosion_falloff", bullet.explosion_falloff);
+        check.non_negative("bullet.explosion_knockback", bullet.explosion_knockback);
+        let modifiers = &bullet.modifiers;
+        check.non_negative("bullet.modifiers.ricochet_range", modifiers.ricochet_range);
+        check.non_negative("bullet.modifiers.homing_cone", modifiers.homing_cone);
+        check.non_negative("bullet.modifiers.homing_range", modifiers.homing_range);
+        check.non_negative(
+            "bullet.modifiers.homing_turn_rate",
+            modifiers.homing_turn_rate,
+        );
+        check.non_negative("bullet.modifiers.split_spread", modifiers.split_spread);
+        check.non_negative(
+            "bullet.modifiers.split_damage_ratio",
+            modifiers.split_damage_ratio,
+        );
+
+        let melee = &self.melee;
+        check.non_negative("melee.damage", melee.damage);
+        check.positive("melee.range", melee.range);
+        check.positive("melee.arc", melee.arc);
+        if melee.arc > 360.0 {
+            check.report.warning(
+                "melee.arc",
+                format!("{} is more than a full circle", melee.arc),
+            );
+        }
+        check.positive("melee.swing_duration", melee.swing_duration);
+        check.non_negative("melee.cooldown", melee.cooldown);
+        check.non_negative("melee.knockback", melee.knockback);
+
+        let boss = &self.boss;
+        if boss.enabled {
+            check.positive("boss.health", boss.health);
+            check.non_negative("boss.speed", boss.speed);
+            check.positive("boss.scale", boss.scale);
+            check.non_negative("boss.first_spawn", boss.first_spawn);
+            check.non_negative("boss.spawn_interval", boss.spawn_interval);
+            check.non_negative("boss.bullet_damage", boss.bullet_damage);
+            check.positive("boss.bullet_speed", boss.bullet_speed);
+            check.positive("boss.bullet_lifetime", boss.bullet_lifetime);
+            if boss.phases.is_empty() {
+                check
+                    .report
+                    .warning("boss.phases", "the boss is enabled but has no phases");
+            }
+        }
+        for (index, phase) in boss.phases.iter().enumerate() {
+            let path = format!("boss.phases.{index}");
+            check.fraction(&format!("{path}.health_threshold"), phase.health_threshold);
+            check.positive(&format!("{path}.fire_interval"), phase.fire_interval);
+            let count = match phase.pattern {
+                BossPattern::RadialBurst { count } | BossPattern::AimedVolley { count, .. } => {
+                    count
+                }
+                BossPattern::Spiral { arms, .. } => arms,
+            };
+            if count == 0 {
+                check
+                    .report
+                    .warning(format!("{path}.pattern"), "fires no bullets");
+            }
+            let previous = index.checked_sub(1).map(|index| &boss.phases[index]);
+            if previous.is_some_and(|previous| previous.health_threshold < phase.health_threshold) {
+                check.report.warning(
+                    format!("{path}.health_threshold"),
+                    "is above the previous phase's, so that phase is skipped",
+                );
+            }
+        }
+
+        check.fraction("audio.master_volume", self.audio.master_volume);
+        check.fraction("audio.music_volume", self.audio.music_volume);
+        check.fraction("audio.sfx_volume", self.audio.sfx_volume);
+        check.fraction("audio.ui_volume", self.audio.ui_volume);
+        for (name, sound) in &self.audio.sounds {
+            let path = format!("audio.sounds.{name}");
+            check.non_negative(&format!("{path}.volume"), sound.volume);
+            if sound.path.trim().is_empty() {
+                check
+                    .report
+                    .warning(format!("{path}.path"), "is empty, the sound is silent");
+            }
+            if sound.max_voices == 0 {
+                check
+                    .report
+                    .warning(format!("{path}.max_voices"), "is 0, the sound never plays");
+            }
+        }
+        let music = &self.audio.music;
+        for (state, name) in [
+            ("main_menu", &music.main_menu),
+            ("running", &music.running),
+            ("paused", &music.paused),
+            ("game_over", &music.game_over),
+        ] {
+            if !name.is_empty() && !self.audio.sounds.contains_key(name) {
+                check.report.warning(
+                    format!("audio.music.{state}"),
+                    format!("`{name}` is not in `audio.sounds`"),
+                );
+            }
+        }
+
+        let water = &self.water;
+        check.fraction("water.foam_intensity", water.foam_intensity);
+        check.positive("water.ripple_lifetime", water.ripple_lifetime);
+        check.non_negative("water.ripple_radius", water.ripple_radius);
+        check.positive("water.ripple_interval", water.ripple_interval);
+
+        let camera = &self.camera;
+        check.positive("camera.zoom_min", camera.zoom_min);
+        if camera.zoom_max < camera.zoom_min {
+            check
+                .report
+                .error("camera.zoom_max", "must not be below `camera.zoom_min`");
+        }
+        check.positive("camera.follow_speed", camera.follow_speed);
+        check.positive("camera.zoom_speed", camera.zoom_speed);
+        check.non_negative("camera.deadzone", camera.deadzone);
+        check.non_negative("camera.look_ahead", camera.look_ahead);
+        check.non_negative("camera.cursor_offset", camera.cursor_offset);
+
+        check.positive("death.fade_duration", self.death.fade_duration);
+        check.non_negative("death.corpse_lifetime", self.death.corpse_lifetime);
+
+        for (name, effect) in &self.particles.effects {
+            let path = format!("particles.effects.{name}");
+            check.range(&format!("{path}.lifetime"), effect.lifetime, true);
+            check.range(&format!("{path}.speed"), effect.speed, false);
+            check.non_negative(&format!("{path}.rate"), effect.rate);
+            check.non_negative(&format!("{path}.cone"), effect.cone);
+            check.non_negative(&format!("{path}.drag"), effect.drag);
+            check.non_negative(&format!("{path}.scale_start"), effect.scale_start);
+            check.non_negative(&format!("{path}.scale_end"), effect.scale_end);
+            if effect.sprite.trim().is_empty() {
+                check
+                    .report
+                    .error(format!("{path}.sprite"), "must not be empty");
+            }
+        }
+
+        let feedback = &self.feedback;
+        check.non_negative("feedback.shake_offset", feedback.shake_offset);
+        check.non_negative("feedback.shake_angle", feedback.shake_angle);
+        check.positive("feedback.shake_frequency", feedback.shake_frequency);
+        check.non_negative("feedback.trauma_decay", feedback.trauma_decay);
+        check.fraction("feedback.player_hit_trauma", feedback.player_hit_trauma);
+        check.fraction("feedback.explosion_trauma", feedback.explosion_trauma);
+        check.fraction("feedback.boss_attack_trauma", feedback.boss_attack_trauma);
+        check.non_negative("feedback.hit_stop_damage", feedback.hit_stop_damage);
+        check.non_negative("feedback.hit_stop_duration", feedback.hit_stop_duration);
+        check.non_negative("feedback.hit_stop_speed", feedback.hit_stop_speed);
+        check.non_negative("feedback.hit_stop_cooldown", feedback.hit_stop_cooldown);
+
+        let day_night = &self.day_night;
+        check.positive("day_night.day_length", day_night.day_length);
+        check.fraction("day_night.start_time", day_night.start_time);
+        check.positive(
+            "day_night.night_spawn_multiplier",
+            day_night.night_spawn_multiplier,
+        );
+        check.positive(
+            "day_night.night_speed_multiplier",
+            day_night.night_speed_multiplier,
+        );
+
+        let lighting = &self.lighting;
+        for (name, light) in [
+            ("player_light", &lighting.player_light),
+            ("muzzle_flash_light", &lighting.muzzle_flash_light),
+            ("explosion_light", &lighting.explosion_light),
+        ] {
+            check.positive(&format!("lighting.{name}.radius"), light.radius);
+            check.non_negative(&format!("lighting.{name}.intensity"), light.intensity);
+            check.non_negative(&format!("lighting.{name}.duration"), light.duration);
+        }
+
+        check.fraction("controls.stick_dead_zone", self.controls.stick_dead_zone);
+        for action in Action::ALL {
+            if self.controls.bindings(action).is_empty() {
+                check.report.warning(
+                    format!("controls.{}", action.name()),
+                    "has no bindings, the action can not be used",
+                );
+            }
+        }
+
+        if self.replay.record && self.replay.path.trim().is_empty() {
+            check
+                .report
+                .error("replay.path", "must not be empty while `record` is set");
+        }
+
+        check.report
+    }
+}
+
+#[derive(Default)]
+struct Checker {
+    report: ConfigReport,
+}
+
+impl Checker {
+    fn positive(&mut self, path: &str, value: f32) {
+        if value.is_nan() || value <= 0.0 {
+            self.report
+                .error(path, format!("must be greater than 0, got {value}"));
+        }
+    }
+
+    fn non_negative(&mut self, path: &str, value: f32) {
+        if value.is_nan() || value < 0.0 {
+            self.report
+                .error(path, format!("must not be negative, got {value}"));
+        }
+    }
+
+    /// A `[min, max]` range, where `min` must be above 0 if `positive`.
+    fn range(&mut self, path: &str, [min, max]: [f32; 2], positive: bool) {
+        if positive {
+            self.positive(path, min);
+        } else {
+            self.non_negative(path, min);
+        }
+        if max < min {
+            self.report.error(
+                path,
+                format!("the maximum {max} is below the minimum {min}"),
+            );
+        }
+    }
+
+    fn fraction(&mut self, path: &str, value: f32) {
+        if value.is_nan() {
+            self.report.error(path, "must be a number");
+        } else if !(0.0..=1.0).contains(&value) {
+            self.report
+                .warning(path, format!("{value} is outside 0..=1"));
+        }
+    }
+}
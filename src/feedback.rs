@@ -0,0 +1,145 @@
+use crate::camera::CameraShake;
+use crate::config::GameConfig;
+use crate::replay::ReplayPlayer;
+use crate::state::{AppState, GameState};
+use crate::world::boss::BossBullet;
+use crate::world::damage::{DamageEvent, DamagePhase};
+use crate::world::explosion::ExplosionEvent;
+use crate::world::player::Player;
+use bevy::prelude::*;
+
+/// Screen shake for player hits, explosions and boss attacks, and hit-stop for big
+/// hits. Both are off with `feedback.reduce_motion`.
+#[derive(Default)]
+pub struct FeedbackPlugin;
+
+/// Slows game time down while the timer runs, which counts real time.
+#[derive(Resource, Debug, Default)]
+pub struct HitStop {
+    pub timer: Option<Timer>,
+    /// Runs after a hit-stop, so a stream of big hits does not keep time slowed down.
+    pub cooldown: Option<Timer>,
+}
+
+impl Plugin for FeedbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HitStop>()
+            .add_systems(
+                Update,
+                (
+                    (
+                        shake_on_player_hits,
+                        // a replay's recorded frame times already include the hit-stop
+                        start_hit_stop.run_if(not(resource_exists::<ReplayPlayer>)),
+                    )
+                        .after(DamagePhase::After),
+                    shake_on_explosions,
+                    shake_on_boss_attacks,
+                )
+                    .run_if(in_state(GameState::Running))
+                    .run_if(motion_allowed),
+            )
+            // also while paused, so time is not left slowed down
+            .add_systems(Update, end_hit_stop)
+            .add_systems(OnExit(AppState::InGame), stop_hit_stop);
+    }
+}
+
+fn motion_allowed(config: Res<GameConfig>) -> bool {
+    !config.feedback.reduce_motion
+}
+
+fn add_trauma(shake_query: &mut Query<&mut CameraShake>, amount: f32) {
+    for mut shake in shake_query.iter_mut() {
+        shake.add_trauma(amount);
+    }
+}
+
+fn shake_on_player_hits(
+    mut damage_events: EventReader<DamageEvent>,
+    player_query: Query<(), With<Player>>,
+    mut shake_query: Query<&mut CameraShake>,
+    config: Res<GameConfig>,
+) {
+    for event in damage_events.read() {
+        if event.apply && player_query.contains(event.target) {
+            add_trauma(&mut shake_query, config.feedback.player_hit_trauma);
+        }
+    }
+}
+
+fn shake_on_explosions(
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut shake_query: Query<&mut CameraShake>,
+    config: Res<GameConfig>,
+) {
+    for _ in explosion_events.read() {
+        add_trauma(&mut shake_query, config.feedback.explosion_trauma);
+    }
+}
+
+/// Once per volley, however many bullets it has.
+fn shake_on_boss_attacks(
+    bullet_query: Query<(), Added<BossBullet>>,
+    mut shake_query: Query<&mut CameraShake>,
+    config: Res<GameConfig>,
+) {
+    if !bullet_query.is_empty() {
+        add_trauma(&mut shake_query, config.feedback.boss_attack_trauma);
+    }
+}
+
+fn start_hit_stop(
+    mut damage_events: EventReader<DamageEvent>,
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+    config: Res<GameConfig>,
+) {
+    let feedback = &config.feedback;
+    let mut big_hit = false;
+    for event in damage_events.read() {
+        big_hit |= event.apply && event.context.damage >= feedback.hit_stop_damage;
+    }
+    if !big_hit || feedback.hit_stop_damage <= 0.0 {
+        return;
+    }
+    if hit_stop.timer.is_some() || hit_stop.cooldown.is_some() {
+        return;
+    }
+    hit_stop.timer = Some(Timer::from_seconds(
+        feedback.hit_stop_duration,
+        TimerMode::Once,
+    ));
+    time.set_relative_speed(feedback.hit_stop_speed);
+}
+
+/// Also cuts the hit-stop short when `feedback.reduce_motion` is turned on during it.
+fn end_hit_stop(
+    mut hit_stop: ResMut<HitStop>,
+    mut time: ResMut<Time<Virtual>>,
+    real_time: Res<Time<Real>>,
+    config: Res<GameConfig>,
+) {
+    if let Some(cooldown) = &mut hit_stop.cooldown {
+        if cooldown.tick(real_time.delta()).finished() {
+            hit_stop.cooldown = None;
+        }
+    }
+    let Some(timer) = &mut hit_stop.timer else {
+        return;
+    };
+    let finished = timer.tick(real_time.delta()).finished();
+    if finished || config.feedback.reduce_motion {
+        hit_stop.timer = None;
+        hit_stop.cooldown = Some(Timer::from_seconds(
+            config.feedback.hit_stop_cooldown,
+            TimerMode::Once,
+        ));
+        time.set_relative_speed(1.0);
+    }
+}
+
+fn stop_hit_stop(mut hit_stop: ResMut<HitStop>, mut time: ResMut<Time<Virtual>>) {
+    *hit_stop = HitStop::default();
+    time.set_relative_speed(1.0);
+}
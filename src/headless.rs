@@ -0,0 +1,233 @@
+use crate::animation::AnimatorPlugin;
+use crate::config::{ConfigChanged, GameConfig};
+use crate::input::InputHandlerPlugin;
+use crate::replay::ReplayPlugin;
+use crate::resource::*;
+use crate::rng::GameRng;
+use crate::rng::RngPlugin;
+use crate::sprite_manifest::SpriteManifest;
+use crate::state::*;
+use crate::world::boss::Boss;
+use crate::world::collision::configured_physics_plugins;
+use crate::world::damage::Health;
+use crate::world::enemy::Enemy;
+use crate::world::map::MapPlugin;
+use crate::world::player::Player;
+use crate::world::WorldPlugins;
+use avian2d::prelude::Gravity;
+use bevy::asset::AssetPlugin;
+use bevy::core::FrameCount;
+use bevy::hierarchy::HierarchyPlugin;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::transform::TransformPlugin;
+use std::fmt;
+use std::time::Duration;
+
+/// Replaces [`ResourcePlugin`] when there is no renderer: the sprite sheets and font
+/// point at empty handles, and the game starts right away instead of in the main menu.
+#[derive(Default)]
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        let manifest = SpriteManifest::builtin();
+        app.insert_resource(GlobalSpriteSheet(SpriteSheet::placeholder(manifest.global)))
+            .insert_resource(TileSet(SpriteSheet::placeholder(manifest.tiles)))
+            .insert_resource(GlobalFont {
+                handle: Handle::default(),
+            })
+            // normally added by `ConfigPlugin`, which watches the config file
+            .add_event::<ConfigChanged>()
+            // normally registered by the render plugins, the lighting and the water
+            // are meshes with their own materials
+            .init_asset::<Mesh>()
+            .init_asset::<Image>();
+    }
+}
+
+/// The timestep of [`HeadlessBuilder`] runs, about 60 frames per second.
+pub const TIMESTEP: Duration = Duration::from_millis(16);
+
+/// Builds an app that simulates the game without a window or any rendering.
+///
+/// Time advances by exactly `timestep` on every [`App::update`], so runs are
+/// repeatable and independent of the machine they run on.
+pub fn headless_app(config: GameConfig, timestep: Duration) -> App {
+    let tile_size = config.basic.tile_size;
+    let mut app = App::new();
+    app.insert_resource(Gravity::ZERO)
+        .insert_resource(config)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(timestep))
+        .add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+            AssetPlugin::default(),
+            InputPlugin,
+            configured_physics_plugins(tile_size),
+            HeadlessPlugin,
+            RngPlugin,
+            InputHandlerPlugin,
+            ReplayPlugin,
+            AnimatorPlugin,
+            WorldPlugins.build().disable::<MapPlugin>(),
+            GameStatePlugin {
+                initial_state: AppState::InGame,
+            },
+        ));
+    app
+}
+
+/// Sets up a [`headless_app`], starting from the default config and [`TIMESTEP`].
+pub struct HeadlessBuilder {
+    config: GameConfig,
+    timestep: Duration,
+}
+
+impl Default for HeadlessBuilder {
+    fn default() -> Self {
+        Self::new(GameConfig::default())
+    }
+}
+
+impl HeadlessBuilder {
+    pub fn new(config: GameConfig) -> Self {
+        Self {
+            config,
+            timestep: TIMESTEP,
+        }
+    }
+
+    pub fn timestep(mut self, timestep: Duration) -> Self {
+        self.timestep = timestep;
+        self
+    }
+
+    /// Changes the config the run starts with.
+    pub fn configure(mut self, configure: impl FnOnce(&mut GameConfig)) -> Self {
+        configure(&mut self.config);
+        self
+    }
+
+    /// No enemy waves, so only what the run spawns itself is around.
+    pub fn without_waves(self) -> Self {
+        self.configure(|config| config.enemy.spawn_waves = false)
+    }
+
+    /// The training dummy, and no waves.
+    pub fn with_dummy(self) -> Self {
+        self.without_waves()
+            .configure(|config| config.enemy.spawn_dummy = true)
+    }
+
+    pub fn build(self) -> App {
+        headless_app(self.config, self.timestep)
+    }
+}
+
+/// A summary of a simulated run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunStats {
+    pub seed: u64,
+    pub frames: u32,
+    /// Seconds the game was running, pauses excluded.
+    pub game_time: f32,
+    pub game_state: Option<GameState>,
+    pub player_health: Option<f32>,
+    pub enemies: usize,
+    pub bosses: usize,
+}
+
+/// Convenience accessors for driving and inspecting a headless app.
+pub trait Simulation {
+    /// Runs `frames` updates.
+    fn step(&mut self, frames: usize);
+
+    /// Runs updates until `seconds` of game time have passed.
+    fn run_for(&mut self, seconds: f32);
+
+    fn game_state(&self) -> Option<GameState>;
+
+    fn player_health(&mut self) -> Option<f32>;
+
+    fn enemy_count(&mut self) -> usize;
+
+    fn stats(&mut self) -> RunStats;
+}
+
+impl Simulation for App {
+    fn step(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.update();
+        }
+    }
+
+    fn run_for(&mut self, seconds: f32) {
+        let timestep = match self.world().get_resource::<TimeUpdateStrategy>() {
+            Some(TimeUpdateStrategy::ManualDuration(timestep)) => timestep.as_secs_f32(),
+            _ => panic!("run_for requires TimeUpdateStrategy::ManualDuration"),
+        };
+        let frames = (seconds / timestep).ceil() as usize;
+        self.step(frames);
+    }
+
+    fn game_state(&self) -> Option<GameState> {
+        self.world()
+            .get_resource::<State<GameState>>()
+            .map(|state| *state.get())
+    }
+
+    fn player_health(&mut self) -> Option<f32> {
+        self.world_mut()
+            .query_filtered::<&Health, With<Player>>()
+            .get_single(self.world())
+            .ok()
+            .map(Health::current)
+    }
+
+    fn enemy_count(&mut self) -> usize {
+        self.world_mut()
+            .query_filtered::<(), With<Enemy>>()
+            .iter(self.world())
+            .count()
+    }
+
+    fn stats(&mut self) -> RunStats {
+        let bosses = self
+            .world_mut()
+            .query_filtered::<(), With<Boss>>()
+            .iter(self.world())
+            .count();
+        RunStats {
+            seed: self.world().resource::<GameRng>().seed(),
+            frames: self.world().resource::<FrameCount>().0,
+            game_time: self.world().resource::<Time<Virtual>>().elapsed_secs(),
+            game_state: self.game_state(),
+            player_health: self.player_health(),
+            enemies: self.enemy_count(),
+            bosses,
+        }
+    }
+}
+
+impl fmt::Display for RunStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed: {}", self.seed)?;
+        writeln!(f, "frames: {}", self.frames)?;
+        writeln!(f, "game time: {:.2}s", self.game_time)?;
+        match self.game_state {
+            Some(state) => writeln!(f, "game state: {:?}", state)?,
+            None => writeln!(f, "game state: none")?,
+        }
+        match self.player_health {
+            Some(health) => writeln!(f, "player health: {:.1}", health)?,
+            None => writeln!(f, "player health: none")?,
+        }
+        writeln!(f, "enemies: {}", self.enemies)?;
+        write!(f, "bosses: {}", self.bosses)
+    }
+}
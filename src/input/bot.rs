@@ -0,0 +1,72 @@
+use crate::input::*;
+use crate::world::enemy::Enemy;
+use crate::world::player::Player;
+
+/// Enemies closer than this are shot at.
+const FIRE_RANGE: f32 = 160.0;
+/// The bot backs off from enemies closer than this, and circles the rest.
+const KEEP_AWAY: f32 = 80.0;
+
+/// A simple kiting bot for unattended soak tests and balance runs. It aims and
+/// fires at the nearest enemy, swings when one gets within melee range, backs away
+/// from enemies that come too close and otherwise circles around its target.
+#[derive(Default)]
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            drive_bot
+                .in_set(InputSet::Collect)
+                .run_if(resource_equals(InputSource::Bot)),
+        );
+    }
+}
+
+fn drive_bot(
+    player_query: Query<&GlobalTransform, With<Player>>,
+    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+    mut move_vector: ResMut<MoveVector>,
+    mut cursor_position: ResMut<CursorPosition>,
+    mut action_state: ResMut<ActionState>,
+    config: Res<GameConfig>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let position = player_transform.translation().truncate();
+    let nearest = enemy_query
+        .iter()
+        .map(|transform| transform.translation().truncate())
+        .min_by(|a, b| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        });
+    let Some(target) = nearest else {
+        move_vector.0 = Vec2::ZERO;
+        action_state.update(Actions::default());
+        return;
+    };
+
+    let offset = target - position;
+    let distance = offset.length();
+    let direction = offset.normalize_or(Vec2::X);
+
+    let mut actions = Actions::default();
+    if distance <= FIRE_RANGE {
+        actions.insert(Action::Fire);
+    }
+    // release between swings, a swing only starts on a fresh press
+    if distance <= config.melee.range && !action_state.pressed(Action::Melee) {
+        actions.insert(Action::Melee);
+    }
+
+    move_vector.0 = if distance < KEEP_AWAY {
+        -direction
+    } else {
+        direction.perp()
+    };
+    cursor_position.0 = target;
+    action_state.update(actions);
+}
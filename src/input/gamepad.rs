@@ -0,0 +1,74 @@
+use crate::input::*;
+use crate::world::player::Player;
+use bevy::window::CursorMoved;
+
+/// How far ahead of the player the right stick puts the aim point.
+const AIM_DISTANCE: f32 = 100.0;
+
+/// Aims with a gamepad's right stick instead of the mouse cursor. The aim is still
+/// written to [`CursorPosition`], so recordings and replays need not tell them apart.
+#[derive(Default)]
+pub struct GamepadAimPlugin;
+
+/// The device that aims: the last one used of the mouse and the right stick.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AimDevice {
+    #[default]
+    Mouse,
+    Gamepad,
+}
+
+/// The last direction the right stick was pushed in. Kept while the stick is at rest.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct StickAim(pub Vec2);
+
+impl Plugin for GamepadAimPlugin {
+    fn build(&self, app: &mut App) {
+        // registered by the window plugin, which headless runs do not have
+        app.add_event::<CursorMoved>()
+            .init_resource::<AimDevice>()
+            .init_resource::<StickAim>()
+            .add_systems(
+                Update,
+                (
+                    switch_aim_device,
+                    aim_with_stick.run_if(resource_equals(AimDevice::Gamepad)),
+                )
+                    .chain()
+                    .in_set(InputSet::Collect)
+                    .run_if(resource_equals(InputSource::Device)),
+            );
+    }
+}
+
+fn switch_aim_device(
+    mut cursor_events: EventReader<CursorMoved>,
+    input: BindingInput,
+    mut aim_device: ResMut<AimDevice>,
+    mut stick_aim: ResMut<StickAim>,
+    config: Res<GameConfig>,
+) {
+    let cursor_moved = cursor_events.read().count() > 0;
+    let stick = input.right_stick(config.controls.stick_dead_zone);
+    if stick != Vec2::ZERO {
+        stick_aim.0 = stick.normalize();
+        aim_device.set_if_neq(AimDevice::Gamepad);
+    } else if cursor_moved {
+        aim_device.set_if_neq(AimDevice::Mouse);
+    }
+}
+
+fn aim_with_stick(
+    player_query: Query<&GlobalTransform, With<Player>>,
+    stick_aim: Res<StickAim>,
+    mut cursor_position: ResMut<CursorPosition>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    // before the stick is first pushed, keep aiming where the mouse left off
+    if stick_aim.0 == Vec2::ZERO {
+        return;
+    }
+    cursor_position.0 = player_transform.translation().truncate() + stick_aim.0 * AIM_DISTANCE;
+}
@@ -0,0 +1,382 @@
+use crate::config::{GameConfig, InputBinding};
+use crate::state::GameState;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+pub mod bot;
+pub mod gamepad;
+pub mod recording;
+
+pub struct InputHandlerPlugin;
+
+/// A vector representing the player's input, accumulated over all frames that ran
+/// since the last time the physics simulation was advanced.
+#[derive(Resource, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
+pub struct MoveVector(pub Vec2);
+
+#[derive(Resource, Default)]
+pub struct CursorPosition(pub Vec2);
+
+/// Discrete player actions, independent of the device or script that triggers them.
+/// Each has its bindings in [`ControlsConfig`](crate::config::ControlsConfig).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Melee,
+    Pause,
+    ReloadConfig,
+    Heal,
+    ConfigView,
+}
+
+/// A set of [`Action`]s, stored as bit flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Actions(u16);
+
+/// The actions held this frame and the previous one.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ActionState {
+    current: Actions,
+    previous: Actions,
+}
+
+/// Where the player's input comes from.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputSource {
+    /// Keyboard and mouse.
+    #[default]
+    Device,
+    /// Frames read from an [`InputPlayback`](recording::InputPlayback).
+    Playback,
+    /// The built-in [`bot`].
+    Bot,
+}
+
+/// Everything the player can input during one frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct InputFrame {
+    pub move_vector: Vec2,
+    pub cursor_position: Vec2,
+    pub actions: Actions,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputSet {
+    /// Input sources write [`MoveVector`], [`CursorPosition`] and [`ActionState`].
+    Collect,
+    /// The collected input is observed, e.g. recorded.
+    Observe,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Melee,
+        Action::Pause,
+        Action::ReloadConfig,
+        Action::Heal,
+        Action::ConfigView,
+    ];
+
+    /// The field of the action's bindings in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveUp => "move_up",
+            Action::MoveDown => "move_down",
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Fire => "fire",
+            Action::Melee => "melee",
+            Action::Pause => "pause",
+            Action::ReloadConfig => "reload_config",
+            Action::Heal => "heal",
+            Action::ConfigView => "config_view",
+        }
+    }
+
+    /// A name for menus, e.g. `Move up`.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveUp => "Move up",
+            Action::MoveDown => "Move down",
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Fire => "Fire",
+            Action::Melee => "Melee",
+            Action::Pause => "Pause",
+            Action::ReloadConfig => "Reload config",
+            Action::Heal => "Heal",
+            Action::ConfigView => "Config view",
+        }
+    }
+
+    const fn bit(self) -> u16 {
+        1 << self as u16
+    }
+}
+
+impl Actions {
+    pub fn from_bits(bits: u16) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(&self, action: Action) -> bool {
+        self.0 & action.bit() != 0
+    }
+
+    pub fn insert(&mut self, action: Action) {
+        self.0 |= action.bit();
+    }
+
+    pub fn with(mut self, action: Action) -> Self {
+        self.insert(action);
+        self
+    }
+}
+
+impl ActionState {
+    /// Advances to the next frame with `actions` held.
+    pub fn update(&mut self, actions: Actions) {
+        self.previous = self.current;
+        self.current = actions;
+    }
+
+    pub fn current(&self) -> Actions {
+        self.current
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.current.contains(action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.current.contains(action) && !self.previous.contains(action)
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        !self.current.contains(action) && self.previous.contains(action)
+    }
+}
+
+impl InputFrame {
+    pub fn capture(
+        move_vector: &MoveVector,
+        cursor_position: &CursorPosition,
+        action_state: &ActionState,
+    ) -> Self {
+        Self {
+            move_vector: move_vector.0,
+            cursor_position: cursor_position.0,
+            actions: action_state.current(),
+        }
+    }
+
+    pub fn apply(
+        &self,
+        move_vector: &mut MoveVector,
+        cursor_position: &mut CursorPosition,
+        action_state: &mut ActionState,
+    ) {
+        move_vector.0 = self.move_vector;
+        cursor_position.0 = self.cursor_position;
+        action_state.update(self.actions);
+    }
+}
+
+impl Plugin for InputHandlerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MoveVector>()
+            .init_resource::<CursorPosition>()
+            .init_resource::<ActionState>()
+            .init_resource::<InputSource>()
+            .configure_sets(
+                Update,
+                (InputSet::Collect, InputSet::Observe)
+                    .chain()
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                Update,
+                (
+                    update_move_vector,
+                    // there is no window to read the cursor from in headless runs
+                    update_cursor_position.run_if(
+                        any_with_component::<Window>
+                            .and(resource_equals(gamepad::AimDevice::Mouse)),
+                    ),
+                    update_actions,
+                )
+                    .in_set(InputSet::Collect)
+                    .run_if(resource_equals(InputSource::Device)),
+            )
+            .add_plugins((
+                recording::RecordingPlugin,
+                bot::BotPlugin,
+                gamepad::GamepadAimPlugin,
+            ));
+    }
+}
+
+/// Keyboard, mouse and gamepad state, for checking [`InputBinding`]s.
+#[derive(SystemParam)]
+pub struct BindingInput<'w, 's> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    mouse_input: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl BindingInput<'_, '_> {
+    /// Whether any of `bindings` is held.
+    pub fn pressed(&self, bindings: &[InputBinding]) -> bool {
+        any_pressed(
+            bindings,
+            &self.keyboard_input,
+            &self.mouse_input,
+            &self.gamepads,
+        )
+    }
+
+    /// Whether any of `bindings` was pressed this frame.
+    pub fn just_pressed(&self, bindings: &[InputBinding]) -> bool {
+        any_just_pressed(
+            bindings,
+            &self.keyboard_input,
+            &self.mouse_input,
+            &self.gamepads,
+        )
+    }
+
+    /// The left stick of the first gamepad pushed past `dead_zone`.
+    pub fn left_stick(&self, dead_zone: f32) -> Vec2 {
+        self.stick(dead_zone, Gamepad::left_stick)
+    }
+
+    /// The right stick of the first gamepad pushed past `dead_zone`.
+    pub fn right_stick(&self, dead_zone: f32) -> Vec2 {
+        self.stick(dead_zone, Gamepad::right_stick)
+    }
+
+    fn stick(&self, dead_zone: f32, stick: fn(&Gamepad) -> Vec2) -> Vec2 {
+        self.gamepads
+            .iter()
+            .map(stick)
+            .find(|value| value.length() > dead_zone)
+            .unwrap_or(Vec2::ZERO)
+            .clamp_length_max(1.0)
+    }
+}
+
+/// Whether any of `bindings` is held.
+pub fn any_pressed<'a>(
+    bindings: &[InputBinding],
+    keyboard_input: &ButtonInput<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    gamepads: impl IntoIterator<Item = &'a Gamepad> + Copy,
+) -> bool {
+    bindings.iter().any(|binding| match binding {
+        InputBinding::Key(key) => keyboard_input.pressed(*key),
+        InputBinding::Mouse(button) => mouse_input.pressed(*button),
+        InputBinding::Gamepad(button) => {
+            gamepads.into_iter().any(|gamepad| gamepad.pressed(*button))
+        }
+    })
+}
+
+/// Whether any of `bindings` was pressed this frame.
+pub fn any_just_pressed<'a>(
+    bindings: &[InputBinding],
+    keyboard_input: &ButtonInput<KeyCode>,
+    mouse_input: &ButtonInput<MouseButton>,
+    gamepads: impl IntoIterator<Item = &'a Gamepad> + Copy,
+) -> bool {
+    bindings.iter().any(|binding| match binding {
+        InputBinding::Key(key) => keyboard_input.just_pressed(*key),
+        InputBinding::Mouse(button) => mouse_input.just_pressed(*button),
+        InputBinding::Gamepad(button) => gamepads
+            .into_iter()
+            .any(|gamepad| gamepad.just_pressed(*button)),
+    })
+}
+
+/// Run condition for the bindings of `action`. Unlike [`ActionState`], it works in
+/// every state.
+pub fn action_just_pressed(
+    action: Action,
+) -> impl FnMut(BindingInput, Res<GameConfig>) -> bool + Clone {
+    move |input: BindingInput, config: Res<GameConfig>| {
+        input.just_pressed(config.controls.bindings(action))
+    }
+}
+
+fn update_move_vector(
+    input: BindingInput,
+    mut move_vector: ResMut<MoveVector>,
+    config: Res<GameConfig>,
+) {
+    let controls = &config.controls;
+
+    let mut delta = Vec2::ZERO;
+    for (action, direction) in [
+        (Action::MoveUp, Vec2::Y),
+        (Action::MoveLeft, Vec2::NEG_X),
+        (Action::MoveDown, Vec2::NEG_Y),
+        (Action::MoveRight, Vec2::X),
+    ] {
+        if input.pressed(controls.bindings(action)) {
+            delta += direction;
+        }
+    }
+
+    // the stick keeps how far it is pushed, the buttons always run
+    move_vector.0 = if delta == Vec2::ZERO {
+        input.left_stick(controls.stick_dead_zone)
+    } else {
+        delta.normalize()
+    };
+}
+
+fn update_cursor_position(
+    camera_query: Single<(&Camera, &GlobalTransform)>,
+    window: Single<&Window>,
+    mut cursor_position: ResMut<CursorPosition>,
+) {
+    let (camera, camera_transform) = *camera_query;
+
+    let Some(cursor_window_pos) = window.cursor_position() else {
+        return;
+    };
+
+    // Calculate a world position based on the cursor's position.
+    let Ok(point) = camera.viewport_to_world_2d(camera_transform, cursor_window_pos) else {
+        return;
+    };
+
+    cursor_position.0 = point;
+}
+
+fn update_actions(
+    input: BindingInput,
+    mut action_state: ResMut<ActionState>,
+    config: Res<GameConfig>,
+) {
+    let mut actions = Actions::default();
+    // the other actions are menu hotkeys, see `action_just_pressed`
+    for action in [Action::Fire, Action::Melee, Action::Heal] {
+        if input.pressed(config.controls.bindings(action)) {
+            actions.insert(action);
+        }
+    }
+    action_state.update(actions);
+}
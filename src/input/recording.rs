@@ -0,0 +1,170 @@
+use crate::input::*;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A sequence of input frames, one per frame of the game.
+///
+/// Stored as plain text, one frame per line:
+/// `move_x move_y cursor_x cursor_y action_bits`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputRecording {
+    pub frames: Vec<InputFrame>,
+}
+
+/// Feeds a recording back as the player's input when [`InputSource::Playback`] is active.
+#[derive(Resource, Default)]
+pub struct InputPlayback {
+    pub recording: InputRecording,
+    pub next_frame: usize,
+}
+
+/// Records the player's input while present, whatever the [`InputSource`].
+#[derive(Resource)]
+pub struct InputRecorder {
+    pub path: PathBuf,
+    pub recording: InputRecording,
+}
+
+#[derive(Default)]
+pub struct RecordingPlugin;
+
+impl InputRecording {
+    const HEADER: &'static str = "# bevy-2d-shooter input recording v1";
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Box<dyn Error>> {
+        let mut frames = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [move_x, move_y, cursor_x, cursor_y, actions] = fields[..] else {
+                return Err(format!("line {}: expected 5 fields", number + 1).into());
+            };
+            frames.push(InputFrame {
+                move_vector: Vec2::new(move_x.parse()?, move_y.parse()?),
+                cursor_position: Vec2::new(cursor_x.parse()?, cursor_y.parse()?),
+                actions: Actions::from_bits(actions.parse()?),
+            });
+        }
+        Ok(Self { frames })
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from(Self::HEADER);
+        text.push('\n');
+        for frame in &self.frames {
+            // `{}` prints the shortest representation that parses back to the same float
+            let _ = writeln!(
+                text,
+                "{} {} {} {} {}",
+                frame.move_vector.x,
+                frame.move_vector.y,
+                frame.cursor_position.x,
+                frame.cursor_position.y,
+                frame.actions.bits()
+            );
+        }
+        text
+    }
+}
+
+impl InputPlayback {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            next_frame: 0,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.recording.frames.len()
+    }
+}
+
+impl InputRecorder {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            recording: InputRecording::default(),
+        }
+    }
+}
+
+impl Plugin for RecordingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                play_back_input
+                    .in_set(InputSet::Collect)
+                    .run_if(resource_equals(InputSource::Playback)),
+                record_input
+                    .in_set(InputSet::Observe)
+                    .run_if(resource_exists::<InputRecorder>),
+            ),
+        )
+        .add_systems(
+            OnExit(GameState::Running),
+            save_recording.run_if(resource_exists::<InputRecorder>),
+        );
+    }
+}
+
+fn play_back_input(
+    playback: Option<ResMut<InputPlayback>>,
+    mut move_vector: ResMut<MoveVector>,
+    mut cursor_position: ResMut<CursorPosition>,
+    mut action_state: ResMut<ActionState>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+    // once the recording runs out the player stands still, still aiming where it last did
+    let frame = playback
+        .recording
+        .frames
+        .get(playback.next_frame)
+        .copied()
+        .unwrap_or(InputFrame {
+            cursor_position: cursor_position.0,
+            ..default()
+        });
+    if playback.next_frame == playback.recording.frames.len() {
+        log::info!("Input playback finished");
+    }
+    playback.next_frame += 1;
+    frame.apply(&mut move_vector, &mut cursor_position, &mut action_state);
+}
+
+fn record_input(
+    mut recorder: ResMut<InputRecorder>,
+    move_vector: Res<MoveVector>,
+    cursor_position: Res<CursorPosition>,
+    action_state: Res<ActionState>,
+) {
+    recorder.recording.frames.push(InputFrame::capture(
+        &move_vector,
+        &cursor_position,
+        &action_state,
+    ));
+}
+
+fn save_recording(recorder: Res<InputRecorder>) {
+    match recorder.recording.save(&recorder.path) {
+        Ok(()) => log::info!("Input recording saved to {}", recorder.path.display()),
+        Err(err) => log::error!("Error saving input recording: {}", err),
+    }
+}
@@ -1,8 +1,15 @@
 pub mod animation;
+pub mod audio;
 pub mod camera;
+pub mod cli;
 pub mod config;
+pub mod feedback;
+pub mod headless;
 pub mod input;
+pub mod replay;
 pub mod resource;
+pub mod rng;
+pub mod sprite_manifest;
 pub mod sprite_order;
 pub mod state;
 pub mod ui;
@@ -1,75 +1,169 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use avian2d::interpolation::PhysicsInterpolationPlugin;
 use avian2d::prelude::Gravity;
-use avian2d::PhysicsPlugins;
+use bevy::ecs::world::Command;
 use bevy::prelude::*;
-use bevy::window::PresentMode;
 use bevy_2d_shooter::animation::AnimatorPlugin;
+use bevy_2d_shooter::audio::SoundPlugin;
 use bevy_2d_shooter::camera::SmoothCameraPlugin;
+use bevy_2d_shooter::cli::Cli;
 use bevy_2d_shooter::config::*;
-use bevy_2d_shooter::input::InputHandlerPlugin;
+use bevy_2d_shooter::feedback::FeedbackPlugin;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::input::*;
+use bevy_2d_shooter::replay::*;
 use bevy_2d_shooter::resource::ResourcePlugin;
+use bevy_2d_shooter::rng::*;
 use bevy_2d_shooter::state::*;
 use bevy_2d_shooter::ui::UIPlugins;
+use bevy_2d_shooter::world::collision::configured_physics_plugins;
 use bevy_2d_shooter::world::WorldPlugins;
+use std::time::Duration;
 
 fn main() {
-    let config = get_config();
+    let cli = match Cli::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, Cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if cli.help {
+        println!("{}", Cli::USAGE);
+        return;
+    }
+
+    let config_source = cli.config_source();
+    let LoadedConfig {
+        config,
+        report,
+        layers,
+    } = match config_source.load() {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(2);
+        }
+    };
+    if !report.is_empty() {
+        eprintln!("{}:\n{}", config_source.path.display(), report);
+    }
+    let replay = cli.replay.as_ref().map(|path| match Replay::load(path) {
+        Ok(replay) => replay,
+        Err(err) => {
+            eprintln!("Error loading replay {}: {}", path.display(), err);
+            std::process::exit(2);
+        }
+    });
+
+    if let Some(seconds) = cli.headless {
+        run_headless(&cli, config, replay, seconds);
+        return;
+    }
+
     let tile_size = config.basic.tile_size;
-    let ww = config.basic.window_width;
-    let wh = config.basic.window_height;
+    let default_plugins = configured_default_plugins(&config.basic);
     let bg_color = Color::srgb_u8(
         config.world.background_color.0,
         config.world.background_color.1,
         config.world.background_color.2,
     );
-    App::new()
-        .insert_resource(ClearColor(bg_color))
+    let mut app = App::new();
+    if report.has_errors() {
+        app.insert_resource(ConfigError(Some(format!(
+            "{}\nThese values use their defaults.",
+            report.error_lines()
+        ))));
+    }
+    if let Some(replay) = replay {
+        // replays start from the main menu
+        app.insert_resource(PendingReplay(replay));
+    } else {
+        app.insert_resource(AfterLoading(cli.start_state));
+    }
+    app.insert_resource(ClearColor(bg_color))
         .insert_resource(Gravity::ZERO)
+        .insert_resource(NextSeed(cli.seed))
+        .insert_resource(initial_input_source(&cli))
+        .insert_resource(config_source)
+        .insert_resource(layers)
         .insert_resource(config)
         .add_plugins((
-            configured_default_plugins(ww, wh),
+            default_plugins,
             configured_physics_plugins(tile_size),
             ConfigPlugin,
             ResourcePlugin,
+            RngPlugin,
             InputHandlerPlugin,
+            ReplayPlugin,
             AnimatorPlugin,
+            SoundPlugin,
             SmoothCameraPlugin,
+            FeedbackPlugin,
             WorldPlugins,
             UIPlugins,
+            GameStatePlugin::default(),
         ))
-        .init_state::<AppState>()
-        .add_sub_state::<GameState>()
-        .enable_state_scoped_entities::<AppState>()
-        .enable_state_scoped_entities::<GameState>()
         .add_systems(Startup, configure_gizmos)
         .run();
 }
 
-fn configured_default_plugins(window_width: f32, window_height: f32) -> impl PluginGroup {
+/// Simulates `seconds` of game time without a window and prints stats to stdout.
+fn run_headless(cli: &Cli, config: GameConfig, replay: Option<Replay>, seconds: f32) {
+    let mut app = headless_app(config, Duration::from_secs_f32(1.0 / 60.0));
+    app.insert_resource(NextSeed(cli.seed))
+        .insert_resource(initial_input_source(cli));
+    if let Some(replay) = replay {
+        StartReplay(replay).apply(app.world_mut());
+    }
+
+    // the run also ends early when the player dies or the replay is over
+    loop {
+        app.update();
+        let game_time = app.world().resource::<Time<Virtual>>().elapsed_secs();
+        let replay_finished = app
+            .world()
+            .get_resource::<ReplayPlayer>()
+            .is_some_and(|player| player.finished);
+        if game_time >= seconds || replay_finished || app.game_state() == Some(GameState::GameOver)
+        {
+            break;
+        }
+    }
+
+    println!("{}", app.stats());
+    if let Some(player) = app.world().get_resource::<ReplayPlayer>() {
+        match player.first_mismatch {
+            Some(tick) => println!("replay: diverged at tick {}", tick),
+            None => println!("replay: no divergence"),
+        }
+    }
+}
+
+fn initial_input_source(cli: &Cli) -> InputSource {
+    if cli.bot {
+        InputSource::Bot
+    } else {
+        InputSource::Device
+    }
+}
+
+fn configured_default_plugins(basic: &BasicConfig) -> impl PluginGroup {
     DefaultPlugins
         .set(ImagePlugin::default_nearest())
         .set(WindowPlugin {
             primary_window: Some(Window {
-                // mode: bevy::window::WindowMode::Fullscreen(MonitorSelection::Current),
+                mode: basic.window_mode.window_mode(),
                 resizable: true,
                 focused: true,
-                resolution: (window_width, window_height).into(),
-                // set vsync to false
-                present_mode: PresentMode::Immediate,
+                resolution: (basic.window_width, basic.window_height).into(),
+                present_mode: basic.present_mode(),
                 ..default()
             }),
             ..default()
         })
 }
 
-fn configured_physics_plugins(tile_size: f32) -> impl PluginGroup {
-    PhysicsPlugins::default()
-        .with_length_unit(tile_size)
-        .set(PhysicsInterpolationPlugin::interpolate_translation_all())
-}
-
 fn configure_gizmos(config: Res<GameConfig>, mut config_store: ResMut<GizmoConfigStore>) {
     let (gizmos_config, _) = config_store.config_mut::<DefaultGizmoConfigGroup>();
     gizmos_config.enabled = config.basic.debug;
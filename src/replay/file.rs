@@ -0,0 +1,173 @@
+use crate::config::{parse_config, GameConfig};
+use crate::input::{Actions, InputFrame};
+use bevy::prelude::*;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// Everything needed to reproduce a run: the seed, the config it was played with
+/// and, for every frame the game was running, the time step, the player's input
+/// and a checksum of the resulting state.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    /// The [`GameConfig`] of the run, as TOML.
+    pub config: String,
+    pub ticks: Vec<ReplayTick>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReplayTick {
+    pub delta: Duration,
+    pub input: InputFrame,
+    pub checksum: u64,
+}
+
+/// Reads the little-endian fields of a replay file.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl Replay {
+    const MAGIC: &'static [u8; 4] = b"B2SR";
+    const VERSION: u16 = 1;
+    /// The magic, version, seed, config length and tick count.
+    const HEADER_SIZE: usize =
+        Self::MAGIC.len() + size_of::<u16>() + size_of::<u64>() + 2 * size_of::<u32>();
+    /// The delta, movement, cursor position, actions and checksum of a tick.
+    const TICK_SIZE: usize =
+        size_of::<u32>() + 4 * size_of::<f32>() + size_of::<u16>() + size_of::<u64>();
+
+    pub fn new(seed: u64, config: &GameConfig) -> Result<Self, toml::ser::Error> {
+        Ok(Self {
+            seed,
+            config: toml::to_string(config)?,
+            ticks: Vec::new(),
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+
+    /// The config the run was recorded with, migrated to the current version.
+    pub fn game_config(&self) -> Result<GameConfig, String> {
+        parse_config(&self.config).map(|(config, _)| config)
+    }
+
+    /// The game time covered by the first `ticks` ticks.
+    pub fn duration_of(&self, ticks: usize) -> Duration {
+        self.ticks.iter().take(ticks).map(|tick| tick.delta).sum()
+    }
+
+    /// The index of the tick running at `time` into the replay.
+    pub fn tick_at(&self, time: Duration) -> usize {
+        let mut elapsed = Duration::ZERO;
+        for (index, tick) in self.ticks.iter().enumerate() {
+            elapsed += tick.delta;
+            if elapsed > time {
+                return index;
+            }
+        }
+        self.ticks.len()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        // a minute at 60 fps is about 100 KiB
+        let mut bytes = Vec::with_capacity(
+            Self::HEADER_SIZE + self.config.len() + self.ticks.len() * Self::TICK_SIZE,
+        );
+        bytes.extend_from_slice(Self::MAGIC);
+        bytes.extend_from_slice(&Self::VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.config.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(self.config.as_bytes());
+        bytes.extend_from_slice(&(self.ticks.len() as u32).to_le_bytes());
+        for tick in &self.ticks {
+            // frame times are capped by `Time<Virtual>`, far below the four seconds a u32 holds
+            let delta = tick.delta.as_nanos().min(u32::MAX as u128) as u32;
+            bytes.extend_from_slice(&delta.to_le_bytes());
+            for value in [
+                tick.input.move_vector.x,
+                tick.input.move_vector.y,
+                tick.input.cursor_position.x,
+                tick.input.cursor_position.y,
+            ] {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&tick.input.actions.bits().to_le_bytes());
+            bytes.extend_from_slice(&tick.checksum.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut reader = Reader { bytes };
+        if reader.take(Self::MAGIC.len())? != Self::MAGIC {
+            return Err("not a replay file".into());
+        }
+        let version = reader.u16()?;
+        if version != Self::VERSION {
+            return Err(format!("unsupported replay version {}", version).into());
+        }
+        let seed = reader.u64()?;
+        let config_len = reader.u32()? as usize;
+        let config = String::from_utf8(reader.take(config_len)?.to_vec())?;
+        let tick_count = reader.u32()? as usize;
+        let mut ticks = Vec::with_capacity(tick_count.min(reader.bytes.len() / Self::TICK_SIZE));
+        for _ in 0..tick_count {
+            let delta = Duration::from_nanos(reader.u32()? as u64);
+            let move_vector = Vec2::new(reader.f32()?, reader.f32()?);
+            let cursor_position = Vec2::new(reader.f32()?, reader.f32()?);
+            let actions = Actions::from_bits(reader.u16()?);
+            let checksum = reader.u64()?;
+            ticks.push(ReplayTick {
+                delta,
+                input: InputFrame {
+                    move_vector,
+                    cursor_position,
+                    actions,
+                },
+                checksum,
+            });
+        }
+        Ok(Self {
+            seed,
+            config,
+            ticks,
+        })
+    }
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        if self.bytes.len() < len {
+            return Err("unexpected end of replay file".into());
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u16(&mut self) -> Result<u16, Box<dyn Error>> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into()?))
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, Box<dyn Error>> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into()?))
+    }
+
+    fn f32(&mut self) -> Result<f32, Box<dyn Error>> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+}
@@ -0,0 +1,514 @@
+use crate::config::GameConfig;
+use crate::input::recording::*;
+use crate::input::*;
+use crate::rng::*;
+use crate::state::*;
+use crate::world::bullet::Bullet;
+use crate::world::damage::Health;
+use crate::world::despawn::PostDespawn;
+use crate::world::enemy::Enemy;
+use crate::world::in_game::InGameScoped;
+use crate::world::player::Player;
+use avian2d::prelude::Position;
+use bevy::app::MainScheduleOrder;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::ecs::world::Command;
+use bevy::prelude::*;
+use bevy::time::{TimeSystem, TimeUpdateStrategy};
+use std::time::Duration;
+
+mod file;
+
+pub use file::*;
+
+/// The most ticks simulated in a single frame while fast-forwarding or seeking.
+const MAX_TICKS_PER_FRAME: usize = 240;
+const MAX_SPEED: usize = 16;
+/// How far the arrow keys seek.
+const SEEK_STEP: Duration = Duration::from_secs(5);
+
+/// Records the current run while present. Removed, and saved to
+/// `config.replay.path`, when the run ends.
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub replay: Replay,
+}
+
+/// The most recently finished recording.
+#[derive(Resource)]
+pub struct LastReplay(pub Replay);
+
+/// A replay to start once the main menu is reached, e.g. given on the command line.
+#[derive(Resource)]
+pub struct PendingReplay(pub Replay);
+
+/// Plays a replay back while present.
+///
+/// Replays are deterministic: every tick runs with the recorded time step and
+/// input, from the recorded seed and config. The current tick is the position of
+/// the [`InputPlayback`] the recorded input is fed through.
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    /// Ticks simulated per rendered frame.
+    pub speed: usize,
+    /// The tick to fast-forward to.
+    pub seek: Option<usize>,
+    /// The first tick whose state did not match the recording.
+    pub first_mismatch: Option<usize>,
+    pub finished: bool,
+    previous_config: Option<GameConfig>,
+    previous_input_source: InputSource,
+    previous_time_strategy: Option<TimeUpdateStrategy>,
+}
+
+/// Starts playing `replay`, from the main menu or by restarting the current run.
+pub struct StartReplay(pub Replay);
+
+#[derive(Default)]
+pub struct ReplayPlugin;
+
+impl ReplayPlayer {
+    pub fn total_ticks(&self) -> usize {
+        self.replay.ticks.len()
+    }
+}
+
+impl Command for StartReplay {
+    fn apply(self, world: &mut World) {
+        let config = match self.0.game_config() {
+            Ok(config) => config,
+            Err(err) => {
+                log::error!("Error loading replay config: {}", err);
+                return;
+            }
+        };
+        let recording = InputRecording {
+            frames: self.0.ticks.iter().map(|tick| tick.input).collect(),
+        };
+        world.resource_mut::<NextSeed>().0 = Some(self.0.seed);
+        let player = match world.remove_resource::<ReplayPlayer>() {
+            // a replay replacing another keeps what is restored afterwards
+            Some(previous) => ReplayPlayer {
+                replay: self.0,
+                speed: 1,
+                seek: None,
+                first_mismatch: None,
+                finished: false,
+                ..previous
+            },
+            None => ReplayPlayer {
+                replay: self.0,
+                speed: 1,
+                seek: None,
+                first_mismatch: None,
+                finished: false,
+                previous_config: world.remove_resource::<GameConfig>(),
+                previous_input_source: *world.resource::<InputSource>(),
+                previous_time_strategy: world.remove_resource::<TimeUpdateStrategy>(),
+            },
+        };
+        world.insert_resource(config);
+        world.insert_resource(player);
+        world.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        world.insert_resource(InputSource::Playback);
+        world.insert_resource(InputPlayback::new(recording));
+        world.remove_resource::<ReplayRecorder>();
+
+        let app_state = world
+            .get_resource::<State<AppState>>()
+            .map(|state| *state.get());
+        match app_state {
+            // the app has not started yet, its initial state starts the run
+            None => {}
+            Some(AppState::InGame) => restart_run(world),
+            Some(_) => {
+                world
+                    .resource_mut::<NextState<AppState>>()
+                    .set(AppState::InGame);
+                world
+                    .resource_mut::<NextState<GameState>>()
+                    .set(GameState::GameInit);
+            }
+        }
+        log::info!("Replay started");
+    }
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            First,
+            feed_replay_time
+                .before(TimeSystem)
+                .run_if(resource_exists::<ReplayPlayer>),
+        )
+        .add_systems(
+            OnEnter(GameState::GameInit),
+            start_recording.after(reseed_rng),
+        )
+        .add_systems(OnEnter(GameState::Running), resume_time)
+        .add_systems(
+            OnExit(GameState::Running),
+            pause_time.run_if(resource_exists::<ReplayRecorder>),
+        )
+        .add_systems(
+            OnEnter(GameState::GameOver),
+            finish_recording.run_if(resource_exists::<ReplayRecorder>),
+        )
+        .add_systems(
+            OnExit(AppState::InGame),
+            (
+                finish_recording.run_if(resource_exists::<ReplayRecorder>),
+                stop_replay.run_if(resource_exists::<ReplayPlayer>),
+                unpause_time,
+            ),
+        )
+        .add_systems(OnEnter(AppState::MainMenu), start_pending_replay)
+        .add_systems(
+            Update,
+            (
+                record_tick
+                    .in_set(InputSet::Observe)
+                    .run_if(resource_exists::<ReplayRecorder>),
+                control_replay
+                    .run_if(resource_exists::<ReplayPlayer>)
+                    .run_if(in_state(AppState::InGame)),
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            check_tick
+                .run_if(resource_exists::<ReplayRecorder>.or(resource_exists::<ReplayPlayer>))
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(
+            Last,
+            run_extra_ticks.run_if(resource_exists::<ReplayPlayer>),
+        );
+    }
+}
+
+fn start_recording(
+    mut commands: Commands,
+    rng: Res<GameRng>,
+    player: Option<Res<ReplayPlayer>>,
+    config: Res<GameConfig>,
+) {
+    if !config.replay.record || player.is_some() {
+        return;
+    }
+    match Replay::new(rng.seed(), &config) {
+        Ok(replay) => commands.insert_resource(ReplayRecorder { replay }),
+        Err(err) => log::error!("Error starting replay recording: {}", err),
+    }
+}
+
+fn finish_recording(
+    mut commands: Commands,
+    recorder: Res<ReplayRecorder>,
+    config: Res<GameConfig>,
+) {
+    let replay = recorder.replay.clone();
+    match replay.save(&config.replay.path) {
+        Ok(()) => log::info!("Replay saved to {}", config.replay.path),
+        Err(err) => log::error!("Error saving replay: {}", err),
+    }
+    commands.insert_resource(LastReplay(replay));
+    commands.remove_resource::<ReplayRecorder>();
+}
+
+fn start_pending_replay(mut commands: Commands, pending: Option<Res<PendingReplay>>) {
+    let Some(pending) = pending else {
+        return;
+    };
+    commands.queue(StartReplay(pending.0.clone()));
+    commands.remove_resource::<PendingReplay>();
+}
+
+fn stop_replay(world: &mut World) {
+    let Some(player) = world.remove_resource::<ReplayPlayer>() else {
+        return;
+    };
+    if let Some(config) = player.previous_config {
+        world.insert_resource(config);
+    }
+    match player.previous_time_strategy {
+        Some(strategy) => world.insert_resource(strategy),
+        None => world.insert_resource(TimeUpdateStrategy::Automatic),
+    }
+    world.insert_resource(player.previous_input_source);
+    world.remove_resource::<InputPlayback>();
+    log::info!("Replay stopped");
+}
+
+/// Starts the current run over, with the seed of the replay being played.
+fn restart_run(world: &mut World) {
+    let in_game_over = world
+        .get_resource::<State<GameState>>()
+        .is_some_and(|state| *state.get() == GameState::GameOver);
+    // leaving the game over screen already cleans up the run
+    if !in_game_over {
+        let roots: Vec<Entity> = world
+            .query_filtered::<Entity, (With<InGameScoped>, Without<Parent>)>()
+            .iter(world)
+            .collect();
+        for entity in roots {
+            world.entity_mut(entity).despawn_recursive();
+        }
+    }
+    let seed = world.get_resource_mut::<ReplayPlayer>().map(|mut player| {
+        player.first_mismatch = None;
+        player.finished = false;
+        player.replay.seed
+    });
+    if seed.is_some() {
+        world.resource_mut::<NextSeed>().0 = seed;
+    }
+    if let Some(mut playback) = world.get_resource_mut::<InputPlayback>() {
+        playback.next_frame = 0;
+    }
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameInit);
+}
+
+/// Makes the next frame advance time exactly as much as the next recorded tick did.
+fn feed_replay_time(
+    player: Res<ReplayPlayer>,
+    playback: Res<InputPlayback>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+) {
+    let delta = player
+        .replay
+        .ticks
+        .get(playback.next_frame)
+        .map_or(Duration::ZERO, |tick| tick.delta);
+    *strategy = TimeUpdateStrategy::ManualDuration(delta);
+}
+
+/// Time only passes for the simulation while the game is running, so that a
+/// recording does not depend on how long the game was paused.
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+/// Every stretch of running game starts with a clean fixed timestep, whatever
+/// time accumulated before it.
+fn resume_time(mut virtual_time: ResMut<Time<Virtual>>, mut fixed_time: ResMut<Time<Fixed>>) {
+    virtual_time.unpause();
+    let overstep = fixed_time.overstep();
+    fixed_time.discard_overstep(overstep);
+}
+
+fn record_tick(
+    mut recorder: ResMut<ReplayRecorder>,
+    move_vector: Res<MoveVector>,
+    cursor_position: Res<CursorPosition>,
+    action_state: Res<ActionState>,
+    time: Res<Time>,
+) {
+    recorder.replay.ticks.push(ReplayTick {
+        delta: time.delta(),
+        input: InputFrame::capture(&move_vector, &cursor_position, &action_state),
+        checksum: 0,
+    });
+}
+
+/// Stores the checksum of the tick that just ran when recording, or compares it with
+/// the recorded one when playing back.
+fn check_tick(
+    recorder: Option<ResMut<ReplayRecorder>>,
+    player: Option<ResMut<ReplayPlayer>>,
+    playback: Option<Res<InputPlayback>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_query: Query<(&Position, &Health), With<Player>>,
+    enemy_query: Query<(&Position, &Health), (With<Enemy>, Without<PostDespawn>)>,
+    bullet_query: Query<&Position, (With<Bullet>, Without<PostDespawn>)>,
+) {
+    let checksum = state_checksum(&player_query, &enemy_query, &bullet_query);
+
+    if let Some(mut recorder) = recorder {
+        if let Some(tick) = recorder.replay.ticks.last_mut() {
+            tick.checksum = checksum;
+        }
+        return;
+    }
+
+    let (Some(mut player), Some(playback)) = (player, playback) else {
+        return;
+    };
+    let Some(index) = playback.next_frame.checked_sub(1) else {
+        return;
+    };
+    if let Some(tick) = player.replay.ticks.get(index) {
+        if player.first_mismatch.is_none() && tick.checksum != checksum {
+            log::warn!("Replay diverged from the recording at tick {}", index);
+            player.first_mismatch = Some(index);
+        }
+    }
+    if !player.finished && playback.next_frame >= player.total_ticks() {
+        log::info!("Replay finished");
+        player.finished = true;
+        player.seek = None;
+        // the last tick may already have ended the run
+        if !matches!(*next_state, NextState::Pending(_)) {
+            next_state.set(GameState::Paused);
+        }
+    }
+}
+
+/// Hashes the parts of the game state that diverge first when a run does not
+/// replay exactly.
+fn state_checksum(
+    player_query: &Query<(&Position, &Health), With<Player>>,
+    enemy_query: &Query<(&Position, &Health), (With<Enemy>, Without<PostDespawn>)>,
+    bullet_query: &Query<&Position, (With<Bullet>, Without<PostDespawn>)>,
+) -> u64 {
+    // entities are combined by addition, so the order queries return them in does not matter
+    let mut checksum = 0u64;
+    for (position, health) in player_query.iter().chain(enemy_query.iter()) {
+        checksum = checksum.wrapping_add(hash_values(&[
+            position.x.to_bits(),
+            position.y.to_bits(),
+            health.current().to_bits(),
+        ]));
+    }
+    for position in bullet_query.iter() {
+        checksum =
+            checksum.wrapping_add(hash_values(&[position.x.to_bits(), position.y.to_bits()]));
+    }
+    let counts = hash_values(&[
+        enemy_query.iter().len() as u32,
+        bullet_query.iter().len() as u32,
+    ]);
+    checksum ^ counts
+}
+
+/// FNV-1a.
+fn hash_values(values: &[u32]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    for value in values {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+fn control_replay(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut player: ResMut<ReplayPlayer>,
+    playback: Res<InputPlayback>,
+    game_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let current = playback.next_frame;
+    let total = player.total_ticks();
+
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        match game_state.get() {
+            GameState::Running => next_state.set(GameState::Paused),
+            GameState::Paused if !player.finished => next_state.set(GameState::Running),
+            _ => {}
+        }
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        player.speed = (player.speed * 2).min(MAX_SPEED);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        player.speed = (player.speed / 2).max(1);
+    }
+
+    let digits = [
+        KeyCode::Digit0,
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+        KeyCode::Digit5,
+        KeyCode::Digit6,
+        KeyCode::Digit7,
+        KeyCode::Digit8,
+        KeyCode::Digit9,
+    ];
+    let mut target = digits
+        .iter()
+        .position(|key| keyboard_input.just_pressed(*key))
+        .map(|digit| total * digit / 10);
+    if keyboard_input.just_pressed(KeyCode::Home) {
+        target = Some(0);
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowRight) {
+        let time = player.replay.duration_of(current) + SEEK_STEP;
+        target = Some(player.replay.tick_at(time));
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowLeft) {
+        let time = player.replay.duration_of(current).saturating_sub(SEEK_STEP);
+        target = Some(player.replay.tick_at(time));
+    }
+    let Some(target) = target else {
+        return;
+    };
+
+    // ticks can only be simulated forwards, seeking back replays from the start
+    if target < current || *game_state.get() == GameState::GameOver {
+        commands.queue(restart_run);
+    } else {
+        next_state.set(GameState::Running);
+    }
+    player.seek = (target > 0).then_some(target.min(total));
+}
+
+/// Simulates additional ticks within this frame to fast-forward or seek. Only the
+/// last tick of a frame is rendered.
+fn run_extra_ticks(world: &mut World) {
+    let labels: Vec<_> = world
+        .resource::<MainScheduleOrder>()
+        .labels
+        .iter()
+        .copied()
+        .filter(|label| *label != Last.intern())
+        .collect();
+
+    for extra in 0..MAX_TICKS_PER_FRAME {
+        let running = world
+            .get_resource::<State<GameState>>()
+            .is_some_and(|state| *state.get() == GameState::Running);
+        let (Some(player), Some(playback)) = (
+            world.get_resource::<ReplayPlayer>(),
+            world.get_resource::<InputPlayback>(),
+        ) else {
+            return;
+        };
+        let wanted = match player.seek {
+            Some(target) => playback.next_frame < target,
+            None => extra + 1 < player.speed,
+        };
+        if !running || player.finished || !wanted {
+            break;
+        }
+        for label in &labels {
+            let _ = world.try_run_schedule(*label);
+        }
+    }
+
+    let reached_seek = match (
+        world.get_resource::<ReplayPlayer>(),
+        world.get_resource::<InputPlayback>(),
+    ) {
+        (Some(player), Some(playback)) => player
+            .seek
+            .is_some_and(|target| playback.next_frame >= target),
+        _ => false,
+    };
+    if reached_seek {
+        world.resource_mut::<ReplayPlayer>().seek = None;
+    }
+}
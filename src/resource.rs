@@ -1,4 +1,10 @@
-use crate::state::AppState;
+use crate::animation::Animator;
+use crate::audio::SoundLibrary;
+use crate::sprite_manifest::*;
+use crate::state::{AfterLoading, AppState};
+use crate::world::lighting::LIGHTING_SHADER;
+use crate::world::water::WATER_SHADER;
+use bevy::asset::{LoadState, UntypedAssetId};
 use bevy::prelude::*;
 
 #[derive(Resource)]
@@ -10,6 +16,7 @@ pub struct TileSet(pub SpriteSheet);
 pub struct SpriteSheet {
     pub layout: Handle<TextureAtlasLayout>,
     pub image: Handle<Image>,
+    pub manifest: SheetManifest,
 }
 
 #[derive(Resource)]
@@ -17,9 +24,75 @@ pub struct GlobalFont {
     pub handle: Handle<Font>,
 }
 
+/// An asset loaded during [`AppState::Loading`].
+pub struct TrackedAsset {
+    pub path: String,
+    pub handle: UntypedHandle,
+}
+
+/// The assets [`AppState::Loading`] waits for. The game does not start if one fails.
+/// Kept after loading, so assets not in use yet like the water shader stay loaded.
+#[derive(Resource, Default)]
+pub struct LoadingAssets(pub Vec<TrackedAsset>);
+
+/// How far loading got, updated every frame of [`AppState::Loading`].
+/// Sounds are waited for too, but a missing sound only leaves it silent.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct LoadingProgress {
+    /// Assets that finished loading, or failed without stopping the game.
+    pub done: usize,
+    pub total: usize,
+    /// The path of the first asset that failed to load and stops the game, with the error.
+    pub failed: Option<(String, String)>,
+}
+
+/// Sheet images waiting for the manifest that says how to cut them.
+#[derive(Resource)]
+struct PendingSheets {
+    manifest: Handle<SpriteManifest>,
+    global: Handle<Image>,
+    tiles: Handle<Image>,
+}
+
 pub struct ResourcePlugin;
 
 impl SpriteSheet {
+    /// A sheet with empty handles, for when nothing is rendered.
+    pub fn placeholder(manifest: SheetManifest) -> Self {
+        Self {
+            layout: Handle::default(),
+            image: Handle::default(),
+            manifest,
+        }
+    }
+
+    /// The sprite named `name` in the manifest.
+    pub fn sprite(&self, name: &str) -> Sprite {
+        self.to_sprite(self.manifest.index(name))
+    }
+
+    /// The image node of the sprite named `name` in the manifest.
+    pub fn image_node(&self, name: &str) -> ImageNode {
+        self.to_image_node(self.manifest.index(name))
+    }
+
+    /// The clip named `name` in the manifest.
+    pub fn clip(&self, name: &str) -> SpriteClip {
+        self.manifest.clip(name)
+    }
+
+    /// The first frame of the clip named `name`, with an animator that plays it.
+    pub fn animated_sprite(&self, name: &str) -> impl Bundle {
+        let clip = self.clip(name);
+        (self.to_sprite(clip.first), Animator::new(clip))
+    }
+
+    /// A sprite with an animator for the clips of a character, see [`Animator::from_manifest`].
+    pub fn character_sprite(&self, prefix: &str) -> impl Bundle {
+        let animator = Animator::from_manifest(&self.manifest, prefix);
+        (self.to_sprite(animator.index()), animator)
+    }
+
     pub fn to_sprite(&self, index: usize) -> Sprite {
         Sprite::from_atlas_image(
             self.image.clone(),
@@ -41,30 +114,127 @@ impl SpriteSheet {
     }
 }
 
+impl LoadingAssets {
+    /// Starts loading the asset at `path` and waits for it while loading.
+    pub fn load<A: Asset>(&mut self, asset_server: &AssetServer, path: &str) -> Handle<A> {
+        let handle = asset_server.load(path.to_string());
+        self.0.push(TrackedAsset {
+            path: path.to_string(),
+            handle: handle.clone().untyped(),
+        });
+        handle
+    }
+}
+
+impl LoadingProgress {
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.done as f32 / self.total as f32
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done == self.total && self.failed.is_none()
+    }
+}
+
 impl Plugin for ResourcePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::Loading), load_assets);
+        app.init_asset::<SpriteManifest>()
+            .init_asset_loader::<SpriteManifestLoader>()
+            .init_resource::<LoadingProgress>()
+            .add_systems(OnEnter(AppState::Loading), load_assets)
+            .add_systems(
+                Update,
+                (
+                    insert_sprite_sheets.run_if(resource_exists::<PendingSheets>),
+                    update_loading_progress.run_if(resource_exists::<LoadingAssets>),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Loading)),
+            );
     }
 }
 
-fn load_assets(
+fn load_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let mut loading = LoadingAssets::default();
+    commands.insert_resource(PendingSheets {
+        manifest: loading.load(&asset_server, "sprites.manifest.toml"),
+        global: loading.load(&asset_server, "sprites.png"),
+        tiles: loading.load(&asset_server, "tiles.png"),
+    });
+    commands.insert_resource(GlobalFont {
+        handle: loading.load(&asset_server, "monogram.ttf"),
+    });
+    loading.load::<Shader>(&asset_server, WATER_SHADER);
+    loading.load::<Shader>(&asset_server, LIGHTING_SHADER);
+    commands.insert_resource(loading);
+}
+
+/// Cuts the sheets once their manifest is loaded.
+fn insert_sprite_sheets(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
+    pending: Res<PendingSheets>,
+    manifests: Res<Assets<SpriteManifest>>,
     mut layouts_assets: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let Some(manifest) = manifests.get(&pending.manifest) else {
+        return;
+    };
+    let mut sheet = |image: &Handle<Image>, manifest: &SheetManifest| SpriteSheet {
+        layout: layouts_assets.add(manifest.layout()),
+        image: image.clone(),
+        manifest: manifest.clone(),
+    };
+    commands.insert_resource(GlobalSpriteSheet(sheet(&pending.global, &manifest.global)));
+    commands.insert_resource(TileSet(sheet(&pending.tiles, &manifest.tiles)));
+    commands.remove_resource::<PendingSheets>();
+}
+
+fn load_failure(asset_server: &AssetServer, id: impl Into<UntypedAssetId>) -> Option<String> {
+    match asset_server.get_load_state(id) {
+        Some(LoadState::Failed(err)) => Some(err.to_string()),
+        _ => None,
+    }
+}
+
+/// Moves on once every asset is loaded, or stops at the first one that failed.
+fn update_loading_progress(
+    asset_server: Res<AssetServer>,
+    loading: Res<LoadingAssets>,
+    sounds: Option<Res<SoundLibrary>>,
+    mut progress: ResMut<LoadingProgress>,
+    after_loading: Res<AfterLoading>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
-    let layout = TextureAtlasLayout::from_grid(UVec2::splat(16), 8, 8, None, None);
-    let layout_handle = layouts_assets.add(layout);
-    commands.insert_resource(GlobalSpriteSheet(SpriteSheet {
-        layout: layout_handle.clone(),
-        image: asset_server.load("sprites.png"),
-    }));
-    commands.insert_resource(TileSet(SpriteSheet {
-        layout: layout_handle,
-        image: asset_server.load("tiles.png"),
-    }));
-    commands.insert_resource(GlobalFont {
-        handle: asset_server.load("monogram.ttf"),
-    });
-    next_state.set(AppState::MainMenu);
+    let mut next = LoadingProgress::default();
+    for asset in &loading.0 {
+        next.total += 1;
+        if asset_server.is_loaded_with_dependencies(asset.handle.id()) {
+            next.done += 1;
+        } else if let Some(err) = load_failure(&asset_server, asset.handle.id()) {
+            next.failed.get_or_insert((asset.path.clone(), err));
+        }
+    }
+    let sounds = sounds.iter().flat_map(|sounds| sounds.0.values());
+    let mut missing_sounds = Vec::new();
+    for handle in sounds {
+        next.total += 1;
+        if asset_server.is_loaded_with_dependencies(handle.id()) {
+            next.done += 1;
+        } else if let Some(err) = load_failure(&asset_server, handle.id()) {
+            next.done += 1;
+            missing_sounds.push(err);
+        }
+    }
+    progress.set_if_neq(next);
+
+    if progress.is_finished() {
+        for err in missing_sounds {
+            log::warn!("Sound not loaded, it stays silent: {}", err);
+        }
+        next_state.set(after_loading.0);
+    }
 }
@@ -0,0 +1,106 @@
+use crate::state::GameState;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Error, Rng, RngCore, SeedableRng};
+
+/// Random numbers for everything that affects the simulation. Reseeded at the start
+/// of every run, so a run can be reproduced from its seed.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+/// Random numbers for purely visual effects. Kept apart from [`GameRng`] so that
+/// whether effects are shown, e.g. in headless runs, never changes the simulation.
+#[derive(Resource)]
+pub struct CosmeticRng(StdRng);
+
+/// The seed for the next run. A random one is picked when empty.
+#[derive(Resource, Default)]
+pub struct NextSeed(pub Option<u64>);
+
+#[derive(Default)]
+pub struct RngPlugin;
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl CosmeticRng {
+    pub fn new(seed: u64) -> Self {
+        // a different stream than the game's, but still derived from the seed
+        Self(StdRng::seed_from_u64(seed ^ 0x9e37_79b9_7f4a_7c15))
+    }
+}
+
+impl RngCore for CosmeticRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.0.try_fill_bytes(dest)
+    }
+}
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = rand::thread_rng().gen();
+        app.insert_resource(GameRng::new(seed))
+            .insert_resource(CosmeticRng::new(seed))
+            .init_resource::<NextSeed>()
+            .add_systems(OnEnter(GameState::GameInit), reseed_rng);
+    }
+}
+
+/// Starts the random streams of a new run. Systems spawning the world on
+/// `OnEnter(GameState::GameInit)` must run after this one.
+pub fn reseed_rng(
+    mut next_seed: ResMut<NextSeed>,
+    mut game_rng: ResMut<GameRng>,
+    mut cosmetic_rng: ResMut<CosmeticRng>,
+) {
+    let seed = next_seed
+        .0
+        .take()
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    log::info!("Run seed: {}", seed);
+    *game_rng = GameRng::new(seed);
+    *cosmetic_rng = CosmeticRng::new(seed);
+}
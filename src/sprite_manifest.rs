@@ -0,0 +1,231 @@
+use crate::animation::PlayMode;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// The manifest shipped in `assets/`, for runs that do not load assets.
+const BUILTIN_MANIFEST: &str = include_str!("../assets/sprites.manifest.toml");
+
+/// Names of the sprites looked up by the game, see `assets/sprites.manifest.toml`.
+pub mod sprites {
+    pub const BULLET: &str = "bullet";
+    pub const GUN: &str = "gun";
+    pub const MELEE: &str = "melee";
+    pub const GRASS: [&str; 2] = ["grass_1", "grass_2"];
+    pub const BAR_BACKGROUND: &str = "bar_background";
+    pub const BAR_FILL: &str = "bar_fill";
+
+    pub const REQUIRED: &[&str] = &[
+        BULLET,
+        GUN,
+        MELEE,
+        GRASS[0],
+        GRASS[1],
+        BAR_BACKGROUND,
+        BAR_FILL,
+    ];
+}
+
+/// Names of the animation clips looked up by the game.
+pub mod clips {
+    /// Prefix of the player's clips, `player_idle`, `player_run` and so on.
+    pub const PLAYER: &str = "player";
+    /// Prefixes of the clips of each kind of enemy.
+    pub const ENEMIES: [&str; 4] = ["enemy_1", "enemy_2", "enemy_3", "enemy_4"];
+    pub const EXPLOSION: &str = "explosion";
+
+    /// Clips for the other states of a prefix are optional, they fall back to idle.
+    pub const REQUIRED: &[&str] = &[
+        "player_idle",
+        "player_run",
+        "enemy_1_idle",
+        "enemy_2_idle",
+        "enemy_3_idle",
+        "enemy_4_idle",
+        EXPLOSION,
+    ];
+}
+
+/// Names of the map tiles.
+pub mod tiles {
+    pub const GRASS: &str = "grass";
+    pub const SAND: &str = "sand";
+    pub const WATER: &str = "water";
+
+    pub const REQUIRED: &[&str] = &[GRASS, SAND, WATER];
+}
+
+/// Named regions of both sprite sheets, loaded from `sprites.manifest.toml`.
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct SpriteManifest {
+    /// `sprites.png`
+    pub global: SheetManifest,
+    /// `tiles.png`
+    pub tiles: SheetManifest,
+}
+
+/// The grid a sheet is cut into and the names of its cells.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SheetManifest {
+    pub tile_size: u32,
+    pub columns: u32,
+    pub rows: u32,
+    #[serde(default)]
+    pub sprites: BTreeMap<String, usize>,
+    #[serde(default)]
+    pub clips: BTreeMap<String, SpriteClip>,
+}
+
+/// `length` cells of a sheet starting at `first`, played in order.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SpriteClip {
+    pub first: usize,
+    pub length: usize,
+    pub frame_duration: f32,
+    #[serde(default)]
+    pub mode: PlayMode,
+}
+
+#[derive(Default)]
+pub struct SpriteManifestLoader;
+
+impl SpriteManifest {
+    /// Parses a manifest and checks that it has every name the game looks up,
+    /// each pointing inside its sheet.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let manifest: Self = toml::from_str(text).map_err(|err| err.to_string())?;
+        let mut errors = Vec::new();
+        manifest
+            .global
+            .check("global", sprites::REQUIRED, clips::REQUIRED, &mut errors);
+        manifest
+            .tiles
+            .check("tiles", tiles::REQUIRED, &[], &mut errors);
+        if errors.is_empty() {
+            Ok(manifest)
+        } else {
+            Err(errors.join("\n"))
+        }
+    }
+
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_MANIFEST).expect("the built-in sprite manifest is valid")
+    }
+}
+
+impl SheetManifest {
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            UVec2::splat(self.tile_size),
+            self.columns,
+            self.rows,
+            None,
+            None,
+        )
+    }
+
+    /// The cell of the sprite named `name`, or the first cell if there is none.
+    pub fn index(&self, name: &str) -> usize {
+        self.sprites.get(name).copied().unwrap_or_else(|| {
+            log::warn!("No sprite named `{}` in the sprite manifest", name);
+            0
+        })
+    }
+
+    /// The clip named `name`, or a single frame of the first cell if there is none.
+    pub fn clip(&self, name: &str) -> SpriteClip {
+        self.clips.get(name).copied().unwrap_or_else(|| {
+            log::warn!("No clip named `{}` in the sprite manifest", name);
+            SpriteClip {
+                first: 0,
+                length: 1,
+                frame_duration: 1.0,
+                mode: PlayMode::Loop,
+            }
+        })
+    }
+
+    /// The frames of the sprite or clip named `name`: a sprite is a clip of one frame.
+    pub fn frames(&self, name: &str) -> Option<SpriteClip> {
+        let sprite = self.sprites.get(name).map(|index| SpriteClip {
+            first: *index,
+            length: 1,
+            frame_duration: 1.0,
+            mode: PlayMode::Loop,
+        });
+        sprite.or_else(|| self.clips.get(name).copied())
+    }
+
+    fn check(&self, sheet: &str, sprites: &[&str], clips: &[&str], errors: &mut Vec<String>) {
+        if self.tile_size == 0 || self.columns == 0 || self.rows == 0 {
+            errors.push(format!(
+                "{sheet}: `tile_size`, `columns` and `rows` must be greater than 0"
+            ));
+        }
+        let cells = (self.columns * self.rows) as usize;
+        for name in sprites {
+            if !self.sprites.contains_key(*name) {
+                errors.push(format!("{sheet}.sprites: missing `{name}`"));
+            }
+        }
+        for name in clips {
+            if !self.clips.contains_key(*name) {
+                errors.push(format!("{sheet}.clips: missing `{name}`"));
+            }
+        }
+        for (name, index) in &self.sprites {
+            if *index >= cells {
+                errors.push(format!(
+                    "{sheet}.sprites.{name}: {index} is outside the {cells} cells of the sheet"
+                ));
+            }
+        }
+        for (name, clip) in &self.clips {
+            if clip.length == 0 {
+                errors.push(format!(
+                    "{sheet}.clips.{name}: `length` must be greater than 0"
+                ));
+            } else if clip.first + clip.length > cells {
+                errors.push(format!(
+                    "{sheet}.clips.{name}: ends outside the {cells} cells of the sheet"
+                ));
+            }
+            if clip.frame_duration.is_nan() || clip.frame_duration <= 0.0 {
+                errors.push(format!(
+                    "{sheet}.clips.{name}: `frame_duration` must be greater than 0"
+                ));
+            }
+        }
+    }
+}
+
+impl SpriteClip {
+    /// How long one pass through the clip takes.
+    pub fn duration(&self) -> f32 {
+        self.frame_duration * self.length as f32
+    }
+}
+
+impl AssetLoader for SpriteManifestLoader {
+    type Asset = SpriteManifest;
+    type Settings = ();
+    type Error = Box<dyn Error + Send + Sync>;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(SpriteManifest::parse(std::str::from_utf8(&bytes)?)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["manifest.toml"]
+    }
+}
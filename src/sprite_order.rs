@@ -1,21 +1,29 @@
 pub enum SpriteOrder {
     MAP,
+    Water,
     GRASS,
+    Corpse,
     Player,
     Gun,
     Bullet,
     Enemy,
+    Explosion,
+    Particle,
 }
 
 impl SpriteOrder {
     pub fn z_index(&self) -> f32 {
         match self {
             SpriteOrder::MAP => 0.0,
+            SpriteOrder::Water => 0.2,
             SpriteOrder::GRASS => 0.5,
+            SpriteOrder::Corpse => 0.6,
             SpriteOrder::Player => 2.0,
             SpriteOrder::Gun => 0.1,
             SpriteOrder::Bullet => 1.0,
             SpriteOrder::Enemy => 1.0,
+            SpriteOrder::Explosion => 1.5,
+            SpriteOrder::Particle => 2.5,
         }
     }
 }
@@ -17,3 +17,29 @@ pub enum GameState {
     Paused,
     GameOver,
 }
+
+/// The state entered once [`AppState::Loading`] is done.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AfterLoading(pub AppState);
+
+/// Sets up [`AppState`] and [`GameState`], starting from `initial_state`.
+#[derive(Default)]
+pub struct GameStatePlugin {
+    pub initial_state: AppState,
+}
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AfterLoading>()
+            .insert_state(self.initial_state)
+            .add_sub_state::<GameState>()
+            .enable_state_scoped_entities::<AppState>()
+            .enable_state_scoped_entities::<GameState>();
+    }
+}
+
+impl Default for AfterLoading {
+    fn default() -> Self {
+        Self(AppState::MainMenu)
+    }
+}
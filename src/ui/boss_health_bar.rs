@@ -0,0 +1,119 @@
+use crate::resource::*;
+use crate::sprite_manifest::sprites;
+use crate::ui::bar::*;
+use crate::ui::util::text;
+use crate::world::boss::Boss;
+use crate::world::damage::Health;
+use bevy::prelude::*;
+use bevy::ui::widget::NodeImageMode;
+
+const BOSS_HEALTH_BAR_BORDER: f32 = 6.0;
+const BOSS_HEALTH_BAR_WIDTH: f32 = 700.0;
+
+/// The container of the boss health bar, only displayed while a boss is alive.
+#[derive(Component)]
+pub struct BossHealthBarPanel;
+
+#[derive(Component)]
+pub struct BossHealthBar;
+
+pub fn update_boss_health_bar(
+    boss_query: Query<&Health, With<Boss>>,
+    mut panel_query: Query<&mut Node, With<BossHealthBarPanel>>,
+    mut bar_query: Query<&mut BarTargetWidth, With<BossHealthBar>>,
+) {
+    let boss_health = boss_query.get_single().ok();
+    for mut node in panel_query.iter_mut() {
+        node.display = if boss_health.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    let Some(boss_health) = boss_health else {
+        return;
+    };
+    for mut bar_target in bar_query.iter_mut() {
+        bar_target.set_target(boss_health.current() / boss_health.max());
+    }
+}
+
+pub fn spawn_boss_health_bar(
+    mut commands: Commands,
+    sheet: Res<GlobalSpriteSheet>,
+    font: Res<GlobalFont>,
+) {
+    let slicer = TextureSlicer {
+        border: BorderRect::square(2.0),
+        center_scale_mode: SliceScaleMode::Stretch,
+        sides_scale_mode: SliceScaleMode::Stretch,
+        max_corner_scale: 10.0,
+    };
+    commands
+        .spawn((
+            crate::ui::hud::Hud,
+            Visibility::Hidden,
+            Node {
+                width: Val::Percent(100.0),
+                top: Val::Px(10.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    BossHealthBarPanel,
+                    Node {
+                        display: Display::None,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(text(font.handle.clone(), "Boss", 40.0));
+                    parent
+                        .spawn((
+                            Node {
+                                border: UiRect::all(Val::Px(BOSS_HEALTH_BAR_BORDER)),
+                                width: Val::Px(
+                                    BOSS_HEALTH_BAR_WIDTH + BOSS_HEALTH_BAR_BORDER * 2.0,
+                                ),
+                                height: Val::Px(30.0),
+                                ..default()
+                            },
+                            sheet
+                                .0
+                                .image_node(sprites::BAR_BACKGROUND)
+                                .with_mode(NodeImageMode::Sliced(slicer.clone())),
+                        ))
+                        .with_children(|parent| {
+                            parent
+                                .spawn((
+                                    sheet
+                                        .0
+                                        .image_node(sprites::BAR_FILL)
+                                        .with_color(Color::srgb(0.6, 0.1, 0.6).with_alpha(0.6))
+                                        .with_mode(NodeImageMode::Sliced(slicer.clone())),
+                                    BarWidth::new(BOSS_HEALTH_BAR_WIDTH),
+                                    BarTargetWidth::Background(1.0),
+                                    BossHealthBar,
+                                ))
+                                .with_children(|parent| {
+                                    parent.spawn((
+                                        sheet
+                                            .0
+                                            .image_node(sprites::BAR_FILL)
+                                            .with_color(Color::srgb(0.7, 0.2, 0.9))
+                                            .with_mode(NodeImageMode::Sliced(slicer.clone())),
+                                        BarWidth::new(BOSS_HEALTH_BAR_WIDTH),
+                                        BarTargetWidth::Foreground(1.0),
+                                        BossHealthBar,
+                                    ));
+                                });
+                        });
+                });
+        });
+}
@@ -0,0 +1,57 @@
+use crate::config::ConfigError;
+use crate::resource::GlobalFont;
+use crate::ui::util::text;
+use bevy::prelude::*;
+
+/// Shows the errors in the config file, until a reload succeeds.
+#[derive(Component)]
+pub struct ConfigErrorBanner;
+
+#[derive(Default)]
+pub struct ConfigErrorPlugin;
+
+impl Plugin for ConfigErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_config_error_banner.run_if(
+                resource_exists::<GlobalFont>
+                    .and(resource_exists::<ConfigError>)
+                    .and(resource_changed::<ConfigError>),
+            ),
+        );
+    }
+}
+
+fn update_config_error_banner(
+    mut commands: Commands,
+    banner_query: Query<Entity, With<ConfigErrorBanner>>,
+    error: Res<ConfigError>,
+    font: Res<GlobalFont>,
+) {
+    for entity in banner_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    let Some(message) = &error.0 else {
+        return;
+    };
+    commands
+        .spawn((
+            ConfigErrorBanner,
+            Node {
+                width: Val::Percent(100.0),
+                top: Val::Px(0.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.5, 0.0, 0.0, 0.85)),
+            // above menus and the HUD
+            GlobalZIndex(100),
+        ))
+        .with_children(|parent| {
+            parent.spawn(text(font.handle.clone(), "Config error", 24.0));
+            parent.spawn(text(font.handle.clone(), message.clone(), 20.0));
+        });
+}
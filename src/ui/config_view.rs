@@ -0,0 +1,142 @@
+use crate::config::{ConfigLayers, GameConfig};
+use crate::input::{action_just_pressed, Action};
+use crate::resource::GlobalFont;
+use crate::ui::util::text;
+use bevy::input::mouse::{MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+/// Lists every value of the effective config and the layer that set it.
+/// Toggled with the `config_view` binding (F1) while `basic.debug` is on.
+#[derive(Component)]
+pub struct ConfigView;
+
+#[derive(Component)]
+pub struct ConfigViewText;
+
+#[derive(Default)]
+pub struct ConfigViewPlugin;
+
+impl Plugin for ConfigViewPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_config_view.run_if(action_just_pressed(Action::ConfigView)),
+                update_config_view,
+                scroll_config_view,
+            )
+                .chain()
+                .run_if(resource_exists::<GlobalFont>.and(resource_exists::<ConfigLayers>)),
+        );
+    }
+}
+
+fn toggle_config_view(
+    mut commands: Commands,
+    view_query: Query<Entity, With<ConfigView>>,
+    font: Res<GlobalFont>,
+    config: Res<GameConfig>,
+    layers: Res<ConfigLayers>,
+) {
+    if let Ok(entity) = view_query.get_single() {
+        commands.entity(entity).despawn_recursive();
+        return;
+    }
+    if !config.basic.debug {
+        return;
+    }
+    commands
+        .spawn((
+            ConfigView,
+            Node {
+                width: Val::Px(560.0),
+                height: Val::Percent(90.0),
+                top: Val::Px(10.0),
+                right: Val::Px(10.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(8.0)),
+                overflow: Overflow::scroll_y(),
+                ..default()
+            },
+            BackgroundColor::from(Color::BLACK.with_alpha(0.9)),
+            GlobalZIndex(90),
+        ))
+        .with_child((
+            text(font.handle.clone(), describe(&config, &layers), 16.0),
+            ConfigViewText,
+        ));
+}
+
+fn update_config_view(
+    mut commands: Commands,
+    view_query: Query<Entity, With<ConfigView>>,
+    mut text_query: Query<&mut Text, With<ConfigViewText>>,
+    config: Res<GameConfig>,
+    layers: Res<ConfigLayers>,
+) {
+    if !config.is_changed() && !layers.is_changed() {
+        return;
+    }
+    if !config.basic.debug {
+        for entity in view_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        return;
+    }
+    for mut text in text_query.iter_mut() {
+        **text = describe(&config, &layers);
+    }
+}
+
+fn scroll_config_view(
+    mut wheel_events: EventReader<MouseWheel>,
+    mut view_query: Query<&mut ScrollPosition, With<ConfigView>>,
+) {
+    for event in wheel_events.read() {
+        let lines = match event.unit {
+            MouseScrollUnit::Line => event.y * 20.0,
+            MouseScrollUnit::Pixel => event.y,
+        };
+        for mut scroll in view_query.iter_mut() {
+            scroll.offset_y = (scroll.offset_y - lines).max(0.0);
+        }
+    }
+}
+
+/// One `key = value` line per config value, followed by the layer that set it.
+fn describe(config: &GameConfig, layers: &ConfigLayers) -> String {
+    let mut lines = Vec::new();
+    if let Ok(root) = toml::Value::try_from(config) {
+        list_values("", &root, layers, &mut lines);
+    }
+    lines.join("\n")
+}
+
+fn list_values(path: &str, value: &toml::Value, layers: &ConfigLayers, lines: &mut Vec<String>) {
+    let child = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match value {
+        toml::Value::Table(table) => {
+            for (key, value) in table {
+                list_values(&child(key), value, layers, lines);
+            }
+        }
+        toml::Value::Array(items)
+            if !items.is_empty() && items.iter().all(toml::Value::is_table) =>
+        {
+            for (index, item) in items.iter().enumerate() {
+                list_values(&child(&index.to_string()), item, layers, lines);
+            }
+        }
+        _ => match layers.origin(path) {
+            Some(origin) => lines.push(format!("{path} = {value}  [{origin}]")),
+            None => lines.push(format!("{path} = {value}")),
+        },
+    }
+}
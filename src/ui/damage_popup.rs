@@ -1,4 +1,5 @@
 use crate::resource::GlobalFont;
+use crate::rng::CosmeticRng;
 use crate::ui::popup::popup_text;
 use crate::world::damage::DamageEvent;
 use crate::world::enemy::Enemy;
@@ -7,6 +8,7 @@ use bevy::prelude::*;
 pub fn on_enemy_damaged(
     mut commands: Commands,
     font: Res<GlobalFont>,
+    mut rng: ResMut<CosmeticRng>,
     mut event_reader: EventReader<DamageEvent>,
     mut enemy_query: Query<&GlobalTransform, With<Enemy>>,
 ) {
@@ -22,6 +24,7 @@ pub fn on_enemy_damaged(
                 event.context.damage.to_string(),
                 transform.compute_transform(),
                 TextColor(Color::srgb(0.8, 0.1, 0.1)),
+                &mut rng,
             );
         }
     }
@@ -1,4 +1,5 @@
 use crate::state::*;
+use crate::ui::boss_health_bar::*;
 use crate::ui::damage_popup::on_enemy_damaged;
 use crate::ui::debug_panel::*;
 use crate::ui::player_health_bar::*;
@@ -21,7 +22,11 @@ impl Plugin for HudPlugin {
         app.add_plugins(FrameTimeDiagnosticsPlugin)
             .add_systems(
                 OnEnter(GameState::GameInit),
-                (spawn_debug_panel, spawn_player_health_bar),
+                (
+                    spawn_debug_panel,
+                    spawn_player_health_bar,
+                    spawn_boss_health_bar,
+                ),
             )
             .add_systems(OnEnter(GameState::Running), show_hud)
             .add_systems(OnEnter(GameState::GameOver), hide_hud)
@@ -30,6 +35,7 @@ impl Plugin for HudPlugin {
                 Update,
                 (
                     update_player_health_bar,
+                    update_boss_health_bar,
                     update_debug_texts.run_if(on_timer(Duration::from_secs_f32(0.2))),
                 )
                     .run_if(in_state(GameState::Running)),
@@ -0,0 +1,139 @@
+use crate::resource::LoadingProgress;
+use crate::state::AppState;
+use crate::ui::util::*;
+use bevy::prelude::*;
+use bevy_button_released_plugin::OnButtonReleased;
+
+const BAR_WIDTH: f32 = 400.0;
+const BAR_HEIGHT: f32 = 24.0;
+
+/// Shows how far loading got, or which asset failed to load.
+/// Texts use Bevy's built-in font, as the game's font may be the asset that failed.
+#[derive(Default)]
+pub struct LoadingScreenPlugin;
+
+#[derive(Component)]
+pub struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingBarFill;
+
+#[derive(Component)]
+struct LoadingLabel;
+
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::Loading), spawn_loading_screen)
+            .add_systems(
+                Update,
+                (
+                    update_loading_screen,
+                    show_loading_error
+                        .run_if(|progress: Res<LoadingProgress>| progress.failed.is_some()),
+                )
+                    .chain()
+                    .run_if(in_state(AppState::Loading).and(resource_changed::<LoadingProgress>)),
+            );
+    }
+}
+
+fn screen_node() -> impl Bundle {
+    (
+        LoadingScreen,
+        StateScoped(AppState::Loading),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(20.0),
+            ..default()
+        },
+    )
+}
+
+fn spawn_loading_screen(mut commands: Commands) {
+    commands.spawn(screen_node()).with_children(|parent| {
+        parent.spawn(text(Handle::default(), "Loading...", 48.0));
+        parent
+            .spawn((
+                Node {
+                    width: Val::Px(BAR_WIDTH),
+                    height: Val::Px(BAR_HEIGHT),
+                    ..default()
+                },
+                BackgroundColor(NORMAL_BUTTON_BG_COLOR),
+            ))
+            .with_child((
+                LoadingBarFill,
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.9, 0.9, 0.9)),
+            ));
+        parent.spawn((text(Handle::default(), "", 20.0), LoadingLabel));
+    });
+}
+
+fn update_loading_screen(
+    mut fill_query: Query<&mut Node, With<LoadingBarFill>>,
+    mut label_query: Query<&mut Text, With<LoadingLabel>>,
+    progress: Res<LoadingProgress>,
+) {
+    for mut node in fill_query.iter_mut() {
+        node.width = Val::Percent(progress.fraction() * 100.0);
+    }
+    for mut label in label_query.iter_mut() {
+        label.0 = format!("{} / {}", progress.done, progress.total);
+    }
+}
+
+/// Replaces the progress with the asset that failed and a way out.
+fn show_loading_error(
+    mut commands: Commands,
+    screen_query: Query<Entity, With<LoadingScreen>>,
+    progress: Res<LoadingProgress>,
+) {
+    let Some((path, error)) = &progress.failed else {
+        return;
+    };
+    for entity in screen_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands
+        .spawn((
+            screen_node(),
+            BackgroundColor(Color::srgba(0.5, 0.0, 0.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn(text(Handle::default(), "Failed to load", 48.0));
+            parent.spawn(text(Handle::default(), format!("`{path}`"), 32.0));
+            parent.spawn((
+                text(Handle::default(), error.clone(), 20.0),
+                Node {
+                    max_width: Val::Percent(80.0),
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    button(),
+                    Node {
+                        width: Val::Px(150.0),
+                        height: Val::Px(60.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .observe(on_quit)
+                .with_child(text(Handle::default(), "Quit", 36.0));
+        });
+}
+
+fn on_quit(_trigger: Trigger<OnButtonReleased>, mut exit: EventWriter<AppExit>) {
+    exit.send(AppExit::Success);
+}
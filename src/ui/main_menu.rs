@@ -1,5 +1,8 @@
+use crate::config::*;
+use crate::replay::*;
 use crate::resource::GlobalFont;
 use crate::state::{AppState, GameState};
+use crate::ui::settings::open_settings;
 use crate::ui::util::*;
 use bevy::prelude::*;
 use bevy_button_released_plugin::*;
@@ -7,16 +10,27 @@ use bevy_button_released_plugin::*;
 #[derive(Component, Default)]
 pub struct MainMenu;
 
+#[derive(Component)]
+pub struct DifficultyLabel;
+
+#[derive(Component)]
+pub struct ProfileLabel;
+
 #[derive(Default)]
 pub struct MainMenuPlugin;
 
 impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu);
+        app.add_systems(OnEnter(AppState::MainMenu), spawn_main_menu)
+            .add_systems(
+                Update,
+                update_profile_labels
+                    .run_if(in_state(AppState::MainMenu).and(resource_changed::<GameConfig>)),
+            );
     }
 }
 
-fn spawn_main_menu(mut commands: Commands, font: Res<GlobalFont>) {
+fn spawn_main_menu(mut commands: Commands, font: Res<GlobalFont>, config: Res<GameConfig>) {
     commands
         .spawn((
             MainMenu,
@@ -58,6 +72,33 @@ fn spawn_main_menu(mut commands: Commands, font: Res<GlobalFont>) {
                         .spawn((button(), button_node.clone()))
                         .observe(on_start)
                         .with_child(text(font.handle.clone(), "Play", 50.0));
+                    parent
+                        .spawn((button(), button_node.clone()))
+                        .observe(on_replay)
+                        .with_child(text(font.handle.clone(), "Replay", 50.0));
+                    let wide_button_node = Node {
+                        width: Val::Px(400.0),
+                        height: Val::Px(60.0),
+                        ..button_node.clone()
+                    };
+                    parent
+                        .spawn((button(), wide_button_node.clone()))
+                        .observe(on_difficulty)
+                        .with_child((
+                            text(font.handle.clone(), difficulty_label(&config), 36.0),
+                            DifficultyLabel,
+                        ));
+                    parent
+                        .spawn((button(), wide_button_node.clone()))
+                        .observe(on_profile)
+                        .with_child((
+                            text(font.handle.clone(), profile_label(&config), 36.0),
+                            ProfileLabel,
+                        ));
+                    parent
+                        .spawn((button(), wide_button_node))
+                        .observe(open_settings)
+                        .with_child(text(font.handle.clone(), "Settings", 36.0));
                     parent
                         .spawn((button(), button_node.clone()))
                         .observe(on_quit)
@@ -75,6 +116,77 @@ fn on_start(
     next_game_state.set(GameState::GameInit);
 }
 
+/// Replays the last recorded run.
+fn on_replay(_trigger: Trigger<OnButtonReleased>, mut commands: Commands, config: Res<GameConfig>) {
+    match Replay::load(&config.replay.path) {
+        Ok(replay) => {
+            commands.queue(StartReplay(replay));
+        }
+        Err(err) => log::error!("Error loading replay {}: {}", config.replay.path, err),
+    }
+}
+
+/// Switches to the next difficulty preset and saves it to the config file.
+fn on_difficulty(
+    _trigger: Trigger<OnButtonReleased>,
+    source: Res<ConfigSource>,
+    config: Res<GameConfig>,
+    mut reload_events: EventWriter<ReloadConfig>,
+) {
+    let next = config.profile.difficulty.next();
+    match source.save_value("profile.difficulty", next.name()) {
+        Ok(()) => {
+            reload_events.send_default();
+        }
+        Err(err) => log::error!("Error saving difficulty: {}", err),
+    }
+}
+
+/// Switches to the next profile found next to the config file, or to none.
+fn on_profile(
+    _trigger: Trigger<OnButtonReleased>,
+    source: Res<ConfigSource>,
+    config: Res<GameConfig>,
+    mut reload_events: EventWriter<ReloadConfig>,
+) {
+    let mut names = vec![String::new()];
+    names.extend(source.profiles());
+    let next = names
+        .iter()
+        .position(|name| *name == config.profile.name)
+        .map_or(0, |index| (index + 1) % names.len());
+    match source.save_value("profile.name", names[next].clone()) {
+        Ok(()) => {
+            reload_events.send_default();
+        }
+        Err(err) => log::error!("Error saving profile: {}", err),
+    }
+}
+
+fn update_profile_labels(
+    mut difficulty_query: Query<&mut Text, (With<DifficultyLabel>, Without<ProfileLabel>)>,
+    mut profile_query: Query<&mut Text, (With<ProfileLabel>, Without<DifficultyLabel>)>,
+    config: Res<GameConfig>,
+) {
+    for mut text in difficulty_query.iter_mut() {
+        **text = difficulty_label(&config);
+    }
+    for mut text in profile_query.iter_mut() {
+        **text = profile_label(&config);
+    }
+}
+
+fn difficulty_label(config: &GameConfig) -> String {
+    format!("Difficulty: {}", config.profile.difficulty.name())
+}
+
+fn profile_label(config: &GameConfig) -> String {
+    match config.profile.name.as_str() {
+        "" => "Profile: none".to_string(),
+        name => format!("Profile: {name}"),
+    }
+}
+
 fn on_quit(_trigger: Trigger<OnButtonReleased>, mut exit: EventWriter<AppExit>) {
     exit.send(AppExit::Success);
 }
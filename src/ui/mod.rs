@@ -1,15 +1,22 @@
 use bevy::app::plugin_group;
 
 pub mod bar;
+pub mod boss_health_bar;
+pub mod config_error;
+pub mod config_view;
 pub mod damage_popup;
 pub mod debug_panel;
 pub mod game_over;
 pub mod hud;
 pub mod interpolate;
+pub mod loading;
 pub mod main_menu;
+pub mod navigation;
 pub mod pause;
 pub mod player_health_bar;
 pub mod popup;
+pub mod replay_hud;
+pub mod settings;
 pub mod util;
 
 plugin_group! {
@@ -18,9 +25,15 @@ plugin_group! {
         popup:::PopupPlugin,
         interpolate:::InterpolatePlugin,
         bar:::BarPlugin,
+        loading:::LoadingScreenPlugin,
         hud:::HudPlugin,
         main_menu:::MainMenuPlugin,
         pause:::PausePlugin,
         game_over:::GameOverPlugin,
+        replay_hud:::ReplayHudPlugin,
+        config_error:::ConfigErrorPlugin,
+        config_view:::ConfigViewPlugin,
+        settings:::SettingsPlugin,
+        navigation:::MenuNavigationPlugin,
     }
 }
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use bevy_button_released_plugin::OnButtonReleased;
+
+/// Stick deflection that moves the focus. It moves again once the stick is back below half of it.
+const STICK_THRESHOLD: f32 = 0.6;
+
+const FOCUS_OUTLINE_COLOR: Color = Color::srgb(0.95, 0.8, 0.3);
+
+/// Menu navigation with a gamepad: the d-pad or left stick moves a focus between the
+/// buttons of the topmost menu and the south button presses the focused one.
+#[derive(Default)]
+pub struct MenuNavigationPlugin;
+
+/// The button a gamepad press goes to.
+#[derive(Resource, Debug, Default)]
+pub struct MenuFocus {
+    pub button: Option<Entity>,
+    /// Whether the left stick came back to rest since it last moved the focus.
+    stick_released: bool,
+}
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>().add_systems(
+            Update,
+            (move_focus, press_focused, show_focus)
+                .chain()
+                .run_if(any_with_component::<Gamepad>.and(any_with_component::<Button>)),
+        );
+    }
+}
+
+/// The direction pressed on any gamepad this frame, in UI space (y pointing down).
+fn navigation_direction(gamepads: &Query<&Gamepad>, stick_released: &mut bool) -> Option<Vec2> {
+    for gamepad in gamepads.iter() {
+        for (button, direction) in [
+            (GamepadButton::DPadUp, Vec2::NEG_Y),
+            (GamepadButton::DPadDown, Vec2::Y),
+            (GamepadButton::DPadLeft, Vec2::NEG_X),
+            (GamepadButton::DPadRight, Vec2::X),
+        ] {
+            if gamepad.just_pressed(button) {
+                return Some(direction);
+            }
+        }
+    }
+
+    let stick = gamepads
+        .iter()
+        .map(Gamepad::left_stick)
+        .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+        .unwrap_or(Vec2::ZERO);
+    if stick.length() < STICK_THRESHOLD / 2.0 {
+        *stick_released = true;
+    }
+    if stick.length() < STICK_THRESHOLD || !*stick_released {
+        return None;
+    }
+    *stick_released = false;
+    // the stick points up for positive y, the UI down
+    let direction = if stick.x.abs() > stick.y.abs() {
+        Vec2::new(stick.x.signum(), 0.0)
+    } else {
+        Vec2::new(0.0, -stick.y.signum())
+    };
+    Some(direction)
+}
+
+/// Buttons of the topmost menu, the one with the highest [`GlobalZIndex`], with their positions.
+fn menu_buttons(
+    button_query: &Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    parent_query: &Query<&Parent>,
+    z_index_query: &Query<&GlobalZIndex>,
+) -> Vec<(Entity, Vec2)> {
+    let layer = |entity: Entity| {
+        std::iter::once(entity)
+            .chain(parent_query.iter_ancestors(entity))
+            .filter_map(|entity| z_index_query.get(entity).ok())
+            .map(|z_index| z_index.0)
+            .max()
+            .unwrap_or(0)
+    };
+    let visible: Vec<(Entity, Vec2, i32)> = button_query
+        .iter()
+        .filter(|(_, _, visibility)| visibility.get())
+        .map(|(entity, transform, _)| (entity, transform.translation().truncate(), layer(entity)))
+        .collect();
+    let top = visible.iter().map(|(_, _, layer)| *layer).max();
+    let mut buttons: Vec<(Entity, Vec2)> = visible
+        .into_iter()
+        .filter(|(_, _, layer)| Some(*layer) == top)
+        .map(|(entity, position, _)| (entity, position))
+        .collect();
+    // reading order, so the first press focuses the top-left button
+    buttons.sort_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+    buttons
+}
+
+fn move_focus(
+    gamepads: Query<&Gamepad>,
+    button_query: Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    parent_query: Query<&Parent>,
+    z_index_query: Query<&GlobalZIndex>,
+    mut focus: ResMut<MenuFocus>,
+) {
+    // only a moved focus needs to be shown
+    let stick_released = &mut focus.bypass_change_detection().stick_released;
+    let Some(direction) = navigation_direction(&gamepads, stick_released) else {
+        return;
+    };
+    let buttons = menu_buttons(&button_query, &parent_query, &z_index_query);
+    let current = focus
+        .button
+        .and_then(|focused| buttons.iter().find(|(entity, _)| *entity == focused));
+    let Some((current, from)) = current.copied() else {
+        // a menu was opened or closed since, start over
+        focus.button = buttons.first().map(|(entity, _)| *entity);
+        return;
+    };
+
+    // the closest button in the direction, preferring ones in line with the current one
+    let next = buttons
+        .iter()
+        .filter(|(entity, _)| *entity != current)
+        .filter_map(|(entity, position)| {
+            let offset = *position - from;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+            (along > 0.0).then_some((*entity, along + across * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b));
+    if let Some((next, _)) = next {
+        focus.button = Some(next);
+    }
+}
+
+fn press_focused(
+    mut commands: Commands,
+    gamepads: Query<&Gamepad>,
+    button_query: Query<(Entity, &GlobalTransform, &InheritedVisibility), With<Button>>,
+    parent_query: Query<&Parent>,
+    z_index_query: Query<&GlobalZIndex>,
+    focus: Res<MenuFocus>,
+) {
+    let Some(focused) = focus.button else {
+        return;
+    };
+    if !gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::South))
+    {
+        return;
+    }
+    // a button of a menu that was covered since is out of reach
+    let buttons = menu_buttons(&button_query, &parent_query, &z_index_query);
+    if buttons.iter().any(|(entity, _)| *entity == focused) {
+        commands.trigger_targets(OnButtonReleased, focused);
+    }
+}
+
+fn show_focus(
+    mut commands: Commands,
+    outline_query: Query<Entity, (With<Button>, With<Outline>)>,
+    focus: Res<MenuFocus>,
+) {
+    if !focus.is_changed() {
+        return;
+    }
+    for entity in outline_query.iter() {
+        if Some(entity) != focus.button {
+            commands.entity(entity).remove::<Outline>();
+        }
+    }
+    if let Some(mut entity) = focus.button.and_then(|entity| commands.get_entity(entity)) {
+        entity.insert(Outline::new(
+            Val::Px(3.0),
+            Val::Px(2.0),
+            FOCUS_OUTLINE_COLOR,
+        ));
+    }
+}
@@ -1,9 +1,11 @@
+use crate::input::{action_just_pressed, Action};
+use crate::replay::ReplayPlayer;
 use crate::resource::GlobalFont;
 use crate::state::GameState;
 use crate::ui::main_menu::back_to_main_menu;
+use crate::ui::settings::open_settings;
 use crate::ui::util::{button, text};
 use crate::world::in_game::InGameScoped;
-use bevy::input::common_conditions::input_just_pressed;
 use bevy::prelude::*;
 use bevy_button_released_plugin::OnButtonReleased;
 
@@ -18,9 +20,13 @@ impl Plugin for PausePlugin {
                 esc_pause.run_if(in_state(GameState::Running)),
                 esc_continue.run_if(in_state(GameState::Paused)),
             )
-                .run_if(input_just_pressed(KeyCode::Escape)),
+                .run_if(action_just_pressed(Action::Pause)),
         )
-        .add_systems(OnEnter(GameState::Paused), spawn_pause);
+        // a paused replay keeps the game in view
+        .add_systems(
+            OnEnter(GameState::Paused),
+            spawn_pause.run_if(not(resource_exists::<ReplayPlayer>)),
+        );
     }
 }
 
@@ -80,6 +86,10 @@ fn spawn_pause(mut commands: Commands, font: Res<GlobalFont>) {
                         .spawn((button(), button_node.clone()))
                         .observe(button_continue)
                         .with_child(text(font.handle.clone(), "Continue", 50.0));
+                    parent
+                        .spawn((button(), button_node.clone()))
+                        .observe(open_settings)
+                        .with_child(text(font.handle.clone(), "Settings", 50.0));
                     parent
                         .spawn((button(), button_node.clone()))
                         .observe(back_to_main_menu)
@@ -1,4 +1,5 @@
 use crate::resource::GlobalSpriteSheet;
+use crate::sprite_manifest::sprites;
 use crate::ui::bar::*;
 use crate::world::damage::Health;
 use crate::world::player::Player;
@@ -49,7 +50,7 @@ pub fn spawn_player_health_bar(mut commands: Commands, sheet: Res<GlobalSpriteSh
                     },
                     sheet
                         .0
-                        .to_image_node(62)
+                        .image_node(sprites::BAR_BACKGROUND)
                         .with_mode(NodeImageMode::Sliced(slicer.clone())),
                 ))
                 .with_children(|parent| {
@@ -57,7 +58,7 @@ pub fn spawn_player_health_bar(mut commands: Commands, sheet: Res<GlobalSpriteSh
                         .spawn((
                             sheet
                                 .0
-                                .to_image_node(63)
+                                .image_node(sprites::BAR_FILL)
                                 .with_color(Color::srgb(0.8, 0.2, 0.2).with_alpha(0.6))
                                 .with_mode(NodeImageMode::Sliced(slicer.clone())),
                             BarWidth::new(PLAYER_HEALTH_BAR_WIDTH),
@@ -68,7 +69,7 @@ pub fn spawn_player_health_bar(mut commands: Commands, sheet: Res<GlobalSpriteSh
                             parent.spawn((
                                 sheet
                                     .0
-                                    .to_image_node(63)
+                                    .image_node(sprites::BAR_FILL)
                                     .with_mode(NodeImageMode::Sliced(slicer.clone())),
                                 BarWidth::new(PLAYER_HEALTH_BAR_WIDTH),
                                 BarTargetWidth::Foreground(1.0),
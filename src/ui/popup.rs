@@ -1,3 +1,4 @@
+use crate::rng::CosmeticRng;
 use crate::ui::hud::Hud;
 use crate::ui::interpolate::text_color;
 use bevy::prelude::*;
@@ -24,8 +25,8 @@ pub fn popup_text(
     text: String,
     transform: Transform,
     color: TextColor,
+    rng: &mut CosmeticRng,
 ) {
-    let mut rng = rand::thread_rng();
     let target = AnimationTarget.into_target();
     let mut transform_state = target.transform_state(transform);
     commands
@@ -0,0 +1,83 @@
+use crate::input::recording::InputPlayback;
+use crate::replay::ReplayPlayer;
+use crate::resource::GlobalFont;
+use crate::state::GameState;
+use crate::ui::util::text;
+use crate::world::in_game::InGameScoped;
+use bevy::prelude::*;
+
+/// Shows the progress and controls of the replay being played.
+#[derive(Component, Default)]
+#[require(InGameScoped)]
+pub struct ReplayHud;
+
+#[derive(Component)]
+pub struct ReplayHudText;
+
+#[derive(Default)]
+pub struct ReplayHudPlugin;
+
+impl Plugin for ReplayHudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::GameInit),
+            spawn_replay_hud.run_if(resource_exists::<ReplayPlayer>),
+        )
+        .add_systems(
+            Update,
+            update_replay_hud.run_if(resource_exists::<ReplayPlayer>),
+        );
+    }
+}
+
+fn spawn_replay_hud(mut commands: Commands, font: Res<GlobalFont>) {
+    commands
+        .spawn((
+            ReplayHud,
+            Node {
+                width: Val::Percent(100.0),
+                bottom: Val::Px(10.0),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((text(font.handle.clone(), "", 30.0), ReplayHudText));
+            parent.spawn(text(
+                font.handle.clone(),
+                "Space pause  Up/Down speed  Left/Right seek  0-9 jump  Home restart",
+                20.0,
+            ));
+        });
+}
+
+fn update_replay_hud(
+    mut query: Query<&mut Text, With<ReplayHudText>>,
+    player: Res<ReplayPlayer>,
+    playback: Option<Res<InputPlayback>>,
+    game_state: Option<Res<State<GameState>>>,
+) {
+    let Ok(mut text) = query.get_single_mut() else {
+        return;
+    };
+    let tick = playback.map_or(0, |playback| playback.next_frame);
+    let total = player.total_ticks();
+    let elapsed = player.replay.duration_of(tick).as_secs_f32();
+    let duration = player.replay.duration_of(total).as_secs_f32();
+
+    let mut status = format!(
+        "Replay {elapsed:.1}s / {duration:.1}s  tick {tick}/{total}  x{}",
+        player.speed
+    );
+    if player.seek.is_some() {
+        status.push_str("  seeking");
+    } else if game_state.is_some_and(|state| *state.get() == GameState::Paused) {
+        status.push_str("  paused");
+    }
+    if let Some(mismatch) = player.first_mismatch {
+        status.push_str(&format!("\nDiverged from the recording at tick {mismatch}"));
+    }
+    **text = status;
+}
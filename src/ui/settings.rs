@@ -0,0 +1,528 @@
+use crate::config::*;
+use crate::input::{any_just_pressed, Action};
+use crate::resource::GlobalFont;
+use crate::state::{AppState, GameState};
+use crate::ui::util::{button, text};
+use crate::world::in_game::InGameScoped;
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use bevy::ui::{FocusPolicy, UiSystem};
+use bevy_button_released_plugin::OnButtonReleased;
+use std::ops::RangeInclusive;
+
+/// Window sizes offered by the settings menu.
+const RESOLUTIONS: [(f32, f32); 4] = [
+    (1280.0, 720.0),
+    (1600.0, 900.0),
+    (1920.0, 1080.0),
+    (2560.0, 1440.0),
+];
+
+const VOLUME_STEP: f32 = 0.1;
+const ZOOM_STEP: f32 = 0.05;
+const ZOOM_LIMIT: f32 = 4.0;
+
+/// Display, audio, camera and control settings. Every change is written to the
+/// config file and applied through a reload.
+#[derive(Component)]
+pub struct SettingsMenu;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    WindowMode,
+    Resolution,
+    Vsync,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    UiVolume,
+    ZoomMin,
+    ZoomMax,
+    ReduceMotion,
+    /// The first binding of an action.
+    Binding(Action),
+}
+
+/// Changes a setting when released. `step` is added to numeric settings.
+#[derive(Component)]
+pub struct SettingButton {
+    pub setting: Setting,
+    pub step: f32,
+}
+
+/// Shows the current value of a setting.
+#[derive(Component)]
+pub struct SettingLabel(pub Setting);
+
+/// The action whose binding is replaced by the next key or mouse button pressed.
+#[derive(Resource, Debug, Default)]
+pub struct AwaitingBinding(pub Option<Action>);
+
+#[derive(Default)]
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AwaitingBinding>()
+            .add_systems(
+                PreUpdate,
+                // before the UI sees the click that picks a mouse binding
+                settings_input
+                    .after(InputSystem)
+                    .before(UiSystem::Focus)
+                    .run_if(any_with_component::<SettingsMenu>),
+            )
+            .add_systems(
+                Update,
+                update_setting_labels
+                    .run_if(resource_changed::<GameConfig>.or(resource_changed::<AwaitingBinding>)),
+            );
+    }
+}
+
+impl Setting {
+    const DISPLAY: [Setting; 3] = [Setting::WindowMode, Setting::Resolution, Setting::Vsync];
+    const AUDIO: [Setting; 4] = [
+        Setting::MasterVolume,
+        Setting::MusicVolume,
+        Setting::SfxVolume,
+        Setting::UiVolume,
+    ];
+    const CAMERA: [Setting; 3] = [Setting::ZoomMin, Setting::ZoomMax, Setting::ReduceMotion];
+
+    fn name(self) -> &'static str {
+        match self {
+            Setting::WindowMode => "Window mode",
+            Setting::Resolution => "Resolution",
+            Setting::Vsync => "Vsync",
+            Setting::MasterVolume => "Master volume",
+            Setting::MusicVolume => "Music volume",
+            Setting::SfxVolume => "Effects volume",
+            Setting::UiVolume => "Menu volume",
+            Setting::ZoomMin => "Zoom in limit",
+            Setting::ZoomMax => "Zoom out limit",
+            Setting::ReduceMotion => "Reduce motion",
+            Setting::Binding(action) => action.label(),
+        }
+    }
+
+    /// The step of the `-` and `+` buttons, `None` for settings changed by a single button.
+    fn step(self) -> Option<f32> {
+        match self {
+            Setting::MasterVolume
+            | Setting::MusicVolume
+            | Setting::SfxVolume
+            | Setting::UiVolume => Some(VOLUME_STEP),
+            Setting::ZoomMin | Setting::ZoomMax => Some(ZOOM_STEP),
+            _ => None,
+        }
+    }
+
+    /// The path, value and allowed range of a numeric setting.
+    fn numeric(self, config: &GameConfig) -> Option<(&'static str, f32, RangeInclusive<f32>)> {
+        let audio = &config.audio;
+        let camera = &config.camera;
+        match self {
+            Setting::MasterVolume => Some(("audio.master_volume", audio.master_volume, 0.0..=1.0)),
+            Setting::MusicVolume => Some(("audio.music_volume", audio.music_volume, 0.0..=1.0)),
+            Setting::SfxVolume => Some(("audio.sfx_volume", audio.sfx_volume, 0.0..=1.0)),
+            Setting::UiVolume => Some(("audio.ui_volume", audio.ui_volume, 0.0..=1.0)),
+            Setting::ZoomMin => Some((
+                "camera.zoom_min",
+                camera.zoom_min,
+                ZOOM_STEP..=camera.zoom_max,
+            )),
+            Setting::ZoomMax => Some((
+                "camera.zoom_max",
+                camera.zoom_max,
+                camera.zoom_min..=ZOOM_LIMIT,
+            )),
+            _ => None,
+        }
+    }
+
+    fn label(self, config: &GameConfig, awaiting: &AwaitingBinding) -> String {
+        let basic = &config.basic;
+        match self {
+            Setting::WindowMode => basic.window_mode.name().to_string(),
+            Setting::Resolution => format!("{}x{}", basic.window_width, basic.window_height),
+            Setting::Vsync if basic.vsync => "on".to_string(),
+            Setting::Vsync => "off".to_string(),
+            Setting::ReduceMotion if config.feedback.reduce_motion => "on".to_string(),
+            Setting::ReduceMotion => "off".to_string(),
+            Setting::MasterVolume
+            | Setting::MusicVolume
+            | Setting::SfxVolume
+            | Setting::UiVolume => {
+                let volume = self.numeric(config).map_or(0.0, |(_, value, _)| value);
+                format!("{:.0}%", volume * 100.0)
+            }
+            Setting::ZoomMin | Setting::ZoomMax => {
+                let zoom = self.numeric(config).map_or(0.0, |(_, value, _)| value);
+                format!("{:.2}", zoom)
+            }
+            Setting::Binding(action) if awaiting.0 == Some(action) => "press a key...".to_string(),
+            Setting::Binding(action) => {
+                let labels: Vec<String> = config
+                    .controls
+                    .bindings(action)
+                    .iter()
+                    .map(|binding| binding.label())
+                    .collect();
+                if labels.is_empty() {
+                    "none".to_string()
+                } else {
+                    labels.join(", ")
+                }
+            }
+        }
+    }
+}
+
+/// Opens the settings menu over the main menu or the pause menu.
+pub fn open_settings(
+    _trigger: Trigger<OnButtonReleased>,
+    mut commands: Commands,
+    menu_query: Query<(), With<SettingsMenu>>,
+    font: Res<GlobalFont>,
+    config: Res<GameConfig>,
+    app_state: Res<State<AppState>>,
+    mut awaiting: ResMut<AwaitingBinding>,
+) {
+    if !menu_query.is_empty() {
+        return;
+    }
+    awaiting.0 = None;
+
+    let mut menu = commands.spawn((
+        SettingsMenu,
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor::from(Color::BLACK.with_alpha(0.95)),
+        // keeps the menu below from being clicked
+        FocusPolicy::Block,
+        GlobalZIndex(80),
+    ));
+    if *app_state.get() == AppState::MainMenu {
+        menu.insert(StateScoped(AppState::MainMenu));
+    } else {
+        menu.insert((InGameScoped, StateScoped(GameState::Paused)));
+    }
+
+    let font = font.handle.clone();
+    menu.with_children(|parent| {
+        parent.spawn((
+            text(font.clone(), "Settings", 80.0),
+            Node {
+                margin: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+        ));
+        parent
+            .spawn(Node {
+                column_gap: Val::Px(60.0),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        let settings = Setting::DISPLAY
+                            .into_iter()
+                            .chain(Setting::AUDIO)
+                            .chain(Setting::CAMERA);
+                        for setting in settings {
+                            spawn_setting_row(parent, &font, &config, setting);
+                        }
+                    });
+                parent
+                    .spawn(Node {
+                        flex_direction: FlexDirection::Column,
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        for action in Action::ALL {
+                            spawn_setting_row(parent, &font, &config, Setting::Binding(action));
+                        }
+                    });
+            });
+        parent
+            .spawn((
+                button(),
+                Node {
+                    width: Val::Px(150.0),
+                    height: Val::Px(70.0),
+                    margin: UiRect::all(Val::Px(20.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+            ))
+            .observe(close_settings)
+            .with_child(text(font.clone(), "Back", 44.0));
+    });
+}
+
+fn spawn_setting_row(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    config: &GameConfig,
+    setting: Setting,
+) {
+    const FONT_SIZE: f32 = 26.0;
+    let button_node = Node {
+        height: Val::Px(38.0),
+        margin: UiRect::horizontal(Val::Px(4.0)),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let label = setting.label(config, &AwaitingBinding::default());
+    parent
+        .spawn(Node {
+            align_items: AlignItems::Center,
+            margin: UiRect::vertical(Val::Px(5.0)),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                text(font.clone(), setting.name(), FONT_SIZE),
+                Node {
+                    width: Val::Px(220.0),
+                    ..default()
+                },
+            ));
+            let Some(step) = setting.step() else {
+                parent
+                    .spawn((
+                        button(),
+                        Node {
+                            width: Val::Px(260.0),
+                            ..button_node
+                        },
+                        SettingButton { setting, step: 0.0 },
+                    ))
+                    .observe(on_setting)
+                    .with_child((text(font.clone(), label, FONT_SIZE), SettingLabel(setting)));
+                return;
+            };
+            let step_button = |parent: &mut ChildBuilder, sign: &str, step: f32| {
+                parent
+                    .spawn((
+                        button(),
+                        Node {
+                            width: Val::Px(38.0),
+                            ..button_node.clone()
+                        },
+                        SettingButton { setting, step },
+                    ))
+                    .observe(on_setting)
+                    .with_child(text(font.clone(), sign, FONT_SIZE));
+            };
+            step_button(parent, "-", -step);
+            parent.spawn((
+                text(font.clone(), label, FONT_SIZE),
+                Node {
+                    width: Val::Px(160.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                TextLayout::new_with_justify(JustifyText::Center),
+                SettingLabel(setting),
+            ));
+            step_button(parent, "+", step);
+        });
+}
+
+fn close_settings(
+    _trigger: Trigger<OnButtonReleased>,
+    mut commands: Commands,
+    menu_query: Query<Entity, With<SettingsMenu>>,
+) {
+    for menu in menu_query.iter() {
+        commands.entity(menu).despawn_recursive();
+    }
+}
+
+fn on_setting(
+    trigger: Trigger<OnButtonReleased>,
+    button_query: Query<&SettingButton>,
+    source: Res<ConfigSource>,
+    config: Res<GameConfig>,
+    mut awaiting: ResMut<AwaitingBinding>,
+    mut reload_events: EventWriter<ReloadConfig>,
+) {
+    let Ok(button) = button_query.get(trigger.entity()) else {
+        return;
+    };
+    let basic = &config.basic;
+    let values: Vec<(&str, toml::Value)> = match button.setting {
+        Setting::WindowMode => vec![("basic.window_mode", basic.window_mode.next().name().into())],
+        Setting::Resolution => {
+            let current = (basic.window_width, basic.window_height);
+            let next = RESOLUTIONS
+                .iter()
+                .position(|resolution| *resolution == current)
+                .map_or(0, |index| (index + 1) % RESOLUTIONS.len());
+            let (width, height) = RESOLUTIONS[next];
+            vec![
+                ("basic.window_width", f64::from(width).into()),
+                ("basic.window_height", f64::from(height).into()),
+            ]
+        }
+        Setting::Vsync => vec![("basic.vsync", (!basic.vsync).into())],
+        Setting::ReduceMotion => vec![(
+            "feedback.reduce_motion",
+            (!config.feedback.reduce_motion).into(),
+        )],
+        Setting::Binding(action) => {
+            awaiting.0 = Some(action);
+            return;
+        }
+        setting => {
+            let Some((key, value, range)) = setting.numeric(&config) else {
+                return;
+            };
+            // rounded so that repeated steps do not drift to 0.30000001
+            let value = ((f64::from(value) + f64::from(button.step)) * 100.0).round() / 100.0;
+            let value = value
+                .min(f64::from(*range.end()))
+                .max(f64::from(*range.start()));
+            vec![(key, value.into())]
+        }
+    };
+    save_settings(&source, values, &mut reload_events);
+}
+
+/// Captures a binding from the keyboard, mouse or a gamepad, or closes the menu on
+/// the pause binding.
+fn settings_input(
+    mut commands: Commands,
+    menu_query: Query<Entity, With<SettingsMenu>>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_input: ResMut<ButtonInput<MouseButton>>,
+    mut gamepads: Query<&mut Gamepad>,
+    mut awaiting: ResMut<AwaitingBinding>,
+    source: Res<ConfigSource>,
+    config: Res<GameConfig>,
+    mut reload_events: EventWriter<ReloadConfig>,
+) {
+    if let Some(action) = awaiting.0 {
+        let pressed = keyboard_input
+            .get_just_pressed()
+            .next()
+            .map(|key| InputBinding::Key(*key))
+            .or_else(|| {
+                mouse_input
+                    .get_just_pressed()
+                    .next()
+                    .map(|button| InputBinding::Mouse(*button))
+            })
+            .or_else(|| {
+                gamepads
+                    .iter()
+                    .find_map(|gamepad| gamepad.get_just_pressed().next().copied())
+                    .map(InputBinding::Gamepad)
+            });
+        let Some(binding) = pressed else {
+            return;
+        };
+        clear_just_pressed(
+            binding,
+            &mut keyboard_input,
+            &mut mouse_input,
+            &mut gamepads,
+        );
+        awaiting.0 = None;
+        // escape cancels
+        if binding == InputBinding::Key(KeyCode::Escape) {
+            return;
+        }
+        let mut bindings = vec![binding];
+        bindings.extend(
+            config
+                .controls
+                .bindings(action)
+                .iter()
+                .skip(1)
+                .filter(|other| **other != binding),
+        );
+        match toml::Value::try_from(bindings) {
+            Ok(value) => {
+                let key = format!("controls.{}", action.name());
+                save_settings(&source, vec![(key.as_str(), value)], &mut reload_events);
+            }
+            Err(err) => log::error!("Error saving binding: {}", err),
+        }
+        return;
+    }
+
+    let pause = config.controls.bindings(Action::Pause);
+    if any_just_pressed(pause, &keyboard_input, &mouse_input, &gamepads) {
+        // the pause menu stays open below
+        for binding in pause {
+            clear_just_pressed(
+                *binding,
+                &mut keyboard_input,
+                &mut mouse_input,
+                &mut gamepads,
+            );
+        }
+        for menu in menu_query.iter() {
+            commands.entity(menu).despawn_recursive();
+        }
+    }
+}
+
+fn clear_just_pressed(
+    binding: InputBinding,
+    keyboard_input: &mut ButtonInput<KeyCode>,
+    mouse_input: &mut ButtonInput<MouseButton>,
+    gamepads: &mut Query<&mut Gamepad>,
+) {
+    match binding {
+        InputBinding::Key(key) => {
+            keyboard_input.clear_just_pressed(key);
+        }
+        InputBinding::Mouse(button) => {
+            mouse_input.clear_just_pressed(button);
+        }
+        InputBinding::Gamepad(button) => {
+            for mut gamepad in gamepads.iter_mut() {
+                gamepad.digital_mut().clear_just_pressed(button);
+            }
+        }
+    }
+}
+
+fn save_settings(
+    source: &ConfigSource,
+    values: Vec<(&str, toml::Value)>,
+    reload_events: &mut EventWriter<ReloadConfig>,
+) {
+    match source.save_values(values) {
+        Ok(()) => {
+            reload_events.send_default();
+        }
+        Err(err) => log::error!("Error saving settings: {}", err),
+    }
+}
+
+fn update_setting_labels(
+    mut label_query: Query<(&mut Text, &SettingLabel)>,
+    config: Res<GameConfig>,
+    awaiting: Res<AwaitingBinding>,
+) {
+    for (mut text, label) in label_query.iter_mut() {
+        **text = label.0.label(&config, &awaiting);
+    }
+}
@@ -0,0 +1,330 @@
+use crate::config::{BossPattern, ConfigChanged, GameConfig};
+use crate::resource::GlobalSpriteSheet;
+use crate::rng::GameRng;
+use crate::sprite_manifest::sprites;
+use crate::sprite_order::SpriteOrder;
+use crate::state::GameState;
+use crate::world::bullet::Lifespan;
+use crate::world::collision::*;
+use crate::world::damage::*;
+use crate::world::despawn::PostDespawn;
+use crate::world::enemy::*;
+use crate::world::in_game::InGameScoped;
+use crate::world::knockback::KnockbackResistance;
+use crate::world::particle::{effects, ParticleEmitter};
+use crate::world::player::Player;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+use std::time::Duration;
+
+/// A boss enemy. Bosses are regular [`Enemy`]s that additionally
+/// cycle through attack phases as their health drops.
+#[derive(Component, Default)]
+#[require(Enemy)]
+pub struct Boss {
+    /// Index into `config.boss.phases`.
+    pub phase: usize,
+}
+
+#[derive(Component)]
+pub struct BossAttack {
+    pub timer: Timer,
+    /// Current angle of rotating patterns, in radians.
+    pub angle: f32,
+}
+
+#[derive(Component, Default)]
+#[require(InGameScoped)]
+pub struct BossBullet {
+    pub damage: f32,
+}
+
+/// Counts down to the next boss encounter. Only ticks while the game is running.
+#[derive(Resource)]
+pub struct BossSpawnTimer(pub Timer);
+
+#[derive(Event)]
+pub struct BossPhaseChanged {
+    pub boss: Entity,
+    pub phase: usize,
+}
+
+#[derive(Default)]
+pub struct BossPlugin;
+
+impl Boss {
+    pub fn new(
+        sheet: &Res<GlobalSpriteSheet>,
+        config: &Res<GameConfig>,
+        rng: &mut GameRng,
+        player_pos: Vec2,
+    ) -> impl Bundle {
+        let fire_interval = config
+            .boss
+            .phases
+            .first()
+            .map_or(1.0, |phase| phase.fire_interval);
+        (
+            Enemy::new(sheet, config, rng, player_pos),
+            Boss::default(),
+            BossAttack {
+                timer: Timer::from_seconds(fire_interval, TimerMode::Repeating),
+                angle: 0.0,
+            },
+        )
+    }
+}
+
+impl BossBullet {
+    pub fn new(
+        sheet: &Res<GlobalSpriteSheet>,
+        config: &Res<GameConfig>,
+        position: Vec2,
+        direction: Vec2,
+    ) -> impl Bundle {
+        (
+            BossBullet {
+                damage: config.boss.bullet_damage,
+            },
+            Lifespan::new(Duration::from_secs_f32(config.boss.bullet_lifetime)),
+            LinearVelocity(direction * config.boss.bullet_speed),
+            Transform::from_xyz(position.x, position.y, SpriteOrder::Bullet.z_index())
+                .with_scale(Vec3::splat(2.0)),
+            RigidBody::Dynamic,
+            Collider::rectangle(2.0, 2.0),
+            Sensor,
+            CollisionLayers::new([CollisionLayer::EnemyBullet], [CollisionLayer::Player]),
+            Sprite {
+                color: Color::srgb(1.0, 0.3, 0.3),
+                ..sheet.0.sprite(sprites::BULLET)
+            },
+            ParticleEmitter::continuous(effects::BOSS_BULLET_TRAIL).with_direction(-direction),
+        )
+    }
+}
+
+impl BossSpawnTimer {
+    fn from_config(config: &GameConfig) -> Self {
+        Self(Timer::from_seconds(
+            config.boss.first_spawn,
+            TimerMode::Once,
+        ))
+    }
+}
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state_scoped_event::<BossPhaseChanged>(GameState::Running)
+            .add_systems(OnEnter(GameState::GameInit), reset_boss_spawn_timer)
+            .add_systems(
+                Update,
+                (
+                    spawn_boss,
+                    update_boss_phase,
+                    boss_attack,
+                    despawn_boss_bullets,
+                    on_hit_player.in_set(DamagePhase::Send),
+                )
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(Update, apply_config_changes);
+    }
+}
+
+fn reset_boss_spawn_timer(mut commands: Commands, config: Res<GameConfig>) {
+    commands.insert_resource(BossSpawnTimer::from_config(&config));
+}
+
+fn spawn_boss(
+    mut commands: Commands,
+    sheet: Res<GlobalSpriteSheet>,
+    mut rng: ResMut<GameRng>,
+    mut spawn_timer: ResMut<BossSpawnTimer>,
+    player_query: Query<&Transform, With<Player>>,
+    boss_query: Query<(), With<Boss>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    if !config.boss.enabled || config.boss.phases.is_empty() {
+        return;
+    }
+    // only one boss at a time, and the countdown waits until it is defeated
+    if !boss_query.is_empty() {
+        return;
+    }
+    spawn_timer.0.tick(time.delta());
+    if !spawn_timer.0.finished() {
+        return;
+    }
+    spawn_timer.0 = Timer::from_seconds(config.boss.spawn_interval, TimerMode::Once);
+
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation.truncate();
+    commands
+        .spawn(Boss::new(&sheet, &config, &mut rng, player_pos))
+        .insert((
+            Health::new(config.boss.health),
+            MoveSpeed(config.boss.speed),
+            KnockbackResistance(1.0),
+            // the collider is scaled along with the sprite
+            Transform::from_xyz(
+                player_pos.x,
+                player_pos.y + 200.0,
+                SpriteOrder::Enemy.z_index(),
+            )
+            .with_scale(Vec3::splat(config.boss.scale)),
+        ));
+    log::info!("Boss spawned");
+}
+
+fn update_boss_phase(
+    mut boss_query: Query<(Entity, &Health, &mut Boss, &mut BossAttack)>,
+    mut phase_events: EventWriter<BossPhaseChanged>,
+    config: Res<GameConfig>,
+) {
+    for (entity, health, mut boss, mut attack) in boss_query.iter_mut() {
+        let ratio = health.current() / health.max();
+        // the last phase whose threshold has been crossed wins
+        let Some(phase) = config
+            .boss
+            .phases
+            .iter()
+            .rposition(|phase| ratio <= phase.health_threshold)
+        else {
+            continue;
+        };
+        if phase <= boss.phase {
+            continue;
+        }
+        boss.phase = phase;
+        attack.timer = Timer::from_seconds(
+            config.boss.phases[phase].fire_interval,
+            TimerMode::Repeating,
+        );
+        phase_events.send(BossPhaseChanged {
+            boss: entity,
+            phase,
+        });
+    }
+}
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut boss_query: Query<(&Boss, &mut Health, &mut MoveSpeed, &mut BossAttack)>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    for (boss, mut health, mut speed, mut attack) in boss_query.iter_mut() {
+        if changed.contains("boss.health") {
+            health.set_max(config.boss.health);
+        }
+        if changed.contains("boss.speed") {
+            speed.0 = config.boss.speed;
+        }
+        if let Some(phase) = config.boss.phases.get(boss.phase) {
+            if changed.contains(&format!("boss.phases.{}.fire_interval", boss.phase)) {
+                attack
+                    .timer
+                    .set_duration(Duration::from_secs_f32(phase.fire_interval));
+            }
+        }
+    }
+}
+
+fn boss_attack(
+    mut commands: Commands,
+    sheet: Res<GlobalSpriteSheet>,
+    mut boss_query: Query<(&GlobalTransform, &Boss, &mut BossAttack)>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+    let player_pos = player_transform.translation().truncate();
+    for (transform, boss, mut attack) in boss_query.iter_mut() {
+        attack.timer.tick(time.delta());
+        if !attack.timer.just_finished() {
+            continue;
+        }
+        let Some(phase) = config.boss.phases.get(boss.phase) else {
+            continue;
+        };
+        let position = transform.translation().truncate();
+        let directions: Vec<Vec2> = match phase.pattern {
+            BossPattern::RadialBurst { count } => (0..count)
+                .map(|i| Vec2::from_angle(TAU * i as f32 / count as f32))
+                .collect(),
+            BossPattern::Spiral { arms, step } => {
+                attack.angle = (attack.angle + step.to_radians()) % TAU;
+                (0..arms)
+                    .map(|i| Vec2::from_angle(attack.angle + TAU * i as f32 / arms as f32))
+                    .collect()
+            }
+            BossPattern::AimedVolley { count, spread } => {
+                let aim = (player_pos - position).normalize_or(Vec2::X);
+                let spread = spread.to_radians();
+                (0..count)
+                    .map(|i| {
+                        let t = if count > 1 {
+                            i as f32 / (count - 1) as f32 - 0.5
+                        } else {
+                            0.0
+                        };
+                        Vec2::from_angle(t * spread).rotate(aim)
+                    })
+                    .collect()
+            }
+        };
+        for direction in directions {
+            commands.spawn(BossBullet::new(&sheet, &config, position, direction));
+        }
+    }
+}
+
+fn despawn_boss_bullets(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bullet_query: Query<(Entity, &mut Lifespan), With<BossBullet>>,
+) {
+    for (entity, mut lifespan) in bullet_query.iter_mut() {
+        lifespan.0.tick(time.delta());
+        if lifespan.0.finished() {
+            commands.entity(entity).insert(PostDespawn);
+        }
+    }
+}
+
+fn on_hit_player(
+    mut commands: Commands,
+    bullet_query: Query<&BossBullet>,
+    player_query: Query<(), With<Player>>,
+    mut collision_events: EventReader<Collision>,
+    mut damage_events: EventWriter<DamageEvent>,
+) {
+    for event in collision_events.read() {
+        let Some(bullet) = try_parse_collider(event.0.entity1, event.0.entity2, &bullet_query)
+        else {
+            continue;
+        };
+        let Some(player) = try_parse_collider(event.0.entity1, event.0.entity2, &player_query)
+        else {
+            continue;
+        };
+        let Ok(boss_bullet) = bullet_query.get(bullet) else {
+            continue;
+        };
+        damage_events.send(DamageEvent {
+            target: player,
+            context: DamageContext::without_attacker(boss_bullet.damage, DamageType::Enemy),
+            apply: true,
+        });
+        commands.entity(bullet).insert(PostDespawn);
+    }
+}
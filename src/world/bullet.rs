@@ -1,16 +1,22 @@
 use crate::config::GameConfig;
 use crate::resource::GlobalSpriteSheet;
+use crate::rng::GameRng;
+use crate::sprite_manifest::sprites;
 use crate::sprite_order::SpriteOrder;
 use crate::state::GameState;
+use crate::world::bullet_modifier::*;
 use crate::world::collision::*;
 use crate::world::damage::*;
 use crate::world::despawn::PostDespawn;
 use crate::world::enemy::Enemy;
+use crate::world::explosion::*;
 use crate::world::in_game::InGameScoped;
 use crate::world::owner::Owner;
+use crate::world::particle::{effects, SpawnParticles};
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use rand::Rng;
+use std::collections::HashSet;
 use std::time::Duration;
 
 #[derive(Component, Default)]
@@ -20,6 +26,9 @@ pub struct Bullet;
 #[derive(Component, Default)]
 pub struct BulletDamage(pub f32);
 
+#[derive(Component, Default)]
+pub struct BulletKnockback(pub f32);
+
 #[derive(Component, Default)]
 pub struct Lifespan(pub Timer);
 
@@ -40,21 +49,22 @@ impl Bullet {
     pub fn new(
         sheet: &Res<GlobalSpriteSheet>,
         config: &Res<GameConfig>,
+        rng: &mut GameRng,
         gun_dir: Vec2,
         gun_pos: Vec2,
     ) -> impl Bundle {
-        let mut rng = rand::thread_rng();
         let offset = Vec2::new(rng.gen_range(-0.5..0.5), rng.gen_range(-0.5..0.5));
         (
             Bullet,
             BulletDamage(config.bullet.damage),
+            BulletKnockback(config.bullet.knockback),
             LinearVelocity((gun_dir + offset) * Vec2::splat(config.bullet.speed)),
             Transform::from_xyz(gun_pos.x, gun_pos.y, SpriteOrder::Bullet.z_index()),
             RigidBody::Dynamic,
             Collider::rectangle(2.0, 2.0),
             Sensor,
             CollisionLayers::new([CollisionLayer::Bullet], [CollisionLayer::Enemy]),
-            sheet.0.to_sprite(16),
+            sheet.0.sprite(sprites::BULLET),
         )
     }
 }
@@ -67,47 +77,84 @@ impl Lifespan {
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                despawn_bullets_out_of_range,
-                despawn_bullets_out_of_lifespan,
-                on_hit_enemy.in_set(DamagePhase::Send),
-            )
-                .run_if(in_state(GameState::Running)),
-        );
+        app.add_state_scoped_event::<BulletHitEvent>(GameState::Running)
+            .add_systems(
+                Update,
+                (
+                    steer_homing_bullets,
+                    (on_hit_enemy, resolve_bullet_hits, despawn_expired_bullets)
+                        .chain()
+                        .in_set(DamagePhase::Send),
+                )
+                    .run_if(in_state(GameState::Running)),
+            );
     }
 }
 
-fn despawn_bullets_out_of_lifespan(
+/// Despawns bullets past their lifespan or range. Explosive ones explode, unless a hit
+/// already set them off this frame.
+fn despawn_expired_bullets(
     mut commands: Commands,
-    bullet_query: Query<(Entity, &Lifespan), With<Bullet>>,
+    time: Res<Time>,
+    mut bullet_query: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&mut Lifespan>,
+            Option<(&SpawnPoint, &MaxTravelDistance)>,
+            Option<&Explosive>,
+            Option<&Owner>,
+        ),
+        With<Bullet>,
+    >,
+    mut hit_events: EventReader<BulletHitEvent>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
 ) {
-    for (bullet, instant) in bullet_query.iter() {
-        if instant.0.finished() {
-            commands.entity(bullet).insert(PostDespawn);
+    let exploded: HashSet<Entity> = hit_events.read().map(|event| event.bullet).collect();
+    for (bullet, transform, lifespan, range, explosive, owner) in bullet_query.iter_mut() {
+        let position = transform.translation().truncate();
+        let out_of_time =
+            lifespan.is_some_and(|mut lifespan| lifespan.0.tick(time.delta()).finished());
+        let out_of_range = range.is_some_and(|(spawn_point, max_distance)| {
+            position.distance(spawn_point.0) > max_distance.0
+        });
+        if !out_of_time && !out_of_range {
+            continue;
         }
-    }
-}
-
-fn despawn_bullets_out_of_range(
-    mut commands: Commands,
-    bullet_query: Query<(Entity, &GlobalTransform, &SpawnPoint, &MaxTravelDistance), With<Bullet>>,
-) {
-    for (bullet, transform, spawn_point, max_distance) in bullet_query.iter() {
-        if transform.translation().truncate().distance(spawn_point.0) > max_distance.0 {
-            commands.entity(bullet).insert(PostDespawn);
+        if let Some(explosive) = explosive.filter(|_| !exploded.contains(&bullet)) {
+            explosion_events.send(ExplosionEvent {
+                position,
+                explosive: *explosive,
+                attacker: owner.map(|owner| owner.0),
+                direct_hit: None,
+            });
         }
+        commands.entity(bullet).insert(PostDespawn);
     }
 }
 
 fn on_hit_enemy(
-    mut commands: Commands,
-    bullet_query: Query<(&BulletDamage, Option<&Owner>, Has<DespawnOnHit>), With<Bullet>>,
+    mut bullet_query: Query<
+        (
+            &BulletDamage,
+            &BulletKnockback,
+            &GlobalTransform,
+            &LinearVelocity,
+            Option<&Owner>,
+            Option<&Explosive>,
+            Option<&mut HitEnemies>,
+        ),
+        With<Bullet>,
+    >,
     enemy_query: Query<(), With<Enemy>>,
     mut collision_events: EventReader<Collision>,
     mut damage_events: EventWriter<DamageEvent>,
+    mut explosion_events: EventWriter<ExplosionEvent>,
+    mut hit_events: EventWriter<BulletHitEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
 ) {
+    // a bullet touching several enemies in the same frame only explodes once
+    let mut exploded = HashSet::new();
     for event in collision_events.read() {
         let Some(bullet) = try_parse_collider(event.0.entity1, event.0.entity2, &bullet_query)
         else {
@@ -116,20 +163,42 @@ fn on_hit_enemy(
         let Some(enemy) = try_parse_collider(event.0.entity1, event.0.entity2, &enemy_query) else {
             continue;
         };
-        let Ok((damage, owner, despawn_on_hit)) = bullet_query.get(bullet) else {
+        let Ok((damage, knockback, transform, velocity, owner, explosive, hit_enemies)) =
+            bullet_query.get_mut(bullet)
+        else {
             continue;
         };
+        if let Some(mut hit_enemies) = hit_enemies {
+            if !hit_enemies.0.insert(enemy) {
+                continue;
+            }
+        }
         damage_events.send(DamageEvent {
             target: enemy,
             context: DamageContext {
                 damage: damage.0,
                 damage_type: DamageType::Bullet.into(),
                 attacker: owner.map(|owner| owner.0),
+                knockback: Some(velocity.0.normalize_or_zero() * knockback.0),
+                ignores_cooldown: false,
             },
             apply: true,
         });
-        if despawn_on_hit {
-            commands.entity(bullet).insert(PostDespawn);
+        if let Some(explosive) = explosive {
+            if exploded.insert(bullet) {
+                explosion_events.send(ExplosionEvent {
+                    position: transform.translation().truncate(),
+                    explosive: *explosive,
+                    attacker: owner.map(|owner| owner.0),
+                    direct_hit: Some(enemy),
+                });
+            }
         }
+        particle_events.send(SpawnParticles::new(
+            effects::BULLET_HIT,
+            transform.translation().truncate(),
+            -velocity.0,
+        ));
+        hit_events.send(BulletHitEvent { bullet, enemy });
     }
 }
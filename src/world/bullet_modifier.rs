@@ -0,0 +1,323 @@
+use crate::config::{BulletConfig, GameConfig};
+use crate::resource::GlobalSpriteSheet;
+use crate::rng::GameRng;
+use crate::world::bullet::*;
+use crate::world::collision::CollisionLayer;
+use crate::world::despawn::PostDespawn;
+use crate::world::enemy::Enemy;
+use crate::world::explosion::Explosive;
+use crate::world::owner::Owner;
+use avian2d::prelude::*;
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::*;
+use std::collections::HashSet;
+
+/// Enemies a bullet has already damaged, so the same enemy is never hit twice.
+#[derive(Component, Clone, Default)]
+pub struct HitEnemies(pub HashSet<Entity>);
+
+/// Passes through enemies instead of stopping at the first one.
+#[derive(Component, Clone, Copy)]
+#[require(HitEnemies)]
+pub struct Pierce {
+    pub remaining: usize,
+}
+
+/// Bounces towards the nearest enemy that has not been hit yet.
+#[derive(Component, Clone, Copy)]
+#[require(HitEnemies)]
+pub struct Ricochet {
+    pub remaining: usize,
+    pub range: f32,
+}
+
+/// Steers towards the nearest enemy inside a cone in front of the bullet.
+#[derive(Component, Clone, Copy)]
+pub struct Homing {
+    /// Half-angle of the cone, in radians.
+    pub cone: f32,
+    pub range: f32,
+    /// Maximum turn speed, in radians per second.
+    pub turn_rate: f32,
+}
+
+/// Splits into several smaller bullets when hitting an enemy.
+#[derive(Component, Clone, Copy)]
+#[require(HitEnemies)]
+pub struct SplitOnHit {
+    pub count: usize,
+    /// Total angle covered by the fragments, in radians.
+    pub spread: f32,
+    /// Fraction of the bullet's damage each fragment deals.
+    pub damage_ratio: f32,
+}
+
+/// A bullet hit an enemy and its damage has been sent.
+#[derive(Event)]
+pub struct BulletHitEvent {
+    pub bullet: Entity,
+    pub enemy: Entity,
+}
+
+/// The set of modifiers a weapon applies to every bullet it fires.
+#[derive(Component, Clone, Default)]
+pub struct BulletModifiers {
+    pub explosive: Option<Explosive>,
+    pub pierce: Option<Pierce>,
+    pub ricochet: Option<Ricochet>,
+    pub homing: Option<Homing>,
+    pub split: Option<SplitOnHit>,
+}
+
+impl BulletModifiers {
+    pub fn from_config(config: &BulletConfig) -> Self {
+        let modifiers = &config.modifiers;
+        Self {
+            explosive: Explosive::from_config(config),
+            pierce: (modifiers.pierce > 0).then_some(Pierce {
+                remaining: modifiers.pierce,
+            }),
+            ricochet: (modifiers.ricochet > 0).then_some(Ricochet {
+                remaining: modifiers.ricochet,
+                range: modifiers.ricochet_range,
+            }),
+            homing: (modifiers.homing_cone > 0.0).then_some(Homing {
+                cone: modifiers.homing_cone.to_radians(),
+                range: modifiers.homing_range,
+                turn_rate: modifiers.homing_turn_rate.to_radians(),
+            }),
+            split: (modifiers.split_count > 0).then_some(SplitOnHit {
+                count: modifiers.split_count,
+                spread: modifiers.split_spread.to_radians(),
+                damage_ratio: modifiers.split_damage_ratio,
+            }),
+        }
+    }
+
+    pub fn apply(&self, commands: &mut EntityCommands) {
+        if let Some(explosive) = self.explosive {
+            commands.insert(explosive);
+        }
+        if let Some(pierce) = self.pierce {
+            commands.insert(pierce);
+        }
+        if let Some(ricochet) = self.ricochet {
+            commands.insert(ricochet);
+        }
+        if let Some(homing) = self.homing {
+            commands.insert(homing);
+        }
+        if let Some(split) = self.split {
+            commands.insert(split);
+        }
+    }
+}
+
+/// Decides what happens to a bullet after it hit an enemy, in this order: it splits,
+/// then pierces or ricochets to carry on, and otherwise despawns. A bullet hitting
+/// several enemies in the same frame only splits once.
+pub fn resolve_bullet_hits(
+    mut commands: Commands,
+    sheet: Res<GlobalSpriteSheet>,
+    config: Res<GameConfig>,
+    mut rng: ResMut<GameRng>,
+    spatial_query: SpatialQuery,
+    mut bullet_query: Query<
+        (
+            &GlobalTransform,
+            &BulletDamage,
+            &mut LinearVelocity,
+            &mut SpawnPoint,
+            Option<&MaxTravelDistance>,
+            Option<&Owner>,
+            Option<&HitEnemies>,
+            Option<&mut Pierce>,
+            Option<&mut Ricochet>,
+            Option<&SplitOnHit>,
+            Has<DespawnOnHit>,
+        ),
+        With<Bullet>,
+    >,
+    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+    mut hit_events: EventReader<BulletHitEvent>,
+) {
+    let mut split_bullets = HashSet::new();
+    for event in hit_events.read() {
+        let Ok((
+            transform,
+            damage,
+            mut velocity,
+            mut spawn_point,
+            max_distance,
+            owner,
+            hit_enemies,
+            pierce,
+            ricochet,
+            split,
+            despawn_on_hit,
+        )) = bullet_query.get_mut(event.bullet)
+        else {
+            continue;
+        };
+        let position = transform.translation().truncate();
+        let hit = hit_enemies.map(|hit| hit.0.clone()).unwrap_or_default();
+
+        if let Some(split) = split.filter(|_| split_bullets.insert(event.bullet)) {
+            spawn_fragments(
+                &mut commands,
+                &sheet,
+                &config,
+                &mut rng,
+                split,
+                position,
+                velocity.0,
+                damage.0,
+                max_distance.map_or(f32::INFINITY, |max| max.0),
+                owner.map(|owner| owner.0),
+                &hit,
+            );
+        }
+
+        if let Some(mut pierce) = pierce {
+            if pierce.remaining > 0 {
+                pierce.remaining -= 1;
+                continue;
+            }
+        }
+
+        if let Some(mut ricochet) = ricochet {
+            if ricochet.remaining > 0 {
+                let target = nearest_enemy(
+                    &spatial_query,
+                    &enemy_query,
+                    position,
+                    ricochet.range,
+                    |entity, _| !hit.contains(&entity),
+                );
+                if let Some(target) = target {
+                    ricochet.remaining -= 1;
+                    let speed = velocity.0.length();
+                    velocity.0 = (target - position).normalize_or_zero() * speed;
+                    spawn_point.0 = position;
+                    continue;
+                }
+            }
+        }
+
+        if despawn_on_hit {
+            commands.entity(event.bullet).insert(PostDespawn);
+        }
+    }
+}
+
+pub fn steer_homing_bullets(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut bullet_query: Query<
+        (
+            &GlobalTransform,
+            &Homing,
+            &mut LinearVelocity,
+            Option<&HitEnemies>,
+        ),
+        With<Bullet>,
+    >,
+    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+) {
+    for (transform, homing, mut velocity, hit_enemies) in bullet_query.iter_mut() {
+        let speed = velocity.0.length();
+        if speed == 0.0 {
+            continue;
+        }
+        let position = transform.translation().truncate();
+        let heading = velocity.0 / speed;
+        let target = nearest_enemy(
+            &spatial_query,
+            &enemy_query,
+            position,
+            homing.range,
+            |entity, target| {
+                let in_cone = heading.angle_to(target - position).abs() <= homing.cone;
+                let not_hit = hit_enemies.map_or(true, |hit| !hit.0.contains(&entity));
+                in_cone && not_hit
+            },
+        );
+        let Some(target) = target else {
+            continue;
+        };
+        let desired = (target - position).normalize_or_zero();
+        let max_angle = homing.turn_rate * time.delta_secs();
+        velocity.0 = heading
+            .rotate_towards(desired, max_angle)
+            .normalize_or_zero()
+            * speed;
+    }
+}
+
+fn nearest_enemy(
+    spatial_query: &SpatialQuery,
+    enemy_query: &Query<&GlobalTransform, With<Enemy>>,
+    position: Vec2,
+    range: f32,
+    predicate: impl Fn(Entity, Vec2) -> bool,
+) -> Option<Vec2> {
+    let filter = SpatialQueryFilter::from_mask(CollisionLayer::Enemy);
+    spatial_query
+        .shape_intersections(&Collider::circle(range), position, 0.0, &filter)
+        .into_iter()
+        .filter_map(|entity| {
+            let target = enemy_query.get(entity).ok()?.translation().truncate();
+            predicate(entity, target).then_some(target)
+        })
+        .min_by(|a, b| {
+            a.distance_squared(position)
+                .total_cmp(&b.distance_squared(position))
+        })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_fragments(
+    commands: &mut Commands,
+    sheet: &Res<GlobalSpriteSheet>,
+    config: &Res<GameConfig>,
+    rng: &mut GameRng,
+    split: &SplitOnHit,
+    position: Vec2,
+    velocity: Vec2,
+    damage: f32,
+    max_distance: f32,
+    owner: Option<Entity>,
+    hit: &HashSet<Entity>,
+) {
+    let speed = velocity.length();
+    let heading = velocity.normalize_or(Vec2::X);
+    let step = if split.count > 1 {
+        split.spread / (split.count - 1) as f32
+    } else {
+        0.0
+    };
+    let start = -split.spread / 2.0;
+    for i in 0..split.count {
+        let angle = if split.count > 1 {
+            start + step * i as f32
+        } else {
+            0.0
+        };
+        let direction = Vec2::from_angle(angle).rotate(heading);
+        let mut fragment = commands.spawn((
+            Bullet::new(sheet, config, rng, direction, position),
+            MaxTravelDistance(max_distance),
+            SpawnPoint(position),
+            HitEnemies(hit.clone()),
+            DespawnOnHit,
+        ));
+        // override the random spread and full damage given by `Bullet::new`
+        fragment.insert((
+            BulletDamage(damage * split.damage_ratio),
+            LinearVelocity(direction * speed),
+        ));
+        if let Some(owner) = owner {
+            fragment.insert(Owner(owner));
+        }
+    }
+}
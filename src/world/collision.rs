@@ -1,4 +1,6 @@
+use avian2d::interpolation::PhysicsInterpolationPlugin;
 use avian2d::prelude::{PhysicsLayer, RigidBody, RigidBodyDisabled};
+use avian2d::PhysicsPlugins;
 use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::prelude::*;
 
@@ -8,6 +10,7 @@ pub enum CollisionLayer {
     Player,
     Enemy,
     Bullet,
+    EnemyBullet,
 }
 
 pub fn try_parse_collider<D: QueryData, F: QueryFilter>(
@@ -35,3 +38,9 @@ pub fn disable_rigid_bodies(mut commands: Commands, query: Query<Entity, With<Ri
         commands.entity(entity).insert(RigidBodyDisabled);
     }
 }
+
+pub fn configured_physics_plugins(tile_size: f32) -> impl PluginGroup {
+    PhysicsPlugins::default()
+        .with_length_unit(tile_size)
+        .set(PhysicsInterpolationPlugin::interpolate_translation_all())
+}
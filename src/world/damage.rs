@@ -20,6 +20,8 @@ pub enum DamagePhase {
 pub enum DamageType {
     Bullet,
     Enemy,
+    Explosion,
+    Melee,
 }
 
 #[derive(Clone, Copy)]
@@ -27,6 +29,10 @@ pub struct DamageContext {
     pub damage: f32,
     pub damage_type: u32,
     pub attacker: Option<Entity>,
+    /// Velocity the target is pushed with if the damage is applied.
+    pub knockback: Option<Vec2>,
+    /// Applied even while the target is on cooldown.
+    pub ignores_cooldown: bool,
 }
 
 #[derive(Event)]
@@ -63,6 +69,16 @@ impl Health {
         self.current
     }
 
+    /// Changes the maximum, keeping the current health at the same fraction of it.
+    pub fn set_max(&mut self, max: f32) {
+        if self.max > 0.0 {
+            self.current *= max / self.max;
+        } else {
+            self.current = max;
+        }
+        self.max = max;
+    }
+
     fn damage(&mut self, damage: f32) {
         self.current -= damage;
         if self.current < 0.0 {
@@ -83,6 +99,8 @@ impl Into<u32> for DamageType {
         match self {
             DamageType::Bullet => 0,
             DamageType::Enemy => 1,
+            DamageType::Explosion => 2,
+            DamageType::Melee => 3,
         }
     }
 }
@@ -93,11 +111,21 @@ impl DamageContext {
             damage,
             damage_type: damage_type.into(),
             attacker,
+            knockback: None,
+            ignores_cooldown: false,
         }
     }
     pub fn without_attacker(damage: f32, damage_type: impl Into<u32>) -> Self {
         Self::new(damage, damage_type, None)
     }
+    pub fn with_knockback(mut self, knockback: Vec2) -> Self {
+        self.knockback = Some(knockback);
+        self
+    }
+    pub fn ignoring_cooldown(mut self) -> Self {
+        self.ignores_cooldown = true;
+        self
+    }
 }
 
 impl DamageCooldown {
@@ -157,12 +185,12 @@ fn apply_damage(
             let mut cd = cooldown.unwrap();
 
             // skip if the target is still on cooldown
-            if !cd.0.finished() {
+            if !cd.0.finished() && !event.context.ignores_cooldown {
                 event.apply = false;
                 continue;
             }
 
-            // reset the cooldown
+            // reset the cooldown, which also ends the damage flash
             cd.0.reset();
 
             // add a damage flash effect
@@ -0,0 +1,49 @@
+use crate::config::GameConfig;
+use crate::state::GameState;
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+/// The time of day, as a fraction of the day: 0 is midnight, 0.5 noon.
+/// Advances with game time, so runs and replays see the same nights.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct DayClock(pub f32);
+
+#[derive(Default)]
+pub struct DayNightPlugin;
+
+impl DayClock {
+    /// How bright it is, from 0 at midnight to 1 at noon.
+    pub fn daylight(&self) -> f32 {
+        0.5 - 0.5 * (self.0 * TAU).cos()
+    }
+
+    /// How deep into the night it is, 0 from sunrise to sunset and 1 at midnight.
+    pub fn night(&self) -> f32 {
+        (1.0 - 2.0 * self.daylight()).max(0.0)
+    }
+
+    /// 1 by day, growing to `multiplier` at midnight.
+    pub fn night_scale(&self, multiplier: f32) -> f32 {
+        1.0 + (multiplier - 1.0) * self.night()
+    }
+}
+
+impl Plugin for DayNightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DayClock>()
+            .add_systems(OnEnter(GameState::GameInit), reset_clock)
+            .add_systems(Update, advance_clock.run_if(in_state(GameState::Running)));
+    }
+}
+
+fn reset_clock(mut clock: ResMut<DayClock>, config: Res<GameConfig>) {
+    clock.0 = config.day_night.start_time;
+}
+
+fn advance_clock(mut clock: ResMut<DayClock>, time: Res<Time>, config: Res<GameConfig>) {
+    let day_night = &config.day_night;
+    if !day_night.enabled {
+        return;
+    }
+    clock.0 = (clock.0 + time.delta_secs() / day_night.day_length).fract();
+}
@@ -0,0 +1,154 @@
+use crate::animation::{AnimationFinished, AnimationState, Animator};
+use crate::config::GameConfig;
+use crate::sprite_order::SpriteOrder;
+use crate::state::GameState;
+use crate::world::despawn::PostDespawn;
+use crate::world::particle::{effects, SpawnParticles};
+use bevy::color::Mix;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use std::cmp::Reverse;
+
+/// Darkens corpses, so they read as part of the ground.
+const CORPSE_TINT: Color = Color::srgb(0.5, 0.45, 0.45);
+
+/// How far an entity without a death clip shrinks while it fades.
+const FADE_SCALE: f32 = 0.6;
+
+/// An entity that died. Whatever made it take part in the game is removed when it
+/// dies; it only plays its death clip, or fades, before it becomes a [`Corpse`].
+#[derive(Component, Default)]
+pub struct Dying {
+    /// Runs while an entity without a death clip fades. The others wait for their clip.
+    /// It only ticks while the game runs, so the fade stops with it.
+    fade: Option<Timer>,
+    /// The scale the entity had when it died, which the fade shrinks.
+    scale: Vec3,
+}
+
+/// What is left of a dead entity, until `config.death.corpse_lifetime` is over.
+#[derive(Component)]
+pub struct Corpse(pub Timer);
+
+#[derive(Default)]
+pub struct DyingPlugin;
+
+impl Plugin for DyingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(start_dying).add_systems(
+            Update,
+            (finish_dying, expire_corpses)
+                .chain()
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn start_dying(
+    trigger: Trigger<OnAdd, Dying>,
+    mut dying_query: Query<(&mut Dying, &Transform, &mut Sprite, Option<&mut Animator>)>,
+    mut particle_events: EventWriter<SpawnParticles>,
+    config: Res<GameConfig>,
+) {
+    let entity = trigger.entity();
+    let Ok((mut dying, transform, mut sprite, animator)) = dying_query.get_mut(entity) else {
+        return;
+    };
+    particle_events.send(SpawnParticles::new(
+        effects::ENEMY_DEATH,
+        transform.translation.truncate(),
+        Vec2::ZERO,
+    ));
+    // the killing blow left the damage flash on
+    sprite.color = Color::WHITE;
+    if let Some(mut animator) = animator {
+        // without a clip, this stops the animation on the frame being shown
+        animator.request(AnimationState::Death);
+        if animator.has_clip(AnimationState::Death) {
+            return;
+        }
+    }
+
+    dying.fade = Some(Timer::from_seconds(
+        config.death.fade_duration,
+        TimerMode::Once,
+    ));
+    dying.scale = transform.scale;
+}
+
+/// The color an entity without a death clip fades to.
+fn faded_color(config: &GameConfig) -> Color {
+    if config.death.corpse_lifetime > 0.0 {
+        CORPSE_TINT
+    } else {
+        Color::WHITE.with_alpha(0.0)
+    }
+}
+
+/// Shrinks and tints the entities without a death clip, and leaves a corpse once the
+/// death clip or the fade is over.
+fn finish_dying(
+    mut commands: Commands,
+    mut finished_events: EventReader<AnimationFinished>,
+    mut dying_query: Query<(Entity, &mut Dying, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let clips_finished: HashSet<Entity> = finished_events
+        .read()
+        .filter(|event| event.state == AnimationState::Death)
+        .map(|event| event.entity)
+        .collect();
+    for (entity, mut dying, mut transform, mut sprite) in dying_query.iter_mut() {
+        let scale = dying.scale;
+        let finished = match &mut dying.fade {
+            Some(fade) => {
+                fade.tick(time.delta());
+                // eases in, slow at first
+                let t = fade.fraction() * fade.fraction();
+                transform.scale = scale.lerp(scale * FADE_SCALE, t);
+                sprite.color = Color::WHITE.mix(&faded_color(&config), t);
+                fade.just_finished()
+            }
+            None => clips_finished.contains(&entity),
+        };
+        if !finished {
+            continue;
+        }
+        if config.death.corpse_lifetime <= 0.0 {
+            commands.entity(entity).insert(PostDespawn);
+            continue;
+        }
+        transform.translation.z = SpriteOrder::Corpse.z_index();
+        sprite.color = CORPSE_TINT;
+        commands
+            .entity(entity)
+            .remove::<Dying>()
+            .insert(Corpse(Timer::from_seconds(
+                config.death.corpse_lifetime,
+                TimerMode::Once,
+            )));
+    }
+}
+
+/// Removes corpses that lay long enough, and the oldest ones beyond `config.death.max_corpses`.
+fn expire_corpses(
+    mut commands: Commands,
+    mut corpse_query: Query<(Entity, &mut Corpse)>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let mut corpses = Vec::new();
+    for (entity, mut corpse) in corpse_query.iter_mut() {
+        if corpse.0.tick(time.delta()).finished() {
+            commands.entity(entity).insert(PostDespawn);
+        } else {
+            corpses.push((entity, corpse.0.elapsed()));
+        }
+    }
+    let excess = corpses.len().saturating_sub(config.death.max_corpses);
+    corpses.sort_by_key(|(_, elapsed)| Reverse(*elapsed));
+    for (entity, _) in corpses.into_iter().take(excess) {
+        commands.entity(entity).insert(PostDespawn);
+    }
+}
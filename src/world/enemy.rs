@@ -1,16 +1,19 @@
-use crate::animation::*;
-use crate::config::GameConfig;
+use crate::config::{ConfigChanged, GameConfig};
 use crate::resource::GlobalSpriteSheet;
+use crate::rng::GameRng;
+use crate::sprite_manifest::clips;
 use crate::sprite_order::SpriteOrder;
 use crate::state::GameState;
+use crate::world::boss::Boss;
 use crate::world::collision::CollisionLayer;
 use crate::world::damage::*;
-use crate::world::despawn::PostDespawn;
+use crate::world::day_night::DayClock;
+use crate::world::dying::Dying;
 use crate::world::in_game::InGameScoped;
+use crate::world::knockback::*;
 use crate::world::player::Player;
 use avian2d::prelude::*;
 use bevy::prelude::*;
-use bevy::time::common_conditions::on_timer;
 use rand::prelude::SliceRandom;
 use rand::Rng;
 use std::time::Duration;
@@ -26,26 +29,37 @@ pub struct Wander;
 #[derive(Component)]
 pub struct TargetRange(pub f32);
 
+/// The sturdy enemy spawned next to the player when `config.enemy.spawn_dummy` is set.
+#[derive(Component)]
+pub struct TrainingDummy;
+
+/// Overrides `config.enemy.speed` for a single enemy.
+#[derive(Component)]
+pub struct MoveSpeed(pub f32);
+
+/// Counts down to the next enemy wave. Only ticks while the game is running.
+#[derive(Resource)]
+pub struct EnemySpawnTimer(pub Timer);
+
 #[derive(Default)]
 pub struct EnemyPlugin;
 
 impl Enemy {
-    const SPRITE_INDEXES: [i32; 4] = [8, 12, 20, 28];
     pub fn new(
         sheet: &Res<GlobalSpriteSheet>,
         config: &Res<GameConfig>,
+        rng: &mut GameRng,
         player_pos: Vec2,
     ) -> impl Bundle {
-        let mut rng = rand::thread_rng();
-        let (x, y) = get_random_position_around(player_pos);
-        let sprite_index = Self::SPRITE_INDEXES.choose(&mut rng).unwrap();
-        let animation_indices = AnimationIndices::from_length(*sprite_index as usize, 4);
+        let (x, y) = get_random_position_around(rng, player_pos);
+        let clip = clips::ENEMIES.choose(rng).unwrap();
         (
             Enemy,
             TargetRange(config.enemy.follow_range),
             Health::new(config.enemy.health),
             DamageCooldown::new(Duration::from_secs_f32(config.enemy.damage_cooldown)),
             DamageFlash,
+            KnockbackResistance(config.enemy.knockback_resistance),
             Transform::from_xyz(x, y, SpriteOrder::Enemy.z_index()),
             RigidBody::Dynamic,
             LockedAxes::ROTATION_LOCKED,
@@ -54,9 +68,7 @@ impl Enemy {
                 [CollisionLayer::Enemy],
                 [CollisionLayer::Player, CollisionLayer::Bullet],
             ),
-            sheet.0.to_sprite(animation_indices.first),
-            animation_indices,
-            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+            sheet.0.character_sprite(clip),
         )
     }
 }
@@ -69,43 +81,47 @@ impl Default for TargetRange {
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        let spawn_interval = app
-            .world()
-            .get_resource::<GameConfig>()
-            .unwrap()
-            .enemy
-            .spawn_interval;
-        app.add_systems(OnEnter(GameState::Running), spawn_dummy)
+        app.add_systems(OnEnter(GameState::GameInit), reset_enemy_spawn_timer)
+            .add_systems(OnEnter(GameState::Running), spawn_dummy)
             .add_systems(
                 Update,
                 (
-                    on_move,
-                    spawn_enemies.run_if(on_timer(Duration::from_secs_f32(spawn_interval))),
+                    // knockback applied this frame wins over the chase
+                    on_move.after(DamagePhase::After),
+                    spawn_enemies,
                     update_facing,
-                    despawn_enemies,
+                    kill_enemies,
                     draw_enemy_hurt_box,
                 )
                     .run_if(in_state(GameState::Running)),
-            );
+            )
+            .add_systems(Update, apply_config_changes);
     }
 }
 
 fn on_move(
     player_transform: Single<&GlobalTransform, With<Player>>,
     mut enemy_query: Query<
-        (&GlobalTransform, &TargetRange, &mut LinearVelocity),
-        (With<Enemy>, Without<Player>),
+        (
+            &GlobalTransform,
+            &TargetRange,
+            &mut LinearVelocity,
+            Option<&MoveSpeed>,
+        ),
+        (With<Enemy>, Without<Player>, Without<Knockback>),
     >,
+    clock: Res<DayClock>,
     config: Res<GameConfig>,
 ) {
-    for (transform, target_range, mut velocity) in enemy_query.iter_mut() {
+    let night_scale = clock.night_scale(config.day_night.night_speed_multiplier);
+    for (transform, target_range, mut velocity, speed) in enemy_query.iter_mut() {
         let sub = (player_transform.translation() - transform.translation()).truncate();
         if sub.length() > target_range.0 {
             velocity.0 = Vec2::ZERO;
             continue;
         }
         let direction = sub.normalize_or_zero();
-        velocity.0 = direction * config.enemy.speed;
+        velocity.0 = direction * speed.map_or(config.enemy.speed, |speed| speed.0) * night_scale;
     }
 }
 
@@ -118,9 +134,17 @@ fn update_facing(
     }
 }
 
+fn reset_enemy_spawn_timer(mut commands: Commands, config: Res<GameConfig>) {
+    commands.insert_resource(EnemySpawnTimer(Timer::from_seconds(
+        config.enemy.spawn_interval,
+        TimerMode::Repeating,
+    )));
+}
+
 fn spawn_dummy(
     mut commands: Commands,
     texture_atlas: Res<GlobalSpriteSheet>,
+    mut rng: ResMut<GameRng>,
     player_query: Query<&Transform, With<Player>>,
     config: Res<GameConfig>,
 ) {
@@ -133,9 +157,11 @@ fn spawn_dummy(
 
     let player_pos = player_transform.translation.truncate();
     commands
-        .spawn(Enemy::new(&texture_atlas, &config, player_pos))
+        .spawn(Enemy::new(&texture_atlas, &config, &mut rng, player_pos))
         .insert((
+            TrainingDummy,
             Health::new(config.enemy.health * 100.0),
+            KnockbackResistance(1.0),
             Transform::from_xyz(
                 player_pos.x + 100.0,
                 player_pos.y,
@@ -147,10 +173,18 @@ fn spawn_dummy(
 fn spawn_enemies(
     mut commands: Commands,
     texture_atlas: Res<GlobalSpriteSheet>,
+    mut rng: ResMut<GameRng>,
+    mut spawn_timer: ResMut<EnemySpawnTimer>,
     player_query: Query<&Transform, With<Player>>,
     enemy_query: Query<&Transform, (With<Enemy>, Without<Player>)>,
+    clock: Res<DayClock>,
+    time: Res<Time>,
     config: Res<GameConfig>,
 ) {
+    spawn_timer.0.tick(time.delta());
+    if !spawn_timer.0.just_finished() {
+        return;
+    }
     if !config.enemy.spawn_waves {
         return;
     }
@@ -159,29 +193,105 @@ fn spawn_enemies(
     };
 
     let num_enemies = enemy_query.iter().len();
-    let enemy_spawn_count: usize =
-        (config.enemy.spawn_limit - num_enemies).min(config.enemy.spawn_rate_per_second);
-    if enemy_spawn_count <= 0 {
+    // waves grow larger at night
+    let night_scale = clock.night_scale(config.day_night.night_spawn_multiplier);
+    let per_wave = (config.enemy.spawn_rate_per_second as f32 * night_scale).round() as usize;
+    // the limit can be exceeded, e.g. by the dummy and the boss
+    let enemy_spawn_count = config
+        .enemy
+        .spawn_limit
+        .saturating_sub(num_enemies)
+        .min(per_wave);
+    if enemy_spawn_count == 0 {
         return;
     }
 
     let player_pos = player_transform.translation.truncate();
     for _ in 0..enemy_spawn_count {
-        commands.spawn(Enemy::new(&texture_atlas, &config, player_pos));
+        commands.spawn(Enemy::new(&texture_atlas, &config, &mut rng, player_pos));
     }
 }
 
-fn despawn_enemies(mut commands: Commands, enemy_query: Query<(Entity, &Health), With<Enemy>>) {
+/// Turns dead enemies into scenery that plays its death: nothing targets, hits or
+/// counts them any more.
+fn kill_enemies(mut commands: Commands, enemy_query: Query<(Entity, &Health), With<Enemy>>) {
     for (entity, health) in enemy_query.iter() {
         if health.is_alive() {
             continue;
         }
-        commands.entity(entity).insert(PostDespawn);
+        commands
+            .entity(entity)
+            .remove::<(
+                Enemy,
+                Boss,
+                TrainingDummy,
+                TargetRange,
+                MoveSpeed,
+                Health,
+                DamageCooldown,
+                DamageFlash,
+                Knockback,
+                RigidBody,
+                Collider,
+                CollisionLayers,
+            )>()
+            .insert(Dying::default());
+    }
+}
+
+/// Applies config changes to living enemies. Bosses keep their own health and resistance.
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut enemy_query: Query<
+        (
+            &mut Health,
+            &mut DamageCooldown,
+            &mut TargetRange,
+            &mut KnockbackResistance,
+            &mut Collider,
+            Has<TrainingDummy>,
+            Has<Boss>,
+        ),
+        With<Enemy>,
+    >,
+    spawn_timer: Option<ResMut<EnemySpawnTimer>>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    for (mut health, mut cooldown, mut range, mut resistance, mut collider, is_dummy, is_boss) in
+        enemy_query.iter_mut()
+    {
+        if changed.contains("enemy.health") && !is_boss {
+            let multiplier = if is_dummy { 100.0 } else { 1.0 };
+            health.set_max(config.enemy.health * multiplier);
+        }
+        if changed.contains("enemy.knockback_resistance") && !is_boss && !is_dummy {
+            resistance.0 = config.enemy.knockback_resistance;
+        }
+        if changed.contains("enemy.damage_cooldown") {
+            cooldown
+                .0
+                .set_duration(Duration::from_secs_f32(config.enemy.damage_cooldown));
+        }
+        if changed.contains("enemy.follow_range") {
+            range.0 = config.enemy.follow_range;
+        }
+        if changed.contains("enemy.collider_size") {
+            *collider = Collider::rectangle(config.enemy.collider_size, config.enemy.collider_size);
+        }
+    }
+    if changed.contains("enemy.spawn_interval") {
+        if let Some(mut spawn_timer) = spawn_timer {
+            spawn_timer
+                .0
+                .set_duration(Duration::from_secs_f32(config.enemy.spawn_interval));
+        }
     }
 }
 
-fn get_random_position_around(pos: Vec2) -> (f32, f32) {
-    let mut rng = rand::thread_rng();
+fn get_random_position_around(rng: &mut GameRng, pos: Vec2) -> (f32, f32) {
     let radius = rng.gen_range(400.0..1500.0);
     let angle = rng.gen_range(0.0..std::f32::consts::PI * 2.0);
     let x = pos.x + angle.cos() * radius;
@@ -0,0 +1,142 @@
+use crate::config::BulletConfig;
+use crate::resource::GlobalSpriteSheet;
+use crate::sprite_manifest::clips;
+use crate::sprite_order::SpriteOrder;
+use crate::state::GameState;
+use crate::world::collision::CollisionLayer;
+use crate::world::damage::*;
+use crate::world::despawn::PostDespawn;
+use crate::world::in_game::InGameScoped;
+use crate::world::particle::{effects, SpawnParticles};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+/// Marks a projectile that explodes when it hits something or expires.
+#[derive(Component, Clone, Copy)]
+pub struct Explosive {
+    pub radius: f32,
+    pub damage: f32,
+    /// Fraction of the damage lost at the edge of the radius, in `0.0..=1.0`.
+    pub falloff: f32,
+    /// Speed enemies are pushed away from the center with.
+    pub knockback: f32,
+}
+
+#[derive(Event)]
+pub struct ExplosionEvent {
+    pub position: Vec2,
+    pub explosive: Explosive,
+    pub attacker: Option<Entity>,
+    /// The enemy the bullet hit, damaged even though that hit just put it on cooldown.
+    /// Everything else in reach only takes damage when off cooldown.
+    pub direct_hit: Option<Entity>,
+}
+
+/// The visual effect of an explosion, removed once its animation has played through.
+#[derive(Component, Default)]
+#[require(InGameScoped)]
+pub struct Explosion(pub Timer);
+
+#[derive(Default)]
+pub struct ExplosionPlugin;
+
+impl Explosive {
+    /// Returns `None` if bullets are not configured to explode.
+    pub fn from_config(config: &BulletConfig) -> Option<Self> {
+        if config.explosion_radius <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            radius: config.explosion_radius,
+            damage: config.explosion_damage,
+            falloff: config.explosion_falloff.clamp(0.0, 1.0),
+            knockback: config.explosion_knockback,
+        })
+    }
+
+    pub fn damage_at(&self, distance: f32) -> f32 {
+        let ratio = (distance / self.radius).clamp(0.0, 1.0);
+        self.damage * (1.0 - self.falloff * ratio)
+    }
+}
+
+impl Explosion {
+    pub fn new(sheet: &Res<GlobalSpriteSheet>, position: Vec2, radius: f32) -> impl Bundle {
+        let clip = sheet.0.clip(clips::EXPLOSION);
+        (
+            Explosion(Timer::from_seconds(clip.duration(), TimerMode::Once)),
+            Transform::from_xyz(position.x, position.y, SpriteOrder::Explosion.z_index())
+                .with_scale(Vec3::splat(radius * 2.0 / 16.0)),
+            sheet.0.animated_sprite(clips::EXPLOSION),
+        )
+    }
+}
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_state_scoped_event::<ExplosionEvent>(GameState::Running)
+            .add_systems(
+                Update,
+                (
+                    explode.in_set(DamagePhase::Send),
+                    despawn_finished_explosions,
+                )
+                    .run_if(in_state(GameState::Running)),
+            );
+    }
+}
+
+fn explode(
+    mut commands: Commands,
+    sheet: Res<GlobalSpriteSheet>,
+    spatial_query: SpatialQuery,
+    enemy_query: Query<&GlobalTransform>,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    mut damage_events: EventWriter<DamageEvent>,
+    mut particle_events: EventWriter<SpawnParticles>,
+) {
+    let filter = SpatialQueryFilter::from_mask(CollisionLayer::Enemy);
+    for event in explosion_events.read() {
+        let explosive = event.explosive;
+        let shape = Collider::circle(explosive.radius);
+        for target in spatial_query.shape_intersections(&shape, event.position, 0.0, &filter) {
+            let Ok(transform) = enemy_query.get(target) else {
+                continue;
+            };
+            let offset = transform.translation().truncate() - event.position;
+            let mut context = DamageContext::new(
+                explosive.damage_at(offset.length()),
+                DamageType::Explosion,
+                event.attacker,
+            )
+            .with_knockback(offset.normalize_or_zero() * explosive.knockback);
+            if event.direct_hit == Some(target) {
+                context = context.ignoring_cooldown();
+            }
+            damage_events.send(DamageEvent {
+                target,
+                context,
+                apply: true,
+            });
+        }
+        commands.spawn(Explosion::new(&sheet, event.position, explosive.radius));
+        particle_events.send(SpawnParticles::new(
+            effects::EXPLOSION,
+            event.position,
+            Vec2::ZERO,
+        ));
+    }
+}
+
+fn despawn_finished_explosions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Explosion)>,
+) {
+    for (entity, mut explosion) in query.iter_mut() {
+        explosion.0.tick(time.delta());
+        if explosion.0.finished() {
+            commands.entity(entity).insert(PostDespawn);
+        }
+    }
+}
@@ -1,10 +1,14 @@
-use crate::config::GameConfig;
-use crate::input::CursorPosition;
+use crate::config::{ConfigChanged, GameConfig};
+use crate::input::*;
 use crate::resource::*;
+use crate::rng::GameRng;
+use crate::sprite_manifest::sprites;
 use crate::sprite_order::SpriteOrder;
 use crate::state::GameState;
 use crate::world::bullet::*;
+use crate::world::bullet_modifier::BulletModifiers;
 use crate::world::owner::Owner;
+use crate::world::particle::{effects, SpawnParticles};
 use bevy::prelude::*;
 use bevy::sprite::Anchor;
 use std::time::Duration;
@@ -15,6 +19,12 @@ pub struct Gun;
 #[derive(Component)]
 pub struct GunTimer(pub Timer);
 
+/// Sent each time the gun fires, however many bullets the shot has.
+#[derive(Event)]
+pub struct ShotFired {
+    pub position: Vec2,
+}
+
 #[derive(Default)]
 pub struct GunPlugin;
 
@@ -29,10 +39,11 @@ impl Gun {
         (
             Gun,
             GunTimer::new(Duration::from_secs_f32(config.bullet.spawn_interval)),
+            BulletModifiers::from_config(&config.bullet),
             Transform::from_xyz(0.0, -4.0, SpriteOrder::Gun.z_index()),
             Sprite {
                 anchor: Anchor::Custom(Vec2::new(-6.0 / 16.0, 0.0)),
-                ..sheet.0.to_sprite(17)
+                ..sheet.0.sprite(sprites::GUN)
             },
         )
     }
@@ -40,10 +51,43 @@ impl Gun {
 
 impl Plugin for GunPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (on_shoot, update_gun_rotation).run_if(in_state(GameState::Running)),
-        );
+        app.add_event::<ShotFired>()
+            .add_systems(
+                Update,
+                (on_shoot, update_gun_rotation)
+                    .after(InputSet::Observe)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(Update, apply_config_changes);
+    }
+}
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut gun_query: Query<(&mut GunTimer, &mut BulletModifiers), With<Gun>>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    for (mut timer, mut modifiers) in gun_query.iter_mut() {
+        if changed.contains("bullet.spawn_interval") {
+            timer
+                .0
+                .set_duration(Duration::from_secs_f32(config.bullet.spawn_interval));
+        }
+        // the explosion settings live next to the modifiers, not under them
+        let explosion_changed = [
+            "bullet.explosion_radius",
+            "bullet.explosion_damage",
+            "bullet.explosion_falloff",
+            "bullet.explosion_knockback",
+        ]
+        .iter()
+        .any(|key| changed.contains(key));
+        if changed.contains("bullet.modifiers") || explosion_changed {
+            *modifiers = BulletModifiers::from_config(&config.bullet);
+        }
     }
 }
 
@@ -64,18 +108,21 @@ fn update_gun_rotation(
 fn on_shoot(
     mut commands: Commands,
     texture_atlas: Res<GlobalSpriteSheet>,
-    mouse_input: Res<ButtonInput<MouseButton>>,
-    mut gun_query: Query<(&Owner, &GlobalTransform, &mut GunTimer), With<Gun>>,
+    mut rng: ResMut<GameRng>,
+    action_state: Res<ActionState>,
+    mut gun_query: Query<(&Owner, &GlobalTransform, &mut GunTimer, &BulletModifiers), With<Gun>>,
+    mut shot_events: EventWriter<ShotFired>,
+    mut particle_events: EventWriter<SpawnParticles>,
     time: Res<Time>,
     config: Res<GameConfig>,
 ) {
-    let Ok((owner, gun_transform, mut gun_timer)) = gun_query.get_single_mut() else {
+    let Ok((owner, gun_transform, mut gun_timer, modifiers)) = gun_query.get_single_mut() else {
         return;
     };
 
     gun_timer.0.tick(time.delta());
 
-    if !mouse_input.pressed(MouseButton::Left) {
+    if !action_state.pressed(Action::Fire) {
         return;
     }
 
@@ -86,15 +133,28 @@ fn on_shoot(
 
     let gun_pos = gun_transform.translation().truncate();
     let gun_dir = gun_transform.right().truncate();
+    shot_events.send(ShotFired { position: gun_pos });
+    particle_events.send(SpawnParticles::new(
+        effects::MUZZLE_FLASH,
+        gun_pos + gun_dir * 12.0,
+        gun_dir,
+    ));
 
     for _ in 0..config.bullet.num_per_shot {
-        commands.spawn((
-            Bullet::new(&texture_atlas, &config, gun_dir, gun_pos + gun_dir * 12.0),
+        let mut bullet_commands = commands.spawn((
+            Bullet::new(
+                &texture_atlas,
+                &config,
+                &mut rng,
+                gun_dir,
+                gun_pos + gun_dir * 12.0,
+            ),
             // Lifespan::new(Duration::from_secs_f32(config.bullet.lifetime)),
             MaxTravelDistance(160.0),
             SpawnPoint(gun_pos),
             Owner(owner.0),
             DespawnOnHit,
         ));
+        modifiers.apply(&mut bullet_commands);
     }
 }
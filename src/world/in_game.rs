@@ -1,14 +1,18 @@
 use crate::config::GameConfig;
 use crate::resource::*;
+use crate::rng::{reseed_rng, CosmeticRng};
+use crate::sprite_manifest::sprites;
 use crate::sprite_order::SpriteOrder;
 use crate::state::*;
 use crate::world::collision::*;
 use crate::world::damage::Health;
 use crate::world::despawn::*;
 use crate::world::gun::Gun;
+use crate::world::melee::MeleeWeapon;
 use crate::world::owner::Owner;
 use crate::world::player::Player;
 use bevy::prelude::*;
+use rand::prelude::SliceRandom;
 use rand::Rng;
 
 #[derive(Component, Default)]
@@ -20,7 +24,7 @@ pub struct InGamePlugin;
 impl Plugin for InGamePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(PostUpdate, despawn_recursive::<PostDespawn>)
-            .add_systems(OnEnter(GameState::GameInit), setup_world)
+            .add_systems(OnEnter(GameState::GameInit), setup_world.after(reseed_rng))
             .add_systems(OnEnter(GameState::Running), enable_rigid_bodies)
             .add_systems(OnExit(GameState::Running), disable_rigid_bodies)
             .add_systems(OnExit(AppState::InGame), despawn_recursive::<InGameScoped>)
@@ -39,11 +43,12 @@ fn setup_world(
     config: Res<GameConfig>,
 ) {
     let mut player_commands = commands.spawn(Player::new(&texture_atlas, &config));
-    player_commands.with_child((
-        Gun::new(&texture_atlas, &config),
-        Owner(player_commands.id()),
-    ));
-    // spawn_world_decorations(&mut commands, &texture_atlas, &config);
+    let player = player_commands.id();
+    player_commands.with_children(|parent| {
+        parent.spawn((Gun::new(&texture_atlas, &config), Owner(player)));
+        parent.spawn((MeleeWeapon::new(&texture_atlas, &config), Owner(player)));
+    });
+    // spawn_world_decorations(&mut commands, &texture_atlas, &config, &mut rng);
     next_state.set(GameState::Running);
 }
 
@@ -51,15 +56,15 @@ fn spawn_world_decorations(
     commands: &mut Commands,
     sheet: &Res<GlobalSpriteSheet>,
     config: &Res<GameConfig>,
+    rng: &mut CosmeticRng,
 ) {
-    let mut rng = rand::thread_rng();
     for _ in 0..config.world.num_world_decorations {
         let x = rng.gen_range(-config.world.world_width..config.world.world_width);
         let y = rng.gen_range(-config.world.world_height..config.world.world_height);
-        let sprite_index = rng.gen_range(24..=25);
+        let sprite = sprites::GRASS.choose(rng).unwrap();
         commands.spawn((
             InGameScoped,
-            sheet.0.to_sprite(sprite_index),
+            sheet.0.sprite(sprite),
             Transform::from_xyz(x, y, SpriteOrder::GRASS.z_index()),
         ));
     }
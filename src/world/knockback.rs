@@ -0,0 +1,83 @@
+use crate::state::GameState;
+use crate::world::damage::*;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+/// Fraction of incoming knockback that is ignored, in `0.0..=1.0`.
+#[derive(Component, Default, Clone, Copy)]
+pub struct KnockbackResistance(pub f32);
+
+/// An entity being pushed back. While present, the entity's own movement is suspended.
+#[derive(Component)]
+pub struct Knockback {
+    pub velocity: Vec2,
+    pub timer: Timer,
+}
+
+#[derive(Default)]
+pub struct KnockbackPlugin;
+
+impl Knockback {
+    const DURATION: f32 = 0.15;
+
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            timer: Timer::from_seconds(Self::DURATION, TimerMode::Once),
+        }
+    }
+}
+
+impl Plugin for KnockbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (apply_knockback.in_set(DamagePhase::After), update_knockback)
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    mut event_reader: EventReader<DamageEvent>,
+    mut target_query: Query<(&mut LinearVelocity, Option<&KnockbackResistance>)>,
+) {
+    for event in event_reader.read() {
+        if !event.apply {
+            continue;
+        }
+        let Some(knockback) = event.context.knockback else {
+            continue;
+        };
+        let Ok((mut velocity, resistance)) = target_query.get_mut(event.target) else {
+            continue;
+        };
+        let resistance = resistance.map_or(0.0, |r| r.0.clamp(0.0, 1.0));
+        let knockback = knockback * (1.0 - resistance);
+        if knockback == Vec2::ZERO {
+            continue;
+        }
+        velocity.0 = knockback;
+        commands
+            .entity(event.target)
+            .insert(Knockback::new(knockback));
+    }
+}
+
+fn update_knockback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Knockback, &mut LinearVelocity)>,
+) {
+    for (entity, mut knockback, mut velocity) in query.iter_mut() {
+        knockback.timer.tick(time.delta());
+        if knockback.timer.finished() {
+            velocity.0 = Vec2::ZERO;
+            commands.entity(entity).remove::<Knockback>();
+            continue;
+        }
+        // ease out so the push fades instead of stopping abruptly
+        velocity.0 = knockback.velocity * knockback.timer.fraction_remaining();
+    }
+}
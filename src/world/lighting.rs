@@ -0,0 +1,313 @@
+use crate::camera::SmoothCamera;
+use crate::config::{ConfigChanged, GameConfig, LightConfig};
+use crate::state::GameState;
+use crate::world::day_night::DayClock;
+use crate::world::despawn::PostDespawn;
+use crate::world::explosion::ExplosionEvent;
+use crate::world::gun::ShotFired;
+use crate::world::in_game::InGameScoped;
+use crate::world::player::Player;
+use bevy::prelude::*;
+use bevy::render::mesh::MeshVertexBufferLayoutRef;
+use bevy::render::render_resource::{
+    AsBindGroup, BlendComponent, BlendFactor, BlendOperation, BlendState, RenderPipelineDescriptor,
+    ShaderRef, ShaderType, SpecializedMeshPipelineError,
+};
+use bevy::sprite::{AlphaMode2d, Material2d, Material2dKey, Material2dPlugin};
+use bevy::transform::TransformSystem;
+
+/// The fragment shader of the lighting, loaded with the other assets.
+pub const LIGHTING_SHADER: &str = "lighting_shader.wgsl";
+
+/// Lights beyond this many, the farthest from the camera, are left out.
+/// The shader has the same limit.
+pub const MAX_LIGHTS: usize = 32;
+
+/// In front of every sprite, so that it can darken them.
+const LIGHTING_Z: f32 = 500.0;
+
+/// How much larger than the view the overlay is, so that it still covers it while
+/// the camera shakes.
+const OVERLAY_MARGIN: f32 = 1.2;
+
+/// Brightens the scene around the entity while lighting is on.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct PointLight2d {
+    pub color: Color,
+    /// Distance in pixels at which the light is gone.
+    pub radius: f32,
+    pub intensity: f32,
+}
+
+/// Fades a light out over the timer, then despawns it.
+#[derive(Component, Debug)]
+pub struct LightFade(pub Timer);
+
+/// The screen-covering quad the lighting is drawn with.
+#[derive(Component)]
+#[require(InGameScoped)]
+pub struct LightingOverlay;
+
+/// Multiplies the scene below it by the ambient color and the point lights.
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone, Default)]
+pub struct Lighting {
+    #[uniform(0)]
+    uniform: LightingUniform,
+}
+
+#[derive(ShaderType, Debug, Clone, Default)]
+struct LightingUniform {
+    ambient: Vec4,
+    light_count: u32,
+    lights: [GpuLight; MAX_LIGHTS],
+}
+
+#[derive(ShaderType, Debug, Clone, Copy, Default)]
+struct GpuLight {
+    position: Vec2,
+    radius: f32,
+    intensity: f32,
+    color: Vec4,
+}
+
+/// Lights the scene with an ambient color that follows the [`DayClock`], a light
+/// around the player and flashes for shots and explosions. With `lighting.enabled`
+/// off, the overlay is hidden and the scene is drawn unlit.
+#[derive(Default)]
+pub struct LightingPlugin;
+
+impl PointLight2d {
+    pub fn from_config(config: &LightConfig) -> Self {
+        let [r, g, b] = config.color;
+        Self {
+            color: Color::srgb(r, g, b),
+            radius: config.radius,
+            intensity: config.intensity,
+        }
+    }
+}
+
+impl Lighting {
+    /// The positions of the lights drawn, nearest to the camera first.
+    pub fn light_positions(&self) -> Vec<Vec2> {
+        let uniform = &self.uniform;
+        uniform.lights[..uniform.light_count as usize]
+            .iter()
+            .map(|light| light.position)
+            .collect()
+    }
+}
+
+impl Material2d for Lighting {
+    fn fragment_shader() -> ShaderRef {
+        LIGHTING_SHADER.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        // drawn after the sprites, in the order of their z
+        AlphaMode2d::Blend
+    }
+
+    fn specialize(
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayoutRef,
+        _key: Material2dKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // multiplies what is already drawn instead of drawing over it
+        let target = descriptor
+            .fragment
+            .as_mut()
+            .and_then(|fragment| fragment.targets.first_mut())
+            .and_then(Option::as_mut);
+        if let Some(target) = target {
+            target.blend = Some(BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::Dst,
+                    dst_factor: BlendFactor::Zero,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent::OVER,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(Material2dPlugin::<Lighting>::default())
+            .add_systems(OnEnter(GameState::GameInit), spawn_lighting)
+            .add_systems(
+                Update,
+                (add_player_light, spawn_flash_lights, fade_lights)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    follow_view.before(TransformSystem::TransformPropagate),
+                    // where the lights ended up this frame
+                    upload_lights.after(TransformSystem::TransformPropagate),
+                ),
+            )
+            .add_systems(Update, apply_config_changes);
+    }
+}
+
+fn srgb([r, g, b]: [f32; 3]) -> Color {
+    Color::srgb(r, g, b)
+}
+
+fn spawn_lighting(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut lighting_materials: ResMut<Assets<Lighting>>,
+) {
+    commands.spawn((
+        LightingOverlay,
+        Mesh2d(meshes.add(Rectangle::default())),
+        MeshMaterial2d(lighting_materials.add(Lighting::default())),
+        Transform::from_xyz(0.0, 0.0, LIGHTING_Z),
+        // shown by `follow_view` while lighting is on
+        Visibility::Hidden,
+    ));
+}
+
+fn add_player_light(
+    mut commands: Commands,
+    player_query: Query<Entity, Added<Player>>,
+    config: Res<GameConfig>,
+) {
+    for player in player_query.iter() {
+        commands
+            .entity(player)
+            .insert(PointLight2d::from_config(&config.lighting.player_light));
+    }
+}
+
+fn spawn_flash_lights(
+    mut commands: Commands,
+    mut shot_events: EventReader<ShotFired>,
+    mut explosion_events: EventReader<ExplosionEvent>,
+    config: Res<GameConfig>,
+) {
+    let lighting = &config.lighting;
+    if !lighting.enabled {
+        shot_events.clear();
+        explosion_events.clear();
+        return;
+    }
+    let shots = shot_events
+        .read()
+        .map(|shot| (shot.position, &lighting.muzzle_flash_light));
+    let explosions = explosion_events
+        .read()
+        .map(|explosion| (explosion.position, &lighting.explosion_light));
+    for (position, light) in shots.chain(explosions) {
+        commands.spawn((
+            PointLight2d::from_config(light),
+            LightFade(Timer::from_seconds(light.duration, TimerMode::Once)),
+            Transform::from_translation(position.extend(0.0)),
+            InGameScoped,
+        ));
+    }
+}
+
+fn fade_lights(
+    mut commands: Commands,
+    mut fade_query: Query<(Entity, &mut LightFade)>,
+    time: Res<Time>,
+) {
+    for (entity, mut fade) in fade_query.iter_mut() {
+        if fade.0.tick(time.delta()).finished() {
+            commands.entity(entity).insert(PostDespawn);
+        }
+    }
+}
+
+/// Keeps the overlay over the view, or hides it while lighting is off.
+fn follow_view(
+    mut overlay_query: Query<(&mut Transform, &mut Visibility), With<LightingOverlay>>,
+    camera_query: Query<
+        (&Transform, &OrthographicProjection),
+        (With<SmoothCamera>, Without<LightingOverlay>),
+    >,
+    config: Res<GameConfig>,
+) {
+    let Ok((mut transform, mut visibility)) = overlay_query.get_single_mut() else {
+        return;
+    };
+    if !config.lighting.enabled {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    }
+    let Ok((camera_transform, projection)) = camera_query.get_single() else {
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+    transform.translation = camera_transform.translation.truncate().extend(LIGHTING_Z);
+    transform.rotation = camera_transform.rotation;
+    transform.scale = (projection.area.size() * OVERLAY_MARGIN).extend(1.0);
+}
+
+/// Uploads the ambient color and the lights nearest to the camera.
+fn upload_lights(
+    overlay_query: Query<&MeshMaterial2d<Lighting>, With<LightingOverlay>>,
+    camera_query: Query<&GlobalTransform, With<SmoothCamera>>,
+    light_query: Query<(&GlobalTransform, &PointLight2d, Option<&LightFade>)>,
+    mut lighting_materials: ResMut<Assets<Lighting>>,
+    clock: Res<DayClock>,
+    config: Res<GameConfig>,
+) {
+    let lighting = &config.lighting;
+    if !lighting.enabled {
+        return;
+    }
+    let (Ok(material), Ok(camera_transform)) =
+        (overlay_query.get_single(), camera_query.get_single())
+    else {
+        return;
+    };
+    let Some(material) = lighting_materials.get_mut(&material.0) else {
+        return;
+    };
+
+    let center = camera_transform.translation().truncate();
+    let mut lights: Vec<GpuLight> = light_query
+        .iter()
+        .map(|(light_transform, light, fade)| GpuLight {
+            position: light_transform.translation().truncate(),
+            radius: light.radius,
+            intensity: light.intensity * fade.map_or(1.0, |fade| fade.0.fraction_remaining()),
+            color: light.color.to_linear().to_vec4(),
+        })
+        .collect();
+    lights.sort_by(|a, b| {
+        let distance = |light: &GpuLight| light.position.distance_squared(center);
+        distance(a).total_cmp(&distance(b))
+    });
+
+    let ambient = srgb(lighting.night_ambient).mix(&srgb(lighting.day_ambient), clock.daylight());
+    let uniform = &mut material.uniform;
+    uniform.ambient = ambient.to_linear().to_vec4();
+    uniform.light_count = lights.len().min(MAX_LIGHTS) as u32;
+    for (slot, light) in uniform.lights.iter_mut().zip(lights) {
+        *slot = light;
+    }
+}
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut light_query: Query<&mut PointLight2d, With<Player>>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    if !changed.contains("lighting.player_light") {
+        return;
+    }
+    for mut light in light_query.iter_mut() {
+        *light = PointLight2d::from_config(&config.lighting.player_light);
+    }
+}
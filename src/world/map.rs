@@ -1,5 +1,7 @@
 use crate::config::{GameConfig, MapConfig};
 use crate::resource::TileSet;
+use crate::rng::{reseed_rng, GameRng};
+use crate::sprite_manifest::tiles;
 use crate::sprite_order::SpriteOrder;
 use crate::state::GameState;
 use crate::world::in_game::InGameScoped;
@@ -7,19 +9,68 @@ use avian2d::math::PI;
 use bevy::prelude::*;
 use bevy_ecs_tilemap::prelude::*;
 use noise::{NoiseFn, Perlin};
-use rand::Rng;
 
 #[derive(Default)]
 pub struct MapPlugin;
 
+/// The area covered by the generated map, centered on the origin.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct MapBounds(pub Rect);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Terrain {
+    Grass,
+    Sand,
+    Water,
+}
+
+/// The terrain of every tile of the generated map, indexed by `[x][y]` from the
+/// bottom left, as the tilemap lays them out around the origin.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct MapTiles {
+    pub tile_size: f32,
+    pub terrain: Vec<Vec<Terrain>>,
+}
+
+impl MapTiles {
+    /// The number of tiles along each axis.
+    pub fn size(&self) -> UVec2 {
+        let height = self.terrain.first().map_or(0, Vec::len);
+        UVec2::new(self.terrain.len() as u32, height as u32)
+    }
+
+    /// The terrain of a tile, `None` outside the map.
+    pub fn get(&self, tile: IVec2) -> Option<Terrain> {
+        let x = usize::try_from(tile.x).ok()?;
+        let y = usize::try_from(tile.y).ok()?;
+        self.terrain.get(x)?.get(y).copied()
+    }
+
+    /// The tile under a world position, which may be outside the map.
+    pub fn tile_at(&self, position: Vec2) -> IVec2 {
+        (position / self.tile_size + self.size().as_vec2() / 2.0)
+            .floor()
+            .as_ivec2()
+    }
+
+    pub fn terrain_at(&self, position: Vec2) -> Option<Terrain> {
+        self.get(self.tile_at(position))
+    }
+}
+
 impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(TilemapPlugin)
-            .add_systems(OnEnter(GameState::GameInit), spawn_map);
+            .add_systems(OnEnter(GameState::GameInit), spawn_map.after(reseed_rng));
     }
 }
 
-pub fn spawn_map(mut commands: Commands, sheet: Res<TileSet>, config: Res<GameConfig>) {
+pub fn spawn_map(
+    mut commands: Commands,
+    sheet: Res<TileSet>,
+    rng: Res<GameRng>,
+    config: Res<GameConfig>,
+) {
     let tile_size = config.basic.tile_size;
 
     let mut parent_commands = commands.spawn(InGameScoped);
@@ -31,7 +82,10 @@ pub fn spawn_map(mut commands: Commands, sheet: Res<TileSet>, config: Res<GameCo
     };
     let mut tile_storage = TileStorage::empty(map_size);
 
-    let map_data = get_map(map_size.x, map_size.y, &config.map);
+    let terrain = get_map(map_size.x, map_size.y, rng.seed(), &config.map);
+    let manifest = &sheet.0.manifest;
+    let [grass, sand, water] =
+        [tiles::GRASS, tiles::SAND, tiles::WATER].map(|name| manifest.index(name) as u32);
 
     parent_commands.with_children(|parent| {
         for x in 0..map_size.x {
@@ -41,7 +95,11 @@ pub fn spawn_map(mut commands: Commands, sheet: Res<TileSet>, config: Res<GameCo
                     .spawn(TileBundle {
                         position: tile_pos,
                         tilemap_id: TilemapId(map_parent_entity),
-                        texture_index: TileTextureIndex(map_data[x as usize][y as usize]),
+                        texture_index: TileTextureIndex(match terrain[x as usize][y as usize] {
+                            Terrain::Grass => grass,
+                            Terrain::Sand => sand,
+                            Terrain::Water => water,
+                        }),
                         ..Default::default()
                     })
                     .id();
@@ -50,6 +108,10 @@ pub fn spawn_map(mut commands: Commands, sheet: Res<TileSet>, config: Res<GameCo
         }
     });
 
+    let map_extent = Vec2::new(map_size.x as f32, map_size.y as f32) * tile_size;
+    commands.insert_resource(MapBounds(Rect::from_center_size(Vec2::ZERO, map_extent)));
+    commands.insert_resource(MapTiles { tile_size, terrain });
+
     let map_tile_size = TilemapTileSize::new(tile_size, tile_size);
     let grid_size = map_tile_size.into();
     let map_type = TilemapType::Square;
@@ -71,10 +133,10 @@ pub fn spawn_map(mut commands: Commands, sheet: Res<TileSet>, config: Res<GameCo
     });
 }
 
-fn get_map(width: u32, height: u32, map_config: &MapConfig) -> Vec<Vec<u32>> {
-    let mut rng = rand::thread_rng();
-    let seed = rng.gen();
-    let perlin = Perlin::new(seed);
+fn get_map(width: u32, height: u32, seed: u64, map_config: &MapConfig) -> Vec<Vec<Terrain>> {
+    // derived from the run seed without drawing from the game's random stream,
+    // so the map stays the same whether or not it is spawned, e.g. in headless runs
+    let perlin = Perlin::new(seed as u32);
 
     let mut min = f32::MAX;
     let mut max = f32::MIN;
@@ -83,18 +145,18 @@ fn get_map(width: u32, height: u32, map_config: &MapConfig) -> Vec<Vec<u32>> {
     for x in 0..width {
         let mut row = Vec::with_capacity(height as usize);
         for y in 0..height {
-            let value: u32 = {
+            let value = {
                 let heightmap = noise(&perlin, map_config.scale, x, y, width / 2, height / 2);
 
                 min = min.min(heightmap);
                 max = max.max(heightmap);
 
                 if heightmap > map_config.grass_height {
-                    0 // grass
+                    Terrain::Grass
                 } else if heightmap > map_config.sand_height {
-                    1 // sand
+                    Terrain::Sand
                 } else {
-                    2 // water
+                    Terrain::Water
                 }
             };
 
@@ -0,0 +1,241 @@
+use crate::animation::{AnimationState, Animator};
+use crate::config::{ConfigChanged, GameConfig};
+use crate::input::*;
+use crate::resource::*;
+use crate::sprite_manifest::sprites;
+use crate::sprite_order::SpriteOrder;
+use crate::state::GameState;
+use crate::world::collision::CollisionLayer;
+use crate::world::damage::*;
+use crate::world::owner::Owner;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use std::collections::HashSet;
+use std::time::Duration;
+
+#[derive(Component)]
+pub struct MeleeWeapon;
+
+#[derive(Component)]
+pub struct MeleeTimer(pub Timer);
+
+/// An ongoing swing. The active edge sweeps from one side of the arc to the other,
+/// and every enemy it passes over is hit once.
+#[derive(Component)]
+pub struct MeleeSwing {
+    /// Angle of the center of the arc, in radians.
+    pub direction: f32,
+    pub timer: Timer,
+    pub hit: HashSet<Entity>,
+}
+
+#[derive(Default)]
+pub struct MeleePlugin;
+
+impl MeleeTimer {
+    pub fn new(cooldown: Duration) -> Self {
+        let mut timer = Timer::new(cooldown, TimerMode::Once);
+        // the first swing is available immediately
+        timer.tick(cooldown);
+        Self(timer)
+    }
+}
+
+impl MeleeWeapon {
+    pub fn new(sheet: &Res<GlobalSpriteSheet>, config: &Res<GameConfig>) -> impl Bundle {
+        (
+            MeleeWeapon,
+            MeleeTimer::new(Duration::from_secs_f32(config.melee.cooldown)),
+            Transform::from_xyz(0.0, -2.0, SpriteOrder::Gun.z_index()),
+            Visibility::Hidden,
+            Sprite {
+                anchor: Anchor::Custom(Vec2::new(-5.0 / 16.0, 0.0)),
+                ..sheet.0.sprite(sprites::MELEE)
+            },
+        )
+    }
+}
+
+impl MeleeSwing {
+    fn new(direction: f32, duration: f32) -> Self {
+        Self {
+            direction,
+            timer: Timer::from_seconds(duration, TimerMode::Once),
+            hit: HashSet::new(),
+        }
+    }
+
+    /// Angle of the swing's leading edge relative to `direction`.
+    fn edge(&self, arc: f32) -> f32 {
+        -arc / 2.0 + arc * self.timer.fraction()
+    }
+}
+
+impl Plugin for MeleePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_swing.after(InputSet::Observe),
+                (update_swing, finish_swing)
+                    .chain()
+                    .in_set(DamagePhase::Send),
+                draw_swing_arc,
+            )
+                .run_if(in_state(GameState::Running)),
+        )
+        .add_systems(Update, apply_config_changes);
+    }
+}
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut weapon_query: Query<&mut MeleeTimer, With<MeleeWeapon>>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    if !changed.contains("melee.cooldown") {
+        return;
+    }
+    for mut timer in weapon_query.iter_mut() {
+        timer
+            .0
+            .set_duration(Duration::from_secs_f32(config.melee.cooldown));
+    }
+}
+
+fn start_swing(
+    mut commands: Commands,
+    action_state: Res<ActionState>,
+    cursor_position: Res<CursorPosition>,
+    mut weapon_query: Query<
+        (
+            Entity,
+            &Owner,
+            &GlobalTransform,
+            &mut MeleeTimer,
+            &mut Visibility,
+            Has<MeleeSwing>,
+        ),
+        With<MeleeWeapon>,
+    >,
+    mut animator_query: Query<&mut Animator>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let Ok((entity, owner, transform, mut timer, mut visibility, swinging)) =
+        weapon_query.get_single_mut()
+    else {
+        return;
+    };
+
+    // the cooldown also runs during a swing
+    timer.0.tick(time.delta());
+
+    if swinging || !action_state.just_pressed(Action::Melee) || !timer.0.finished() {
+        return;
+    }
+    timer.0.reset();
+
+    let origin = transform.translation().truncate();
+    let direction = (cursor_position.0 - origin)
+        .normalize_or(Vec2::X)
+        .to_angle();
+    *visibility = Visibility::Inherited;
+    if let Ok(mut animator) = animator_query.get_mut(owner.0) {
+        animator.request(AnimationState::Attack);
+    }
+    commands
+        .entity(entity)
+        .insert(MeleeSwing::new(direction, config.melee.swing_duration));
+}
+
+fn update_swing(
+    spatial_query: SpatialQuery,
+    mut weapon_query: Query<(&Owner, &GlobalTransform, &mut Transform, &mut MeleeSwing)>,
+    enemy_query: Query<&GlobalTransform>,
+    mut damage_events: EventWriter<DamageEvent>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let arc = config.melee.arc.to_radians();
+    let filter = SpatialQueryFilter::from_mask(CollisionLayer::Enemy);
+    for (owner, global_transform, mut transform, mut swing) in weapon_query.iter_mut() {
+        let from = swing.edge(arc);
+        swing.timer.tick(time.delta());
+        let to = swing.edge(arc);
+        transform.rotation = Quat::from_rotation_z(swing.direction + to);
+
+        let origin = global_transform.translation().truncate();
+        // only hit enemies the leading edge passed over during this frame
+        let Some(shape) = swept_sector(swing.direction, from, to, config.melee.range) else {
+            continue;
+        };
+        for target in spatial_query.shape_intersections(&shape, origin, 0.0, &filter) {
+            if swing.hit.contains(&target) {
+                continue;
+            }
+            let Ok(target_transform) = enemy_query.get(target) else {
+                continue;
+            };
+            let offset = target_transform.translation().truncate() - origin;
+            swing.hit.insert(target);
+            damage_events.send(DamageEvent {
+                target,
+                context: DamageContext::new(config.melee.damage, DamageType::Melee, Some(owner.0))
+                    .with_knockback(offset.normalize_or_zero() * config.melee.knockback),
+                apply: true,
+            });
+        }
+    }
+}
+
+/// The slice of a circle of `range` between the angles `direction + from` and
+/// `direction + to`, or `None` if the angles are the same.
+fn swept_sector(direction: f32, from: f32, to: f32, range: f32) -> Option<Collider> {
+    if to <= from {
+        return None;
+    }
+    // a corner every 10 degrees keeps the arc close to round
+    let corners = ((to - from) / 10f32.to_radians()).ceil().max(1.0) as usize;
+    let mut points = vec![Vec2::ZERO];
+    points.extend((0..=corners).map(|corner| {
+        let angle = direction + from + (to - from) * corner as f32 / corners as f32;
+        Vec2::from_angle(angle) * range
+    }));
+    Collider::convex_hull(points)
+}
+
+fn finish_swing(
+    mut commands: Commands,
+    mut weapon_query: Query<(Entity, &MeleeSwing, &mut Visibility)>,
+) {
+    for (entity, swing, mut visibility) in weapon_query.iter_mut() {
+        if !swing.timer.finished() {
+            continue;
+        }
+        *visibility = Visibility::Hidden;
+        commands.entity(entity).remove::<MeleeSwing>();
+    }
+}
+
+fn draw_swing_arc(
+    mut gizmos: Gizmos,
+    weapon_query: Query<(&GlobalTransform, &MeleeSwing)>,
+    config: Res<GameConfig>,
+) {
+    let arc = config.melee.arc.to_radians();
+    for (transform, swing) in weapon_query.iter() {
+        // `arc_2d` is centered on the +Y axis
+        let rotation = swing.direction - std::f32::consts::FRAC_PI_2;
+        gizmos.arc_2d(
+            Isometry2d::new(transform.translation().truncate(), Rot2::radians(rotation)),
+            arc,
+            config.melee.range,
+            Color::srgb(1.0, 0.0, 0.0),
+        );
+    }
+}
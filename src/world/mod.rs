@@ -1,14 +1,23 @@
 use bevy::app::plugin_group;
 
+pub mod boss;
 pub mod bullet;
+pub mod bullet_modifier;
 pub mod collision;
 pub mod damage;
+pub mod day_night;
 pub mod despawn;
+pub mod dying;
 pub mod enemy;
+pub mod explosion;
 pub mod gun;
 pub mod in_game;
+pub mod knockback;
+pub mod lighting;
 pub mod map;
+pub mod melee;
 pub mod owner;
+pub mod particle;
 pub mod player;
 pub mod water;
 
@@ -16,11 +25,19 @@ plugin_group! {
     pub struct WorldPlugins{
         map:::MapPlugin,
         water:::WaterPlugin,
+        lighting:::LightingPlugin,
         player:::PlayerPlugin,
         gun:::GunPlugin,
+        melee:::MeleePlugin,
         bullet:::BulletPlugin,
         enemy:::EnemyPlugin,
+        boss:::BossPlugin,
+        explosion:::ExplosionPlugin,
+        dying:::DyingPlugin,
+        particle:::ParticlePlugin,
         in_game:::InGamePlugin,
+        day_night:::DayNightPlugin,
         damage:::DamagePlugin,
+        knockback:::KnockbackPlugin,
     }
 }
@@ -0,0 +1,258 @@
+use crate::config::{GameConfig, ParticleEffectConfig};
+use crate::resource::{GlobalSpriteSheet, SpriteSheet};
+use crate::rng::CosmeticRng;
+use crate::sprite_manifest::SpriteClip;
+use crate::sprite_order::SpriteOrder;
+use crate::state::GameState;
+use crate::world::despawn::PostDespawn;
+use crate::world::in_game::InGameScoped;
+use bevy::prelude::*;
+use rand::Rng;
+
+/// Names of the effects emitted by the game, see `[particles.effects]` in the config.
+pub mod effects {
+    pub const MUZZLE_FLASH: &str = "muzzle_flash";
+    pub const BULLET_HIT: &str = "bullet_hit";
+    pub const EXPLOSION: &str = "explosion";
+    pub const ENEMY_DEATH: &str = "enemy_death";
+    pub const BOSS_BULLET_TRAIL: &str = "boss_bullet_trail";
+}
+
+/// Cosmetic sprite particles, emitted in bursts for gameplay events or continuously
+/// by [`ParticleEmitter`]s. They use [`CosmeticRng`], so runs stay repeatable.
+#[derive(Default)]
+pub struct ParticlePlugin;
+
+/// Asks for a burst of an effect of the registry, by name.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct SpawnParticles {
+    pub effect: String,
+    pub position: Vec2,
+    /// The middle of the effect's cone.
+    pub direction: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitMode {
+    /// The effect's `count` at once, then the emitter is removed.
+    Burst,
+    /// The effect's `rate` per second, for as long as the emitter exists.
+    Continuous,
+}
+
+/// Emits the particles of an effect from the entity's position.
+#[derive(Component, Debug, Clone)]
+pub struct ParticleEmitter {
+    pub effect: String,
+    pub mode: EmitMode,
+    pub direction: Vec2,
+    /// Particles a continuous emitter owes from earlier frames.
+    pending: f32,
+}
+
+/// A single particle, flying on its own until its lifetime is over.
+#[derive(Component, Debug, Clone)]
+#[require(InGameScoped)]
+pub struct Particle {
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    drag: f32,
+    colors: [Color; 2],
+    scales: [f32; 2],
+    frames: SpriteClip,
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParticleSet;
+
+/// Emits particles until `budget` runs out.
+struct Emission<'a> {
+    sheet: &'a SpriteSheet,
+    rng: &'a mut CosmeticRng,
+    budget: usize,
+}
+
+impl SpawnParticles {
+    pub fn new(effect: &str, position: Vec2, direction: Vec2) -> Self {
+        Self {
+            effect: effect.to_string(),
+            position,
+            direction,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    pub fn burst(effect: &str) -> Self {
+        Self::new(effect, EmitMode::Burst)
+    }
+
+    pub fn continuous(effect: &str) -> Self {
+        Self::new(effect, EmitMode::Continuous)
+    }
+
+    fn new(effect: &str, mode: EmitMode) -> Self {
+        Self {
+            effect: effect.to_string(),
+            mode,
+            direction: Vec2::ZERO,
+            pending: 0.0,
+        }
+    }
+
+    pub fn with_direction(mut self, direction: Vec2) -> Self {
+        self.direction = direction;
+        self
+    }
+}
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnParticles>().add_systems(
+            Update,
+            (
+                emit_particles.in_set(ParticleSet),
+                update_particles.after(ParticleSet),
+            )
+                .run_if(in_state(GameState::Running)),
+        );
+    }
+}
+
+fn random_in(rng: &mut CosmeticRng, [min, max]: [f32; 2]) -> f32 {
+    if max > min {
+        rng.gen_range(min..max)
+    } else {
+        min
+    }
+}
+
+fn srgba([r, g, b, a]: [f32; 4]) -> Color {
+    Color::srgba(r, g, b, a)
+}
+
+impl Emission<'_> {
+    fn emit(
+        &mut self,
+        commands: &mut Commands,
+        effect: &ParticleEffectConfig,
+        position: Vec2,
+        direction: Vec2,
+        count: usize,
+    ) {
+        // like a sound without a file, an effect without a sprite shows nothing
+        let Some(frames) = self.sheet.manifest.frames(&effect.sprite) else {
+            return;
+        };
+        let count = count.min(self.budget);
+        self.budget -= count;
+        let half_cone = effect.cone.to_radians() / 2.0;
+        for _ in 0..count {
+            let angle = direction.to_angle() + self.rng.gen_range(-half_cone..=half_cone);
+            let speed = random_in(self.rng, effect.speed);
+            let color = srgba(effect.color_start);
+            commands.spawn((
+                Particle {
+                    velocity: Vec2::from_angle(angle) * speed,
+                    age: 0.0,
+                    lifetime: random_in(self.rng, effect.lifetime),
+                    drag: effect.drag,
+                    colors: [color, srgba(effect.color_end)],
+                    scales: [effect.scale_start, effect.scale_end],
+                    frames,
+                },
+                Transform::from_translation(position.extend(SpriteOrder::Particle.z_index()))
+                    .with_scale(Vec3::splat(effect.scale_start)),
+                Sprite {
+                    color,
+                    ..self.sheet.to_sprite(frames.first)
+                },
+            ));
+        }
+    }
+}
+
+/// Emits the requested bursts and the particles of emitters, dropping those over
+/// `config.particles.max_particles`.
+fn emit_particles(
+    mut commands: Commands,
+    mut spawn_events: EventReader<SpawnParticles>,
+    mut emitter_query: Query<(Entity, &mut ParticleEmitter, &GlobalTransform)>,
+    particle_query: Query<(), With<Particle>>,
+    sheet: Res<GlobalSpriteSheet>,
+    mut rng: ResMut<CosmeticRng>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let particles = &config.particles;
+    if !particles.enabled {
+        spawn_events.clear();
+        return;
+    }
+    let mut emission = Emission {
+        sheet: &sheet.0,
+        rng: &mut rng,
+        budget: particles
+            .max_particles
+            .saturating_sub(particle_query.iter().count()),
+    };
+
+    for event in spawn_events.read() {
+        if let Some(effect) = particles.effects.get(&event.effect) {
+            emission.emit(
+                &mut commands,
+                effect,
+                event.position,
+                event.direction,
+                effect.count,
+            );
+        }
+    }
+
+    for (entity, mut emitter, transform) in emitter_query.iter_mut() {
+        let Some(effect) = particles.effects.get(&emitter.effect) else {
+            continue;
+        };
+        let count = match emitter.mode {
+            EmitMode::Burst => {
+                commands.entity(entity).remove::<ParticleEmitter>();
+                effect.count
+            }
+            EmitMode::Continuous => {
+                emitter.pending += effect.rate * time.delta_secs();
+                let count = emitter.pending as usize;
+                emitter.pending -= count as f32;
+                count
+            }
+        };
+        let position = transform.translation().truncate();
+        emission.emit(&mut commands, effect, position, emitter.direction, count);
+    }
+}
+
+fn update_particles(
+    mut commands: Commands,
+    mut particle_query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in particle_query.iter_mut() {
+        particle.age += delta;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).insert(PostDespawn);
+            continue;
+        }
+        let life = particle.age / particle.lifetime;
+        let drag = (-particle.drag * delta).exp();
+        particle.velocity *= drag;
+        transform.translation += particle.velocity.extend(0.0) * delta;
+        transform.scale = Vec3::splat(particle.scales[0].lerp(particle.scales[1], life));
+        sprite.color = particle.colors[0].mix(&particle.colors[1], life);
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            let frames = particle.frames;
+            let frame = (life * frames.length as f32) as usize;
+            atlas.index = frames.first + frame.min(frames.length - 1);
+        }
+    }
+}
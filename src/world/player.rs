@@ -1,13 +1,15 @@
 use crate::animation::*;
-use crate::config::GameConfig;
+use crate::config::{ConfigChanged, GameConfig};
 use crate::input::*;
 use crate::resource::*;
+use crate::sprite_manifest::clips;
 use crate::sprite_order::SpriteOrder;
 use crate::state::GameState;
 use crate::world::collision::{try_parse_collider, CollisionLayer};
 use crate::world::damage::*;
 use crate::world::enemy::Enemy;
 use crate::world::in_game::InGameScoped;
+use crate::world::knockback::*;
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use std::time::Duration;
@@ -21,21 +23,22 @@ pub struct PlayerPlugin;
 
 impl Player {
     pub fn new(sheet: &Res<GlobalSpriteSheet>, config: &Res<GameConfig>) -> impl Bundle {
-        let animation_indices = AnimationIndices::from_length(0, 4);
         (
             Player,
             Health::new(config.player.health),
             DamageCooldown::new(Duration::from_secs_f32(config.player.damage_cooldown)),
             DamageFlash,
+            KnockbackResistance(config.player.knockback_resistance),
             Transform::from_xyz(0.0, 0.0, SpriteOrder::Player.z_index()),
             RigidBody::Dynamic,
             LockedAxes::ROTATION_LOCKED,
             Collider::rectangle(config.player.collider_size, config.player.collider_size),
-            CollisionLayers::new([CollisionLayer::Player], [CollisionLayer::Enemy]),
+            CollisionLayers::new(
+                [CollisionLayer::Player],
+                [CollisionLayer::Enemy, CollisionLayer::EnemyBullet],
+            ),
             Dominance(5),
-            sheet.0.to_sprite(animation_indices.first),
-            animation_indices,
-            AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+            sheet.0.character_sprite(clips::PLAYER),
         )
     }
 }
@@ -45,31 +48,34 @@ impl Plugin for PlayerPlugin {
         app.add_systems(
             Update,
             (
-                on_move,
-                update_facing,
+                (on_move, update_facing).after(InputSet::Observe),
                 on_hurt.in_set(DamagePhase::Send),
-                on_heal,
+                on_heal.after(InputSet::Observe),
                 draw_player_hurt_box,
             )
                 .run_if(in_state(GameState::Running)),
-        );
+        )
+        .add_systems(Update, apply_config_changes);
     }
 }
 
 fn on_move(
-    mut player_query: Query<(&mut AnimationIndices, &mut LinearVelocity), With<Player>>,
+    mut player_query: Query<
+        (&mut Animator, &mut LinearVelocity),
+        (With<Player>, Without<Knockback>),
+    >,
     move_vector: Res<MoveVector>,
     config: Res<GameConfig>,
 ) {
-    let Ok((mut anim_indices, mut velocity)) = player_query.get_single_mut() else {
+    let Ok((mut animator, mut velocity)) = player_query.get_single_mut() else {
         return;
     };
-    let anim_index = if move_vector.0.x == 0.0 && move_vector.0.y == 0.0 {
-        0
+    let state = if move_vector.0.x == 0.0 && move_vector.0.y == 0.0 {
+        AnimationState::Idle
     } else {
-        4
+        AnimationState::Run
     };
-    anim_indices.with_first(anim_index);
+    animator.request(state);
     velocity.0 = move_vector.0 * config.player.speed;
 }
 
@@ -84,8 +90,8 @@ fn update_facing(
 }
 
 fn on_hurt(
-    enemy_query: Query<(), With<Enemy>>,
-    player_query: Query<(), With<Player>>,
+    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+    player_query: Query<&GlobalTransform, With<Player>>,
     mut collision_events: EventReader<Collision>,
     mut damage_events: EventWriter<DamageEvent>,
     config: Res<GameConfig>,
@@ -98,12 +104,22 @@ fn on_hurt(
         else {
             continue;
         };
+        let (Ok(enemy_transform), Ok(player_transform)) =
+            (enemy_query.get(enemy), player_query.get(player))
+        else {
+            continue;
+        };
+        let direction = (player_transform.translation() - enemy_transform.translation())
+            .truncate()
+            .normalize_or_zero();
         damage_events.send(DamageEvent {
             target: player,
             context: DamageContext {
                 damage: config.enemy.damage,
                 damage_type: DamageType::Enemy.into(),
                 attacker: Some(enemy),
+                knockback: Some(direction * config.enemy.knockback),
+                ignores_cooldown: false,
             },
             apply: true,
         });
@@ -111,14 +127,14 @@ fn on_hurt(
 }
 
 fn on_heal(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_state: Res<ActionState>,
     mut player_query: Single<&mut Health, With<Player>>,
     config: Res<GameConfig>,
 ) {
     if !config.basic.debug {
         return;
     }
-    if keyboard_input.just_released(KeyCode::KeyH) {
+    if action_state.just_released(Action::Heal) {
         player_query.heal(20.0);
     }
 }
@@ -134,3 +150,38 @@ fn draw_player_hurt_box(
         Color::srgb(1.0, 0.0, 0.0),
     );
 }
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    mut player_query: Query<
+        (
+            &mut Health,
+            &mut DamageCooldown,
+            &mut KnockbackResistance,
+            &mut Collider,
+        ),
+        With<Player>,
+    >,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    for (mut health, mut cooldown, mut resistance, mut collider) in player_query.iter_mut() {
+        if changed.contains("player.health") {
+            health.set_max(config.player.health);
+        }
+        if changed.contains("player.damage_cooldown") {
+            cooldown
+                .0
+                .set_duration(Duration::from_secs_f32(config.player.damage_cooldown));
+        }
+        if changed.contains("player.knockback_resistance") {
+            resistance.0 = config.player.knockback_resistance;
+        }
+        if changed.contains("player.collider_size") {
+            *collider =
+                Collider::rectangle(config.player.collider_size, config.player.collider_size);
+        }
+    }
+}
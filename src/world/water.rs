@@ -1,9 +1,29 @@
-use crate::config::GameConfig;
-use crate::state::GameState::GameInit;
+use crate::config::{ConfigChanged, GameConfig, WaterConfig};
+use crate::sprite_order::SpriteOrder;
+use crate::state::GameState;
+use crate::world::bullet::Bullet;
+use crate::world::despawn::{despawn_recursive, PostDespawn};
+use crate::world::enemy::Enemy;
 use crate::world::in_game::InGameScoped;
+use crate::world::map::{spawn_map, MapTiles, Terrain};
+use crate::world::player::Player;
+use avian2d::prelude::LinearVelocity;
+use bevy::image::ImageSampler;
 use bevy::prelude::*;
-use bevy::render::render_resource::{AsBindGroup, ShaderRef};
-use bevy::sprite::{Material2d, Material2dPlugin};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::render::render_resource::{
+    AsBindGroup, Extent3d, ShaderRef, ShaderType, TextureDimension, TextureFormat,
+};
+use bevy::sprite::{AlphaMode2d, Material2d, Material2dPlugin};
+
+/// The fragment shader of the water, loaded with the other assets.
+pub const WATER_SHADER: &str = "water_shader.wgsl";
+
+/// Ripples beyond this many, the oldest, are dropped. The shader has the same limit.
+pub const MAX_RIPPLES: usize = 16;
+
+/// Slower than this, nothing ripples the water it moves through.
+const RIPPLE_MIN_SPEED: f32 = 1.0;
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 struct Water {
@@ -19,42 +39,262 @@ struct Water {
     intensity: f32,
     #[uniform(5)]
     color_offset: Vec3,
+    #[uniform(6)]
+    foam_color: Vec3,
+    #[uniform(7)]
+    foam_intensity: f32,
+    /// See [`water_mask`].
+    #[texture(8)]
+    #[sampler(9)]
+    mask: Handle<Image>,
+    #[uniform(10)]
+    ripples: RippleUniform,
+}
+
+#[derive(ShaderType, Debug, Clone, Default)]
+struct RippleUniform {
+    count: u32,
+    ripples: [GpuRipple; MAX_RIPPLES],
+}
+
+#[derive(ShaderType, Debug, Clone, Copy, Default)]
+struct GpuRipple {
+    position: Vec2,
+    radius: f32,
+    strength: f32,
+}
+
+/// A ring spreading over the water, from something moving through it or a bullet
+/// landing in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ripple {
+    pub position: Vec2,
+    /// Seconds since it started.
+    pub age: f32,
 }
 
+/// The ripples on the water, oldest first.
+#[derive(Resource, Debug, Default)]
+pub struct Ripples(pub Vec<Ripple>);
+
+/// Draws animated water over the water tiles of the map, with foam along sandy
+/// shores and ripples where things move through it.
 #[derive(Default)]
 pub struct WaterPlugin;
 
+impl Water {
+    fn from_config(config: &WaterConfig, mask: Handle<Image>) -> Self {
+        Self {
+            radial_scale: config.radial_scale,
+            axial_scale: config.axial_scale,
+            contrast: config.contrast,
+            speed: config.speed,
+            intensity: config.intensity,
+            color_offset: config.color_offset,
+            foam_color: config.foam_color,
+            foam_intensity: config.foam_intensity,
+            mask,
+            ripples: RippleUniform::default(),
+        }
+    }
+}
+
 impl Material2d for Water {
     fn fragment_shader() -> ShaderRef {
-        "water_shader.wgsl".into()
+        WATER_SHADER.into()
+    }
+
+    fn alpha_mode(&self) -> AlphaMode2d {
+        // the tiles show through where there is no water
+        AlphaMode2d::Blend
     }
 }
 
 impl Plugin for WaterPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((Material2dPlugin::<Water>::default(),))
-            .add_systems(OnEnter(GameInit), spawn_water);
+            .init_resource::<Ripples>()
+            .add_systems(OnEnter(GameState::GameInit), spawn_water.after(spawn_map))
+            .add_systems(
+                Update,
+                (walk_ripples, update_ripples)
+                    .chain()
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(
+                PostUpdate,
+                bullet_ripples
+                    .before(despawn_recursive::<PostDespawn>)
+                    .run_if(in_state(GameState::Running)),
+            )
+            .add_systems(Update, apply_config_changes);
+    }
+}
+
+/// One texel per tile, from the top of the map down like the quad's uv: red where
+/// there is water, green where the water borders sand. Sampled linearly, so that
+/// shores are smooth.
+pub fn water_mask(tiles: &MapTiles) -> Image {
+    let size = tiles.size();
+    let mut data = Vec::with_capacity((size.x * size.y * 4) as usize);
+    for y in (0..size.y as i32).rev() {
+        for x in 0..size.x as i32 {
+            let tile = IVec2::new(x, y);
+            let water = tiles.get(tile) == Some(Terrain::Water);
+            let shore = water
+                && [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y]
+                    .into_iter()
+                    .any(|offset| tiles.get(tile + offset) == Some(Terrain::Sand));
+            data.extend_from_slice(&[u8::from(water) * 255, u8::from(shore) * 255, 0, 255]);
+        }
     }
+    let mut image = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::linear();
+    image
 }
 
 fn spawn_water(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
+    mut images: ResMut<Assets<Image>>,
     mut water_materials: ResMut<Assets<Water>>,
+    mut ripples: ResMut<Ripples>,
+    tiles: Option<Res<MapTiles>>,
     config: Res<GameConfig>,
 ) {
-    let water = Water {
-        radial_scale: config.water.radial_scale,
-        axial_scale: config.water.axial_scale,
-        contrast: config.water.contrast,
-        speed: config.water.speed,
-        intensity: config.water.intensity,
-        color_offset: config.water.color_offset,
+    ripples.0.clear();
+    // the water only covers water tiles
+    let Some(tiles) = tiles else {
+        return;
     };
+    let extent = tiles.size().as_vec2() * tiles.tile_size;
+    let mask = images.add(water_mask(&tiles));
+    let water = Water::from_config(&config.water, mask);
     commands.spawn((
         InGameScoped,
         Mesh2d(meshes.add(Rectangle::default())),
         MeshMaterial2d(water_materials.add(water)),
-        Transform::from_xyz(0., 0., -100.).with_scale(Vec3::new(6000., 6000., 1.)),
+        Transform::from_xyz(0.0, 0.0, SpriteOrder::Water.z_index()).with_scale(extent.extend(1.0)),
     ));
 }
+
+/// Every `config.water.ripple_interval`, a ripple under the player and each enemy
+/// moving through water.
+fn walk_ripples(
+    mut ripples: ResMut<Ripples>,
+    mut since_last: Local<f32>,
+    mover_query: Query<(&GlobalTransform, &LinearVelocity), Or<(With<Player>, With<Enemy>)>>,
+    tiles: Option<Res<MapTiles>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let Some(tiles) = tiles else {
+        return;
+    };
+    *since_last += time.delta_secs();
+    if *since_last < config.water.ripple_interval {
+        return;
+    }
+    *since_last = 0.0;
+    for (transform, velocity) in mover_query.iter() {
+        let position = transform.translation().truncate();
+        if velocity.0.length() > RIPPLE_MIN_SPEED
+            && tiles.terrain_at(position) == Some(Terrain::Water)
+        {
+            ripples.0.push(Ripple { position, age: 0.0 });
+        }
+    }
+}
+
+/// A ripple where a bullet ends over water, whether it hit something or ran out.
+fn bullet_ripples(
+    mut ripples: ResMut<Ripples>,
+    bullet_query: Query<&GlobalTransform, (With<Bullet>, Added<PostDespawn>)>,
+    tiles: Option<Res<MapTiles>>,
+) {
+    let Some(tiles) = tiles else {
+        return;
+    };
+    for transform in bullet_query.iter() {
+        let position = transform.translation().truncate();
+        if tiles.terrain_at(position) == Some(Terrain::Water) {
+            ripples.0.push(Ripple { position, age: 0.0 });
+        }
+    }
+}
+
+/// Ages the ripples, drops the finished and the oldest beyond [`MAX_RIPPLES`], and
+/// hands the rest to the water material.
+fn update_ripples(
+    mut ripples: ResMut<Ripples>,
+    water_query: Query<&MeshMaterial2d<Water>>,
+    mut water_materials: ResMut<Assets<Water>>,
+    time: Res<Time>,
+    config: Res<GameConfig>,
+) {
+    let water = &config.water;
+    for ripple in ripples.0.iter_mut() {
+        ripple.age += time.delta_secs();
+    }
+    ripples
+        .0
+        .retain(|ripple| ripple.age < water.ripple_lifetime);
+    let excess = ripples.0.len().saturating_sub(MAX_RIPPLES);
+    ripples.0.drain(..excess);
+
+    for material in water_query.iter() {
+        // only touched while there is something to show or to clear, as a change
+        // uploads the material again
+        let count = water_materials
+            .get(&material.0)
+            .map_or(0, |water| water.ripples.count);
+        if ripples.0.is_empty() && count == 0 {
+            continue;
+        }
+        let Some(material) = water_materials.get_mut(&material.0) else {
+            continue;
+        };
+        let uniform = &mut material.ripples;
+        uniform.count = ripples.0.len() as u32;
+        for (slot, ripple) in uniform.ripples.iter_mut().zip(&ripples.0) {
+            let progress = ripple.age / water.ripple_lifetime;
+            *slot = GpuRipple {
+                position: ripple.position,
+                radius: progress * water.ripple_radius,
+                strength: 1.0 - progress,
+            };
+        }
+    }
+}
+
+fn apply_config_changes(
+    mut changed_events: EventReader<ConfigChanged>,
+    water_query: Query<&MeshMaterial2d<Water>>,
+    mut water_materials: ResMut<Assets<Water>>,
+    config: Res<GameConfig>,
+) {
+    let Some(changed) = ConfigChanged::read_merged(&mut changed_events) else {
+        return;
+    };
+    if !changed.contains("water") {
+        return;
+    }
+    for material in water_query.iter() {
+        if let Some(water) = water_materials.get_mut(&material.0) {
+            *water = Water {
+                ripples: water.ripples.clone(),
+                ..Water::from_config(&config.water, water.mask.clone())
+            };
+        }
+    }
+}
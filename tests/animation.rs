@@ -0,0 +1,89 @@
+use bevy_2d_shooter::animation::*;
+use bevy_2d_shooter::sprite_manifest::SpriteClip;
+use std::time::Duration;
+
+const FRAME: Duration = Duration::from_millis(100);
+
+fn clip(first: usize, length: usize, mode: PlayMode) -> SpriteClip {
+    SpriteClip {
+        first,
+        length,
+        frame_duration: FRAME.as_secs_f32(),
+        mode,
+    }
+}
+
+/// The atlas index shown after each of `frames` frame durations.
+fn indices(animator: &mut Animator, frames: usize) -> Vec<usize> {
+    (0..frames)
+        .map(|_| {
+            animator.tick(FRAME);
+            animator.index()
+        })
+        .collect()
+}
+
+#[test]
+fn clips_loop_once_or_ping_pong() {
+    let mut looping = Animator::new(clip(10, 3, PlayMode::Loop));
+    assert_eq!(indices(&mut looping, 5), [11, 12, 10, 11, 12]);
+
+    let mut once = Animator::new(clip(10, 3, PlayMode::Once));
+    assert_eq!(indices(&mut once, 5), [11, 12, 12, 12, 12]);
+    assert!(once.is_finished());
+
+    let mut ping_pong = Animator::new(clip(10, 3, PlayMode::PingPong));
+    assert_eq!(indices(&mut ping_pong, 6), [11, 12, 11, 10, 11, 12]);
+}
+
+#[test]
+fn once_finishes_after_the_last_frame_was_shown() {
+    let mut animator = Animator::new(clip(0, 2, PlayMode::Once));
+    assert!(!animator.tick(FRAME));
+    assert!(animator.tick(FRAME));
+    assert!(!animator.tick(FRAME));
+}
+
+#[test]
+fn frame_durations_are_per_clip() {
+    let slow = SpriteClip {
+        frame_duration: 0.5,
+        ..clip(20, 2, PlayMode::Loop)
+    };
+    let mut animator =
+        Animator::new(clip(0, 4, PlayMode::Loop)).with_clip(AnimationState::Run, slow);
+    animator.request(AnimationState::Run);
+    assert_eq!(indices(&mut animator, 5), [20, 20, 20, 20, 21]);
+}
+
+#[test]
+fn one_shot_states_are_not_interrupted_by_movement() {
+    let mut animator = Animator::new(clip(0, 4, PlayMode::Loop))
+        .with_clip(AnimationState::Run, clip(4, 4, PlayMode::Loop))
+        .with_clip(AnimationState::Hurt, clip(8, 2, PlayMode::Once));
+
+    assert!(animator.request(AnimationState::Hurt));
+    assert!(!animator.request(AnimationState::Run));
+    assert_eq!(animator.state(), AnimationState::Hurt);
+
+    animator.tick(FRAME * 2);
+    assert!(animator.is_finished());
+    assert!(animator.request(AnimationState::Run));
+    assert_eq!(animator.state(), AnimationState::Run);
+}
+
+#[test]
+fn one_shot_states_without_a_clip_are_skipped() {
+    let mut animator = Animator::new(clip(0, 4, PlayMode::Loop));
+    assert!(!animator.request(AnimationState::Attack));
+    assert_eq!(animator.state(), AnimationState::Idle);
+}
+
+#[test]
+fn death_without_a_clip_finishes_at_once_and_is_never_left() {
+    let mut animator = Animator::new(clip(0, 4, PlayMode::Loop));
+    assert!(animator.request(AnimationState::Death));
+    assert!(animator.tick(Duration::ZERO));
+    assert!(!animator.request(AnimationState::Idle));
+    assert_eq!(animator.state(), AnimationState::Death);
+}
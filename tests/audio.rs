@@ -0,0 +1,136 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::audio::music::*;
+use bevy_2d_shooter::audio::*;
+use bevy_2d_shooter::config::{AudioBus, GameConfig, SoundConfig};
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::state::GameState;
+
+/// A config with the game's sounds and music registered, as none are by default.
+fn registered_sounds() -> GameConfig {
+    let mut config = GameConfig::default();
+    let audio = &mut config.audio;
+    for (name, bus, max_voices) in [
+        (sounds::SHOT, AudioBus::Sfx, 4),
+        (sounds::HIT, AudioBus::Sfx, 6),
+        ("music_game", AudioBus::Music, 1),
+        ("music_pause", AudioBus::Music, 1),
+    ] {
+        let sound = SoundConfig {
+            path: format!("audio/{name}.ogg"),
+            bus,
+            max_voices,
+            ..default()
+        };
+        audio.sounds.insert(name.to_string(), sound);
+    }
+    audio.music.running = "music_game".to_string();
+    audio.music.paused = "music_pause".to_string();
+    config
+}
+
+fn audio_app(config: GameConfig) -> App {
+    let mut app = HeadlessBuilder::new(config).without_waves().build();
+    // there is no audio output in tests, only the asset type is needed
+    app.init_asset::<AudioSource>().add_plugins(SoundPlugin);
+    app.step(1);
+    app
+}
+
+fn voices(app: &mut App, sound: &str) -> usize {
+    app.world_mut()
+        .query::<&SoundVoice>()
+        .iter(app.world())
+        .filter(|voice| voice.0 == sound)
+        .count()
+}
+
+#[test]
+fn voices_are_limited_per_sound() {
+    let mut app = audio_app(registered_sounds());
+    let max_voices = app.world().resource::<GameConfig>().audio.sounds[sounds::HIT].max_voices;
+
+    for _ in 0..500 {
+        app.world_mut().send_event(PlaySound::new(sounds::HIT));
+    }
+    app.step(1);
+
+    assert_eq!(voices(&mut app, sounds::HIT), max_voices);
+}
+
+#[test]
+fn voices_are_limited_over_all_sounds() {
+    let mut config = registered_sounds();
+    config.audio.max_voices = 3;
+    let mut app = audio_app(config);
+
+    for _ in 0..5 {
+        app.world_mut().send_event(PlaySound::new(sounds::HIT));
+        app.world_mut().send_event(PlaySound::new(sounds::SHOT));
+    }
+    app.step(1);
+
+    assert_eq!(
+        voices(&mut app, sounds::HIT) + voices(&mut app, sounds::SHOT),
+        3
+    );
+}
+
+#[test]
+fn unknown_sounds_are_ignored() {
+    let mut app = audio_app(registered_sounds());
+
+    app.world_mut().send_event(PlaySound::new("no_such_sound"));
+    app.step(1);
+
+    assert_eq!(voices(&mut app, "no_such_sound"), 0);
+}
+
+#[test]
+fn nothing_plays_without_registered_sounds() {
+    let mut app = audio_app(GameConfig::default());
+
+    app.world_mut().send_event(PlaySound::new(sounds::SHOT));
+    app.step(3);
+
+    assert_eq!(voices(&mut app, sounds::SHOT), 0);
+    assert!(app.world().resource::<SoundLibrary>().0.is_empty());
+}
+
+fn set_game_state(app: &mut App, state: GameState) {
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(state);
+    app.step(2);
+}
+
+/// The music entities, with whether each is paused.
+fn music(app: &mut App) -> Vec<(Entity, String, bool)> {
+    app.world_mut()
+        .query::<(Entity, &MusicTrack, &PlaybackSettings)>()
+        .iter(app.world())
+        .map(|(entity, track, settings)| (entity, track.0.clone(), settings.paused))
+        .collect()
+}
+
+#[test]
+fn pausing_suspends_the_game_music_until_the_game_resumes() {
+    let mut app = audio_app(registered_sounds());
+    app.step(2);
+    let tracks = music(&mut app);
+    assert_eq!(tracks.len(), 1);
+    let game_music = tracks[0].0;
+    assert_eq!(tracks[0], (game_music, "music_game".to_string(), false));
+
+    set_game_state(&mut app, GameState::Paused);
+    let tracks = music(&mut app);
+    assert!(tracks.contains(&(game_music, "music_game".to_string(), true)));
+    assert!(tracks
+        .iter()
+        .any(|(_, track, paused)| track == "music_pause" && !paused));
+
+    set_game_state(&mut app, GameState::Running);
+    assert_eq!(
+        music(&mut app),
+        vec![(game_music, "music_game".to_string(), false)]
+    );
+}
@@ -0,0 +1,165 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_2d_shooter::config::{BossPattern, BossPhaseConfig, GameConfig};
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::boss::*;
+use bevy_2d_shooter::world::damage::*;
+use bevy_2d_shooter::world::player::Player;
+use std::f32::consts::TAU;
+
+/// A run without waves whose boss arrives after `first_spawn` seconds.
+fn boss_app(first_spawn: f32, configure: impl FnOnce(&mut GameConfig)) -> App {
+    let mut app = HeadlessBuilder::default()
+        .without_waves()
+        .configure(|config| config.boss.first_spawn = first_spawn)
+        .configure(configure)
+        .build();
+    app.step(3);
+    app
+}
+
+fn boss(app: &mut App) -> Option<Entity> {
+    app.world_mut()
+        .query_filtered::<Entity, With<Boss>>()
+        .iter(app.world())
+        .next()
+}
+
+fn boss_phase(app: &mut App) -> usize {
+    app.world_mut().query::<&Boss>().single(app.world()).phase
+}
+
+/// Takes `fraction` of the boss's maximum health away.
+fn damage_boss(app: &mut App, fraction: f32) {
+    let target = boss(app).unwrap();
+    let max = app.world().get::<Health>(target).unwrap().max();
+    app.world_mut().send_event(DamageEvent {
+        target,
+        context: DamageContext::without_attacker(max * fraction, DamageType::Bullet)
+            .ignoring_cooldown(),
+        apply: true,
+    });
+    app.step(1);
+}
+
+fn position<F: bevy::ecs::query::QueryFilter>(app: &mut App) -> Vec2 {
+    app.world_mut()
+        .query_filtered::<&GlobalTransform, F>()
+        .single(app.world())
+        .translation()
+        .truncate()
+}
+
+/// The directions of the bullets of the first volley fired with only `pattern`,
+/// and the direction from the boss to the player when it was fired.
+fn first_volley(pattern: BossPattern) -> (Vec<Vec2>, Vec2) {
+    let mut app = boss_app(0.0, |config| {
+        config.boss.phases = vec![BossPhaseConfig {
+            health_threshold: 1.0,
+            fire_interval: 0.2,
+            pattern,
+        }];
+    });
+    for _ in 0..60 {
+        app.step(1);
+        let directions: Vec<Vec2> = app
+            .world_mut()
+            .query_filtered::<&LinearVelocity, With<BossBullet>>()
+            .iter(app.world())
+            .map(|velocity| velocity.0.normalize())
+            .collect();
+        if !directions.is_empty() {
+            let aim = position::<With<Player>>(&mut app) - position::<With<Boss>>(&mut app);
+            return (directions, aim.normalize());
+        }
+    }
+    panic!("the boss never fired");
+}
+
+fn has_direction(directions: &[Vec2], direction: Vec2) -> bool {
+    directions
+        .iter()
+        .any(|other| other.angle_to(direction).abs() < 0.02)
+}
+
+#[test]
+fn the_boss_spawns_when_its_timer_runs_out() {
+    let mut app = boss_app(0.5, |_| {});
+
+    app.run_for(0.4);
+    assert_eq!(boss(&mut app), None);
+    app.run_for(0.2);
+    assert!(boss(&mut app).is_some(), "the boss did not spawn");
+
+    // the next one waits for the spawn interval, once this one is defeated
+    let spawn_interval = app.world().resource::<GameConfig>().boss.spawn_interval;
+    let timer = &app.world().resource::<BossSpawnTimer>().0;
+    assert_eq!(timer.duration().as_secs_f32(), spawn_interval);
+    assert_eq!(app.stats().bosses, 1);
+}
+
+#[test]
+fn phases_change_at_their_health_threshold() {
+    let mut app = boss_app(0.0, |_| {});
+    app.step(1);
+    let phases = &app.world().resource::<GameConfig>().boss.phases;
+    let second = phases[1].health_threshold;
+    assert_eq!(boss_phase(&mut app), 0);
+
+    // just above the second phase
+    damage_boss(&mut app, 0.99 - second);
+    assert_eq!(boss_phase(&mut app), 0);
+    damage_boss(&mut app, 0.02);
+    assert_eq!(boss_phase(&mut app), 1);
+    // well past the last threshold
+    damage_boss(&mut app, 0.5);
+    assert_eq!(boss_phase(&mut app), 2);
+}
+
+#[test]
+fn radial_bursts_fire_in_every_direction() {
+    let (directions, _) = first_volley(BossPattern::RadialBurst { count: 8 });
+
+    assert_eq!(directions.len(), 8);
+    for i in 0..8 {
+        let direction = Vec2::from_angle(TAU * i as f32 / 8.0);
+        assert!(
+            has_direction(&directions, direction),
+            "nothing towards {direction}"
+        );
+    }
+}
+
+#[test]
+fn spirals_turn_their_arms_by_the_step() {
+    let (directions, _) = first_volley(BossPattern::Spiral {
+        arms: 4,
+        step: 10.0,
+    });
+
+    assert_eq!(directions.len(), 4);
+    for i in 0..4 {
+        let direction = Vec2::from_angle(10f32.to_radians() + TAU * i as f32 / 4.0);
+        assert!(
+            has_direction(&directions, direction),
+            "nothing towards {direction}"
+        );
+    }
+}
+
+#[test]
+fn aimed_volleys_fan_out_around_the_player() {
+    let (directions, aim) = first_volley(BossPattern::AimedVolley {
+        count: 3,
+        spread: 30.0,
+    });
+
+    assert_eq!(directions.len(), 3);
+    for angle in [-15f32, 0.0, 15.0] {
+        let direction = Vec2::from_angle(angle.to_radians()).rotate(aim);
+        assert!(
+            has_direction(&directions, direction),
+            "nothing towards {direction}"
+        );
+    }
+}
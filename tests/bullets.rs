@@ -0,0 +1,185 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::bullet::*;
+use bevy_2d_shooter::world::bullet_modifier::*;
+use bevy_2d_shooter::world::collision::CollisionLayer;
+use bevy_2d_shooter::world::damage::*;
+use bevy_2d_shooter::world::enemy::{Enemy, TrainingDummy};
+use bevy_2d_shooter::world::explosion::*;
+use std::time::Duration;
+
+const BULLET_DAMAGE: f32 = 10.0;
+const EXPLOSION_DAMAGE: f32 = 25.0;
+
+#[derive(Resource, Default)]
+struct Explosions(usize);
+
+fn count_explosions(mut events: EventReader<ExplosionEvent>, mut explosions: ResMut<Explosions>) {
+    explosions.0 += events.read().count();
+}
+
+/// A run with the training dummy, past the cooldown it spawns with.
+fn dummy_app() -> App {
+    let mut app = HeadlessBuilder::default()
+        .with_dummy()
+        .configure(|config| config.enemy.damage_cooldown = 0.05)
+        .build();
+    app.init_resource::<Explosions>()
+        .add_systems(Update, count_explosions);
+    app.step(10);
+    app
+}
+
+fn dummy(app: &mut App) -> (Vec2, f32) {
+    let (transform, health) = app
+        .world_mut()
+        .query_filtered::<(&Transform, &Health), With<TrainingDummy>>()
+        .single(app.world());
+    (transform.translation.truncate(), health.current())
+}
+
+/// A sturdy enemy away from the player.
+fn enemy_at(position: Vec2) -> impl Bundle {
+    (
+        Enemy,
+        Health::new(1000.0),
+        Transform::from_translation(position.extend(0.0)),
+        RigidBody::Kinematic,
+        Collider::rectangle(16.0, 16.0),
+        CollisionLayers::new([CollisionLayer::Enemy], [CollisionLayer::Bullet]),
+    )
+}
+
+fn bullet_count(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<Bullet>>()
+        .iter(app.world())
+        .count()
+}
+
+/// A bullet on top of the dummy, flying right.
+fn bullet_at(position: Vec2) -> impl Bundle {
+    (
+        Bullet,
+        BulletDamage(BULLET_DAMAGE),
+        BulletKnockback(0.0),
+        LinearVelocity(Vec2::X * 300.0),
+        Transform::from_translation(position.extend(0.0)),
+        RigidBody::Dynamic,
+        Collider::rectangle(2.0, 2.0),
+        Sensor,
+        CollisionLayers::new([CollisionLayer::Bullet], [CollisionLayer::Enemy]),
+        DespawnOnHit,
+    )
+}
+
+fn explosive() -> Explosive {
+    Explosive {
+        radius: 50.0,
+        damage: EXPLOSION_DAMAGE,
+        falloff: 0.0,
+        knockback: 0.0,
+    }
+}
+
+#[test]
+fn explosions_also_damage_the_enemy_hit_directly() {
+    let mut app = dummy_app();
+    let (position, before) = dummy(&mut app);
+
+    app.world_mut().spawn((bullet_at(position), explosive()));
+    app.step(5);
+
+    let (_, after) = dummy(&mut app);
+    assert_eq!(app.world().resource::<Explosions>().0, 1);
+    assert!(
+        (before - after - (BULLET_DAMAGE + EXPLOSION_DAMAGE)).abs() < 1e-3,
+        "dummy lost {} health",
+        before - after
+    );
+}
+
+#[test]
+fn bullets_explode_once_when_they_hit_on_their_last_frame() {
+    let mut app = dummy_app();
+    let (position, _) = dummy(&mut app);
+
+    // out of range after its first move, the frame it hits the dummy
+    app.world_mut().spawn((
+        bullet_at(position),
+        explosive(),
+        SpawnPoint(position),
+        MaxTravelDistance(1.0),
+    ));
+    app.step(5);
+
+    assert_eq!(app.world().resource::<Explosions>().0, 1);
+}
+
+#[test]
+fn explosions_flash_bystanders_only_until_their_cooldown_ends() {
+    let mut app = dummy_app();
+    let (position, _) = dummy(&mut app);
+    let bystander = app
+        .world_mut()
+        .spawn((
+            enemy_at(position + Vec2::Y * 30.0),
+            DamageFlash,
+            DamageCooldown::new(Duration::from_millis(50)),
+        ))
+        .id();
+    app.step(5);
+
+    // two pellets, both exploding next to the bystander in the same frame
+    for _ in 0..2 {
+        app.world_mut().spawn((bullet_at(position), explosive()));
+    }
+    app.step(2);
+    let health = app.world().get::<Health>(bystander).unwrap();
+    assert_eq!(health.max() - health.current(), EXPLOSION_DAMAGE);
+
+    app.step(10);
+    let sprite = app.world().get::<Sprite>(bystander).unwrap();
+    assert_eq!(sprite.color, Color::default());
+}
+
+fn split() -> SplitOnHit {
+    SplitOnHit {
+        count: 3,
+        spread: 1.0,
+        damage_ratio: 0.5,
+    }
+}
+
+#[test]
+fn bullets_split_once_when_they_hit_several_enemies_at_once() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let position = Vec2::new(200.0, 0.0);
+    for _ in 0..2 {
+        app.world_mut().spawn(enemy_at(position));
+    }
+
+    app.world_mut().spawn((bullet_at(position), split()));
+    app.step(3);
+
+    assert_eq!(bullet_count(&mut app), split().count);
+}
+
+#[test]
+fn split_bullets_keep_piercing() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let position = Vec2::new(200.0, 0.0);
+    app.world_mut().spawn(enemy_at(position));
+
+    let bullet = app
+        .world_mut()
+        .spawn((bullet_at(position), split(), Pierce { remaining: 1 }))
+        .id();
+    app.step(3);
+
+    assert!(app.world().get_entity(bullet).is_ok(), "the bullet stopped");
+    assert_eq!(bullet_count(&mut app), split().count + 1);
+}
@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::camera::*;
+
+#[test]
+fn smoothing_does_not_depend_on_the_frame_rate() {
+    let speed = 3.0;
+    let one_frame = smoothing(speed, 1.0 / 30.0);
+    let half_frame = smoothing(speed, 1.0 / 60.0);
+    // what is left after two half frames is what is left after one whole frame
+    let left = (1.0 - half_frame) * (1.0 - half_frame);
+    assert!((left - (1.0 - one_frame)).abs() < 1e-6);
+    assert_eq!(smoothing(speed, 0.0), 0.0);
+}
+
+#[test]
+fn the_view_stays_inside_the_map() {
+    let bounds = Rect::new(-100.0, -50.0, 100.0, 50.0);
+    let half_size = Vec2::new(20.0, 10.0);
+
+    assert_eq!(
+        clamp_to_bounds(Vec2::new(10.0, 5.0), half_size, bounds),
+        Vec2::new(10.0, 5.0)
+    );
+    assert_eq!(
+        clamp_to_bounds(Vec2::new(500.0, -500.0), half_size, bounds),
+        Vec2::new(80.0, -40.0)
+    );
+}
+
+#[test]
+fn views_larger_than_the_map_are_centered_on_it() {
+    let bounds = Rect::new(0.0, 0.0, 100.0, 50.0);
+    let half_size = Vec2::new(80.0, 10.0);
+
+    assert_eq!(
+        clamp_to_bounds(Vec2::new(10.0, 5.0), half_size, bounds),
+        Vec2::new(50.0, 10.0)
+    );
+}
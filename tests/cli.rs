@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::cli::Cli;
+use bevy_2d_shooter::config::*;
+use bevy_2d_shooter::state::AppState;
+
+fn parse(args: &[&str]) -> Result<Cli, String> {
+    Cli::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn no_arguments_give_the_defaults() {
+    assert_eq!(parse(&[]).unwrap(), Cli::default());
+}
+
+#[test]
+fn parses_every_option() {
+    let cli = parse(&[
+        "--config",
+        "custom.toml",
+        "--seed",
+        "42",
+        "--state",
+        "game",
+        "--window",
+        "1920x1080",
+        "--debug",
+        "--headless",
+        "30",
+        "--bot",
+        "--set",
+        "enemy.speed=60",
+        "--set",
+        "player.health = 200",
+        "--replay",
+        "run.replay",
+        "--record",
+        "next.replay",
+    ])
+    .unwrap();
+
+    assert_eq!(cli.config_path.to_str(), Some("custom.toml"));
+    assert_eq!(cli.seed, Some(42));
+    assert_eq!(cli.start_state, AppState::InGame);
+    assert_eq!(cli.window_size, Some((1920.0, 1080.0)));
+    assert!(cli.debug);
+    assert_eq!(cli.headless, Some(30.0));
+    assert!(cli.bot);
+    assert_eq!(
+        cli.overrides,
+        vec![
+            ("enemy.speed".to_string(), "60".to_string()),
+            ("player.health".to_string(), "200".to_string()),
+        ]
+    );
+    assert_eq!(
+        cli.replay.as_deref().and_then(|path| path.to_str()),
+        Some("run.replay")
+    );
+    assert_eq!(
+        cli.record.as_deref().and_then(|path| path.to_str()),
+        Some("next.replay")
+    );
+}
+
+#[test]
+fn rejects_invalid_arguments() {
+    assert!(parse(&["--unknown"]).is_err());
+    assert!(parse(&["--seed"]).is_err());
+    assert!(parse(&["--seed", "-1"]).is_err());
+    assert!(parse(&["--state", "paused"]).is_err());
+    assert!(parse(&["--window", "1920"]).is_err());
+    assert!(parse(&["--headless", "0"]).is_err());
+    assert!(parse(&["--set", "enemy.speed"]).is_err());
+}
+
+/// Loads `contents` as the config file of `args`, like a reload does.
+fn reload_with(name: &str, contents: &str, args: &[&str]) -> GameConfig {
+    let path = std::env::temp_dir().join(format!("bevy-2d-shooter-cli-{name}.toml"));
+    std::fs::write(&path, format!("version = {CONFIG_VERSION}\n{contents}")).unwrap();
+    let mut cli = parse(args).unwrap();
+    cli.config_path = path;
+    cli.config_source().reload().unwrap().config
+}
+
+#[test]
+fn options_override_the_config_on_every_reload() {
+    let config = reload_with(
+        "options",
+        "[basic]\nwindow_width = 1280.0\ndebug = false\n",
+        &["--window", "800x600", "--debug"],
+    );
+
+    assert_eq!(config.basic.window_width, 800.0);
+    assert_eq!(config.basic.window_height, 600.0);
+    assert!(config.basic.debug);
+}
+
+#[test]
+fn runs_are_only_recorded_when_asked_to() {
+    assert!(!GameConfig::default().replay.record);
+
+    let config = reload_with("record", "", &["--record", "1"]);
+
+    assert!(config.replay.record);
+    assert_eq!(config.replay.path, "1");
+}
+
+#[test]
+fn overrides_set_nested_values() {
+    let mut config = GameConfig::default();
+
+    apply_override(&mut config, "enemy.speed", "60").unwrap();
+    apply_override(&mut config, "enemy.spawn_waves", "false").unwrap();
+    apply_override(&mut config, "boss.phases.1.fire_interval", "2.5").unwrap();
+    apply_override(&mut config, "controls.melee.0.key", "KeyG").unwrap();
+
+    assert_eq!(config.enemy.speed, 60.0);
+    assert!(!config.enemy.spawn_waves);
+    assert_eq!(config.boss.phases[1].fire_interval, 2.5);
+    assert_eq!(config.controls.melee[0], InputBinding::Key(KeyCode::KeyG));
+}
+
+#[test]
+fn overrides_reject_unknown_keys_and_mistyped_values() {
+    let mut config = GameConfig::default();
+
+    assert!(apply_override(&mut config, "enemy.sped", "60").is_err());
+    assert!(apply_override(&mut config, "boss.phases.9.fire_interval", "1").is_err());
+    assert!(apply_override(&mut config, "enemy.speed", "fast").is_err());
+    assert_eq!(config.enemy.speed, GameConfig::default().enemy.speed);
+}
@@ -0,0 +1,375 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::config::*;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::bullet_modifier::BulletModifiers;
+use bevy_2d_shooter::world::gun::Gun;
+
+#[test]
+fn identical_configs_have_no_changes() {
+    assert!(diff_configs(&GameConfig::default(), &GameConfig::default()).is_empty());
+}
+
+#[test]
+fn diff_lists_changed_paths() {
+    let old = GameConfig::default();
+    let mut new = GameConfig::default();
+    new.enemy.speed += 10.0;
+    new.water.color_offset.x = 1.0;
+    new.boss.phases[1].fire_interval = 0.5;
+
+    let keys = diff_configs(&old, &new);
+
+    assert_eq!(keys.len(), 3, "unexpected changes {keys:?}");
+    let changed = ConfigChanged { keys };
+    assert!(changed.contains("enemy.speed"));
+    assert!(changed.contains("enemy"));
+    assert!(changed.contains("water"));
+    assert!(changed.contains("boss.phases.1.fire_interval"));
+    assert!(!changed.contains("boss.phases.0.fire_interval"));
+    assert!(!changed.contains("enemy.spe"));
+    assert!(!changed.contains("player"));
+}
+
+#[test]
+fn resized_arrays_change_as_a_whole() {
+    let old = GameConfig::default();
+    let mut new = GameConfig::default();
+    new.boss.phases.pop();
+
+    let changed = ConfigChanged {
+        keys: diff_configs(&old, &new),
+    };
+
+    assert_eq!(changed.keys, vec!["boss.phases".to_string()]);
+    assert!(changed.contains("boss.phases.0.fire_interval"));
+}
+
+#[test]
+fn changes_apply_to_the_living_player() {
+    let config = GameConfig::default();
+    let max_health = config.player.health;
+    let mut app = HeadlessBuilder::new(config).build();
+    app.step(3);
+
+    app.world_mut().resource_mut::<GameConfig>().player.health = max_health * 2.0;
+    app.world_mut().send_event(ConfigChanged {
+        keys: vec!["player.health".to_string()],
+    });
+    app.step(1);
+
+    assert_eq!(app.player_health(), Some(max_health * 2.0));
+}
+
+#[test]
+fn changes_sent_in_the_same_frame_all_apply() {
+    let config = GameConfig::default();
+    let max_health = config.player.health;
+    let mut app = HeadlessBuilder::new(config).build();
+    app.step(3);
+
+    app.world_mut().resource_mut::<GameConfig>().player.health = max_health * 2.0;
+    app.world_mut().send_event(ConfigChanged {
+        keys: vec!["player.health".to_string()],
+    });
+    app.world_mut().send_event(ConfigChanged {
+        keys: vec!["enemy.speed".to_string()],
+    });
+    app.step(1);
+
+    assert_eq!(app.player_health(), Some(max_health * 2.0));
+}
+
+#[test]
+fn explosion_changes_reach_the_gun() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+
+    app.world_mut()
+        .resource_mut::<GameConfig>()
+        .bullet
+        .explosion_radius = 30.0;
+    app.world_mut().send_event(ConfigChanged {
+        keys: vec!["bullet.explosion_radius".to_string()],
+    });
+    app.step(1);
+
+    let modifiers = app
+        .world_mut()
+        .query_filtered::<&BulletModifiers, With<Gun>>()
+        .single(app.world());
+    assert_eq!(
+        modifiers.explosive.map(|explosive| explosive.radius),
+        Some(30.0)
+    );
+}
+
+#[test]
+fn default_config_is_valid() {
+    assert!(GameConfig::default().validate().is_empty());
+}
+
+#[test]
+fn missing_values_take_their_defaults() {
+    let (config, report) = parse_config(&format!(
+        "version = {CONFIG_VERSION}\n\
+         [enemy]\n\
+         speed = 80.0\n"
+    ))
+    .unwrap();
+
+    assert!(report.is_empty(), "unexpected issues: {report}");
+    assert_eq!(config.enemy.speed, 80.0);
+    assert_eq!(config.enemy.health, GameConfig::default().enemy.health);
+    assert_eq!(
+        config.boss.phases.len(),
+        GameConfig::default().boss.phases.len()
+    );
+}
+
+#[test]
+fn invalid_values_are_reported_by_path() {
+    let (config, report) = parse_config(&format!(
+        "version = {CONFIG_VERSION}\n\
+         [enemy]\n\
+         speed = \"fast\"\n\
+         health = 30.0\n\
+         [player]\n\
+         helth = 10.0\n"
+    ))
+    .unwrap();
+
+    assert_eq!(report.errors.len(), 1, "unexpected errors: {report}");
+    assert_eq!(report.errors[0].path, "enemy.speed");
+    assert_eq!(report.warnings.len(), 1, "unexpected warnings: {report}");
+    assert_eq!(report.warnings[0].path, "player.helth");
+    // the rest of the file is kept
+    assert_eq!(config.enemy.speed, GameConfig::default().enemy.speed);
+    assert_eq!(config.enemy.health, 30.0);
+}
+
+#[test]
+fn broken_toml_fails_as_a_whole() {
+    assert!(parse_config("[enemy\nspeed = 1.0").is_err());
+}
+
+#[test]
+fn files_without_a_version_are_read_as_the_first_version() {
+    let (config, report) = parse_config("[enemy]\nspawn_rate_per_second = 7\n").unwrap();
+
+    assert_eq!(config.version, CONFIG_VERSION);
+    assert_eq!(config.enemy.spawn_rate_per_second, 7);
+    assert!(report.is_empty(), "unexpected report: {report}");
+}
+
+#[test]
+fn invalid_versions_are_rejected() {
+    assert!(parse_config("version = 0").is_err());
+    assert!(parse_config("version = \"1\"").is_err());
+    // would be 1 if truncated to 32 bits
+    assert!(parse_config("version = 4294967297").is_err());
+}
+
+#[test]
+fn newer_files_are_rejected() {
+    let contents = format!("version = {}", CONFIG_VERSION + 1);
+    assert!(parse_config(&contents).is_err());
+}
+
+#[test]
+fn validation_reports_values_that_break_the_game() {
+    let mut config = GameConfig::default();
+    config.basic.tile_size = 0.0;
+    config.enemy.spawn_interval = -1.0;
+    config.boss.phases[2].fire_interval = 0.0;
+    config.player.knockback_resistance = 2.0;
+
+    let report = config.validate();
+
+    let errors: Vec<&str> = report
+        .errors
+        .iter()
+        .map(|issue| issue.path.as_str())
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            "basic.tile_size",
+            "enemy.spawn_interval",
+            "boss.phases.2.fire_interval"
+        ]
+    );
+    assert_eq!(report.warnings.len(), 1, "unexpected warnings: {report}");
+    assert_eq!(report.warnings[0].path, "player.knockback_resistance");
+}
+
+#[test]
+fn invalid_values_can_be_reset() {
+    let mut config = GameConfig::default();
+    config.enemy.spawn_interval = 0.0;
+    config.boss.phases[1].fire_interval = 0.0;
+
+    reset_to_default(&mut config, "enemy.spawn_interval");
+    reset_to_default(&mut config, "boss.phases.1.fire_interval");
+
+    assert!(config.validate().is_empty());
+}
+
+fn temp_source(name: &str, contents: &str) -> ConfigSource {
+    let dir = std::env::temp_dir().join(format!("bevy-2d-shooter-{name}"));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("profiles")).unwrap();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, contents).unwrap();
+    ConfigSource {
+        path,
+        overrides: Vec::new(),
+    }
+}
+
+#[test]
+fn presets_give_valid_configs() {
+    for difficulty in Difficulty::ALL {
+        let mut config = GameConfig::default();
+        apply_factors(&mut config, &difficulty.factors()).unwrap();
+        assert!(
+            config.validate().is_empty(),
+            "{} is invalid",
+            difficulty.name()
+        );
+    }
+}
+
+#[test]
+fn layers_apply_in_order() {
+    let mut source = temp_source(
+        "layers",
+        &format!(
+            "version = {CONFIG_VERSION}\n\
+             [profile]\n\
+             difficulty = \"hard\"\n\
+             name = \"fast\"\n\
+             [enemy]\n\
+             health = 10.0\n"
+        ),
+    );
+    std::fs::write(
+        source.profile_dir().join("fast.toml"),
+        "[enemy]\nspeed = 90\n",
+    )
+    .unwrap();
+    source.overrides = vec![("enemy.damage".to_string(), "1".to_string())];
+
+    let LoadedConfig {
+        config,
+        report,
+        layers,
+    } = source.load().unwrap();
+
+    assert!(report.is_empty(), "unexpected issues: {report}");
+    // the preset scales the value of the base file rather than replacing it
+    let hard = Difficulty::Hard.factors();
+    assert_eq!(
+        config.enemy.health,
+        (10.0 * hard["enemy"]["health"].as_float().unwrap()) as f32
+    );
+    assert_eq!(config.enemy.speed, 90.0);
+    assert_eq!(config.enemy.damage, 1.0);
+    assert_eq!(layers.origin("enemy.health"), Some("hard"));
+    assert_eq!(layers.origin("enemy.speed"), Some("profile `fast`"));
+    assert_eq!(layers.origin("enemy.damage"), Some("command line"));
+    assert_eq!(layers.origin("enemy.collider_size"), None);
+    assert_eq!(source.profiles(), vec!["fast".to_string()]);
+}
+
+#[test]
+fn presets_round_integers_and_reject_other_values() {
+    let mut config = GameConfig::default();
+    config.enemy.spawn_rate_per_second = 3;
+    let factors: toml::Table = "[enemy]\nspawn_rate_per_second = 1.5\n".parse().unwrap();
+
+    let keys = apply_factors(&mut config, &factors).unwrap();
+
+    assert_eq!(keys, vec!["enemy.spawn_rate_per_second".to_string()]);
+    assert_eq!(config.enemy.spawn_rate_per_second, 5);
+    let not_a_number: toml::Table = "[profile]\nname = 2.0\n".parse().unwrap();
+    assert!(apply_factors(&mut config, &not_a_number).is_err());
+}
+
+#[test]
+fn saving_a_value_keeps_the_rest_of_the_file() {
+    let source = temp_source("save", "[enemy]\nhealth = 10.0\n");
+
+    source
+        .save_value("profile.difficulty", Difficulty::Nightmare.name())
+        .unwrap();
+    let loaded = source.load().unwrap();
+
+    assert_eq!(loaded.config.profile.difficulty, Difficulty::Nightmare);
+    assert_eq!(loaded.layers.origin("enemy.health"), Some("nightmare"));
+    let saved = std::fs::read_to_string(&source.path).unwrap();
+    assert!(saved.contains("health = 10.0"), "{saved}");
+}
+
+#[test]
+fn bindings_mix_keys_mouse_and_gamepad_buttons() {
+    let (config, report) = parse_config(&format!(
+        "version = {CONFIG_VERSION}\n\
+         [controls]\n\
+         fire = [{{ mouse = \"Left\" }}, {{ gamepad = \"RightTrigger2\" }}]\n\
+         pause = [{{ key = \"KeyP\" }}]\n"
+    ))
+    .unwrap();
+
+    assert!(report.is_empty(), "{report}");
+    assert_eq!(
+        config.controls.fire,
+        vec![
+            InputBinding::Mouse(MouseButton::Left),
+            InputBinding::Gamepad(GamepadButton::RightTrigger2)
+        ]
+    );
+    assert_eq!(
+        config.controls.pause,
+        vec![InputBinding::Key(KeyCode::KeyP)]
+    );
+    // actions not in the file keep their default bindings
+    assert_eq!(config.controls.heal, ControlsConfig::default().heal);
+}
+
+#[test]
+fn sounds_are_registered_from_the_file() {
+    let (config, report) = parse_config(&format!(
+        "version = {CONFIG_VERSION}\n\
+         [audio.sounds.shot]\n\
+         path = \"audio/shot.ogg\"\n\
+         volume = 0.25\n\
+         [audio.sounds.reload]\n\
+         path = \"audio/reload.ogg\"\n"
+    ))
+    .unwrap();
+
+    assert!(report.is_empty(), "{report}");
+    assert!(GameConfig::default().audio.sounds.is_empty());
+    assert_eq!(config.audio.sounds.len(), 2);
+    assert_eq!(config.audio.sounds["shot"].path, "audio/shot.ogg");
+    assert_eq!(config.audio.sounds["shot"].volume, 0.25);
+    // missing fields of an entry take their defaults
+    assert_eq!(config.audio.sounds["reload"].volume, 1.0);
+    assert_eq!(
+        config.audio.sounds["reload"].max_voices,
+        SoundConfig::default().max_voices
+    );
+}
+
+#[test]
+fn music_must_name_a_registered_sound() {
+    let mut config = GameConfig::default();
+    config.audio.music.paused = "missing".to_string();
+
+    let report = config.validate();
+
+    assert!(report
+        .warnings
+        .iter()
+        .any(|issue| issue.path == "audio.music.paused"));
+}
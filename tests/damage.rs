@@ -0,0 +1,225 @@
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy_2d_shooter::world::damage::*;
+use std::time::Duration;
+
+const TIMESTEP: Duration = Duration::from_millis(50);
+const COOLDOWN: Duration = Duration::from_millis(100);
+const FLASH_COLOR: Color = Color::srgb(1.0, 0.0, 0.0);
+
+/// Whether each damage event was still marked `apply` once the pipeline finished.
+#[derive(Resource, Default)]
+struct Outcomes(Vec<bool>);
+
+#[derive(Resource)]
+struct CancelAll;
+
+fn damage_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, DamagePlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(TIMESTEP))
+        .init_resource::<Outcomes>()
+        .add_systems(
+            Update,
+            (
+                cancel_all
+                    .run_if(resource_exists::<CancelAll>)
+                    .in_set(DamagePhase::Before),
+                record_outcomes.in_set(DamagePhase::After),
+            ),
+        );
+    // the first update only initializes time, its delta is always zero
+    app.update();
+    app
+}
+
+fn cancel_all(mut events: EventMutator<DamageEvent>) {
+    for event in events.read() {
+        event.apply = false;
+    }
+}
+
+fn record_outcomes(mut events: EventReader<DamageEvent>, mut outcomes: ResMut<Outcomes>) {
+    outcomes.0.extend(events.read().map(|event| event.apply));
+}
+
+fn hit(app: &mut App, target: Entity, damage: f32) {
+    app.world_mut().send_event(DamageEvent {
+        target,
+        context: DamageContext::without_attacker(damage, DamageType::Bullet),
+        apply: true,
+    });
+}
+
+fn health(app: &App, entity: Entity) -> f32 {
+    app.world().get::<Health>(entity).unwrap().current()
+}
+
+fn outcomes(app: &mut App) -> Vec<bool> {
+    std::mem::take(&mut app.world_mut().resource_mut::<Outcomes>().0)
+}
+
+fn sprite_color(app: &App, entity: Entity) -> Color {
+    app.world().get::<Sprite>(entity).unwrap().color
+}
+
+/// Steps past the cooldown of a freshly spawned entity, which starts unfinished.
+fn wait_for_cooldown(app: &mut App) {
+    for _ in 0..(COOLDOWN.as_millis() / TIMESTEP.as_millis()) {
+        app.update();
+    }
+}
+
+#[test]
+fn damage_without_cooldown_is_always_applied() {
+    let mut app = damage_app();
+    let target = app.world_mut().spawn(Health::new(100.0)).id();
+
+    hit(&mut app, target, 30.0);
+    app.update();
+    hit(&mut app, target, 30.0);
+    hit(&mut app, target, 30.0);
+    app.update();
+
+    assert_eq!(health(&app, target), 10.0);
+    assert_eq!(outcomes(&mut app), vec![true, true, true]);
+}
+
+#[test]
+fn cooldown_gates_damage() {
+    let mut app = damage_app();
+    let target = app
+        .world_mut()
+        .spawn((Health::new(100.0), DamageCooldown::new(COOLDOWN)))
+        .id();
+
+    // a fresh cooldown has not elapsed yet
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(health(&app, target), 100.0);
+    assert_eq!(outcomes(&mut app), vec![false]);
+
+    wait_for_cooldown(&mut app);
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(health(&app, target), 90.0);
+
+    // still cooling down
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(health(&app, target), 90.0);
+    assert_eq!(outcomes(&mut app), vec![true, false]);
+
+    app.update();
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(health(&app, target), 80.0);
+    assert_eq!(outcomes(&mut app), vec![true]);
+}
+
+#[test]
+fn only_first_hit_in_a_frame_passes_the_cooldown() {
+    let mut app = damage_app();
+    let target = app
+        .world_mut()
+        .spawn((Health::new(100.0), DamageCooldown::new(COOLDOWN)))
+        .id();
+    wait_for_cooldown(&mut app);
+
+    hit(&mut app, target, 10.0);
+    hit(&mut app, target, 20.0);
+    app.update();
+
+    assert_eq!(health(&app, target), 90.0);
+    assert_eq!(outcomes(&mut app), vec![true, false]);
+}
+
+#[test]
+fn health_is_clamped_at_zero_and_dead_targets_ignore_damage() {
+    let mut app = damage_app();
+    let target = app.world_mut().spawn(Health::new(50.0)).id();
+
+    hit(&mut app, target, 80.0);
+    app.update();
+    let health_component = app.world().get::<Health>(target).unwrap();
+    assert_eq!(health_component.current(), 0.0);
+    assert!(!health_component.is_alive());
+
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(health(&app, target), 0.0);
+    assert_eq!(outcomes(&mut app), vec![true, false]);
+}
+
+#[test]
+fn targets_without_health_cancel_the_event() {
+    let mut app = damage_app();
+    let target = app.world_mut().spawn_empty().id();
+
+    hit(&mut app, target, 10.0);
+    app.update();
+
+    assert_eq!(outcomes(&mut app), vec![false]);
+}
+
+#[test]
+fn cancelled_events_are_not_applied() {
+    let mut app = damage_app();
+    app.insert_resource(CancelAll);
+    let target = app.world_mut().spawn(Health::new(100.0)).id();
+
+    hit(&mut app, target, 10.0);
+    app.update();
+
+    assert_eq!(health(&app, target), 100.0);
+    assert_eq!(outcomes(&mut app), vec![false]);
+}
+
+#[test]
+fn flash_is_shown_on_hit_and_reset_after_cooldown() {
+    let mut app = damage_app();
+    let target = app
+        .world_mut()
+        .spawn((
+            Health::new(100.0),
+            DamageCooldown::new(COOLDOWN),
+            DamageFlash,
+        ))
+        .id();
+    wait_for_cooldown(&mut app);
+    assert_eq!(sprite_color(&app, target), Color::default());
+
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(sprite_color(&app, target), FLASH_COLOR);
+
+    app.update();
+    assert_eq!(sprite_color(&app, target), FLASH_COLOR);
+
+    app.update();
+    assert_eq!(sprite_color(&app, target), Color::default());
+}
+
+#[test]
+fn cancelled_hits_do_not_flash_or_reset_the_cooldown() {
+    let mut app = damage_app();
+    app.insert_resource(CancelAll);
+    let target = app
+        .world_mut()
+        .spawn((
+            Health::new(100.0),
+            DamageCooldown::new(COOLDOWN),
+            DamageFlash,
+        ))
+        .id();
+    wait_for_cooldown(&mut app);
+
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(sprite_color(&app, target), Color::default());
+
+    app.world_mut().remove_resource::<CancelAll>();
+    hit(&mut app, target, 10.0);
+    app.update();
+    assert_eq!(health(&app, target), 90.0);
+}
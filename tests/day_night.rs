@@ -0,0 +1,107 @@
+use avian2d::prelude::LinearVelocity;
+use bevy::prelude::*;
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::day_night::DayClock;
+use bevy_2d_shooter::world::enemy::TrainingDummy;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-5
+}
+
+#[test]
+fn nights_are_darkest_and_most_dangerous_at_midnight() {
+    let midnight = DayClock(0.0);
+    let sunrise = DayClock(0.25);
+    let noon = DayClock(0.5);
+
+    assert!(close(midnight.daylight(), 0.0));
+    assert!(close(noon.daylight(), 1.0));
+    assert!(close(midnight.night_scale(1.5), 1.5));
+    assert!(close(sunrise.night_scale(1.5), 1.0));
+    assert!(close(noon.night_scale(1.5), 1.0));
+    assert!(DayClock(0.9).night() > DayClock(0.8).night());
+}
+
+#[test]
+fn the_clock_starts_at_the_start_time_and_wraps_around() {
+    let mut app = HeadlessBuilder::default()
+        .without_waves()
+        .configure(|config| {
+            config.day_night.day_length = 2.0;
+            config.day_night.start_time = 0.75;
+        })
+        .build();
+    app.step(1);
+    let time_of_day = app.world().resource::<DayClock>().0;
+    assert!((0.75..0.76).contains(&time_of_day), "{time_of_day}");
+
+    app.run_for(1.0);
+    let time_of_day = app.world().resource::<DayClock>().0;
+    assert!((0.2..0.3).contains(&time_of_day), "{time_of_day}");
+}
+
+#[test]
+fn without_the_cycle_the_time_of_day_stays() {
+    let start_time = GameConfig::default().day_night.start_time;
+    let mut app = HeadlessBuilder::default()
+        .without_waves()
+        .configure(|config| config.day_night.enabled = false)
+        .build();
+
+    app.run_for(1.0);
+    assert_eq!(app.world().resource::<DayClock>().0, start_time);
+}
+
+fn app_at(start_time: f32, configure: impl FnOnce(&mut GameConfig)) -> App {
+    HeadlessBuilder::default()
+        .configure(|config| {
+            config.day_night.enabled = false;
+            config.day_night.start_time = start_time;
+            configure(config);
+        })
+        .build()
+}
+
+#[test]
+fn enemies_run_faster_at_night() {
+    let speed = |start_time: f32| {
+        let mut app = app_at(start_time, |config| {
+            config.enemy.spawn_waves = false;
+            config.enemy.spawn_dummy = true;
+            config.day_night.night_speed_multiplier = 2.0;
+        });
+        app.step(3);
+        app.world_mut()
+            .query_filtered::<&LinearVelocity, With<TrainingDummy>>()
+            .single(app.world())
+            .length()
+    };
+    let enemy_speed = GameConfig::default().enemy.speed;
+
+    let (day, night) = (speed(0.5), speed(0.0));
+    assert!((day - enemy_speed).abs() < 0.01, "{day}");
+    assert!((night - enemy_speed * 2.0).abs() < 0.01, "{night}");
+}
+
+#[test]
+fn waves_are_larger_at_night() {
+    let first_wave = |start_time: f32| {
+        let mut app = app_at(start_time, |config| {
+            config.enemy.spawn_interval = 0.1;
+            config.enemy.spawn_rate_per_second = 2;
+            config.enemy.spawn_limit = 100;
+            config.day_night.night_spawn_multiplier = 2.0;
+        });
+        for _ in 0..60 {
+            app.step(1);
+            if app.enemy_count() > 0 {
+                break;
+            }
+        }
+        app.enemy_count()
+    };
+
+    assert_eq!(first_wave(0.5), 2);
+    assert_eq!(first_wave(0.0), 4);
+}
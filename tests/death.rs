@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::state::GameState;
+use bevy_2d_shooter::world::damage::*;
+use bevy_2d_shooter::world::dying::*;
+use bevy_2d_shooter::world::enemy::Enemy;
+
+/// A run with only the training dummy, killed right away.
+fn killed_dummy(corpse_lifetime: f32) -> (App, Entity) {
+    let mut app = HeadlessBuilder::default()
+        .with_dummy()
+        .configure(|config| {
+            config.death.fade_duration = 0.2;
+            config.death.corpse_lifetime = corpse_lifetime;
+        })
+        .build();
+    // past the damage cooldown the dummy spawns with
+    app.run_for(0.5);
+
+    let dummy = app
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .single(app.world());
+    app.world_mut().send_event(DamageEvent {
+        target: dummy,
+        context: DamageContext::without_attacker(f32::MAX, DamageType::Bullet),
+        apply: true,
+    });
+    app.step(2);
+    (app, dummy)
+}
+
+#[test]
+fn dead_enemies_stop_counting_but_stay_while_dying() {
+    let (mut app, dummy) = killed_dummy(1.0);
+
+    assert_eq!(app.enemy_count(), 0);
+    let entity = app.world().entity(dummy);
+    assert!(entity.contains::<Dying>());
+    assert!(!entity.contains::<Health>());
+}
+
+#[test]
+fn dying_enemies_leave_a_corpse_for_a_while() {
+    let (mut app, dummy) = killed_dummy(0.5);
+
+    app.run_for(0.3);
+    assert!(app.world().entity(dummy).contains::<Corpse>());
+
+    app.run_for(0.6);
+    assert!(!app.world().entities().contains(dummy));
+}
+
+#[test]
+fn without_corpses_dying_enemies_are_despawned_after_the_fade() {
+    let (mut app, dummy) = killed_dummy(0.0);
+
+    app.run_for(0.3);
+    assert!(!app.world().entities().contains(dummy));
+}
+
+fn set_game_state(app: &mut App, state: GameState) {
+    app.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(state);
+    app.step(2);
+}
+
+fn fade_progress(app: &App, dummy: Entity) -> (Color, Vec3) {
+    let entity = app.world().entity(dummy);
+    (
+        entity.get::<Sprite>().unwrap().color,
+        entity.get::<Transform>().unwrap().scale,
+    )
+}
+
+#[test]
+fn the_fade_stops_while_paused() {
+    let (mut app, dummy) = killed_dummy(1.0);
+    app.run_for(0.1);
+
+    set_game_state(&mut app, GameState::Paused);
+    let paused = fade_progress(&app, dummy);
+    app.run_for(0.5);
+    assert_eq!(fade_progress(&app, dummy), paused);
+    assert!(app.world().entity(dummy).contains::<Dying>());
+
+    set_game_state(&mut app, GameState::Running);
+    app.run_for(0.2);
+    assert!(app.world().entity(dummy).contains::<Corpse>());
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::camera::*;
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::feedback::*;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::damage::*;
+use bevy_2d_shooter::world::enemy::Enemy;
+use bevy_2d_shooter::world::player::Player;
+
+/// A run with only the training dummy and a camera to shake.
+fn feedback_app(reduce_motion: bool) -> App {
+    let mut app = HeadlessBuilder::default()
+        .with_dummy()
+        .configure(|config| config.feedback.reduce_motion = reduce_motion)
+        .build();
+    app.add_plugins(FeedbackPlugin);
+    app.world_mut().spawn(CameraShake::default());
+    // past the damage cooldowns everything spawns with
+    app.run_for(0.5);
+    app
+}
+
+fn hit<F: bevy::ecs::query::QueryFilter>(app: &mut App, damage: f32) {
+    let target = app
+        .world_mut()
+        .query_filtered::<Entity, F>()
+        .single(app.world());
+    app.world_mut().send_event(DamageEvent {
+        target,
+        context: DamageContext::without_attacker(damage, DamageType::Bullet),
+        apply: true,
+    });
+    app.step(1);
+}
+
+fn relative_speed(app: &App) -> f32 {
+    app.world().resource::<Time<Virtual>>().relative_speed()
+}
+
+fn trauma(app: &mut App) -> f32 {
+    app.world_mut()
+        .query::<&CameraShake>()
+        .single(app.world())
+        .trauma()
+}
+
+#[test]
+fn big_hits_slow_time_down_for_a_moment() {
+    let mut app = feedback_app(false);
+    let feedback = &app.world().resource::<GameConfig>().feedback;
+    let (damage, speed) = (feedback.hit_stop_damage, feedback.hit_stop_speed);
+
+    hit::<With<Enemy>>(&mut app, damage / 2.0);
+    assert_eq!(relative_speed(&app), 1.0);
+    app.run_for(0.5);
+
+    hit::<With<Enemy>>(&mut app, damage);
+    assert_eq!(relative_speed(&app), speed);
+
+    app.step(10);
+    assert_eq!(relative_speed(&app), 1.0);
+}
+
+#[test]
+fn explosions_in_every_frame_do_not_keep_time_slowed_down() {
+    let mut app = feedback_app(false);
+    let damage = app
+        .world()
+        .resource::<GameConfig>()
+        .feedback
+        .hit_stop_damage;
+    let target = app
+        .world_mut()
+        .query_filtered::<Entity, With<Enemy>>()
+        .single(app.world());
+
+    // half a second of sustained explosive fire
+    let mut slowed_frames = 0;
+    for _ in 0..30 {
+        app.world_mut().send_event(DamageEvent {
+            target,
+            context: DamageContext::without_attacker(damage, DamageType::Explosion)
+                .ignoring_cooldown(),
+            apply: true,
+        });
+        app.step(1);
+        if relative_speed(&app) != 1.0 {
+            slowed_frames += 1;
+        }
+    }
+
+    assert!(slowed_frames > 0);
+    assert!(slowed_frames < 10, "slowed down for {slowed_frames} frames");
+    assert_eq!(relative_speed(&app), 1.0);
+}
+
+#[test]
+fn player_hits_shake_the_camera() {
+    let mut app = feedback_app(false);
+
+    hit::<With<Player>>(&mut app, 1.0);
+    assert!(trauma(&mut app) > 0.0);
+}
+
+#[test]
+fn reduced_motion_turns_shake_and_hit_stop_off() {
+    let mut app = feedback_app(true);
+
+    hit::<With<Enemy>>(&mut app, f32::MAX);
+    assert_eq!(relative_speed(&app), 1.0);
+
+    hit::<With<Player>>(&mut app, 1.0);
+    assert_eq!(trauma(&mut app), 0.0);
+}
+
+#[test]
+fn turning_reduced_motion_on_ends_the_hit_stop() {
+    let mut app = feedback_app(false);
+    let damage = app
+        .world()
+        .resource::<GameConfig>()
+        .feedback
+        .hit_stop_damage;
+
+    hit::<With<Enemy>>(&mut app, damage);
+    assert_ne!(relative_speed(&app), 1.0);
+
+    app.world_mut()
+        .resource_mut::<GameConfig>()
+        .feedback
+        .reduce_motion = true;
+    app.step(1);
+    assert_eq!(relative_speed(&app), 1.0);
+}
+
+/// How far the camera moves towards the player, who jumps away right after a hit.
+fn camera_catch_up(damage: f32) -> f32 {
+    let mut app = feedback_app(false);
+    app.add_plugins(SmoothCameraPlugin);
+    app.step(1);
+    let mut player = app
+        .world_mut()
+        .query_filtered::<&mut Transform, With<Player>>()
+        .single_mut(app.world_mut());
+    player.translation += Vec3::new(200.0, 0.0, 0.0);
+
+    hit::<With<Enemy>>(&mut app, damage);
+    app.step(2);
+    app.world_mut()
+        .query_filtered::<&Transform, With<SmoothCamera>>()
+        .single(app.world())
+        .translation
+        .x
+}
+
+#[test]
+fn the_camera_keeps_following_through_hit_stop() {
+    let damage = GameConfig::default().feedback.hit_stop_damage;
+
+    let normal = camera_catch_up(0.0);
+    let slowed = camera_catch_up(damage);
+    assert!(normal > 0.0);
+    assert!(
+        (normal - slowed).abs() < 1e-3,
+        "{slowed} instead of {normal}"
+    );
+}
@@ -0,0 +1,49 @@
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::state::GameState;
+
+#[test]
+fn starts_running_with_a_healthy_player() {
+    let max_health = GameConfig::default().player.health;
+    let mut app = HeadlessBuilder::default().build();
+
+    app.step(3);
+
+    assert_eq!(app.game_state(), Some(GameState::Running));
+    assert_eq!(app.player_health(), Some(max_health));
+}
+
+#[test]
+fn spawns_enemy_waves() {
+    let mut app = HeadlessBuilder::default()
+        .configure(|config| {
+            config.enemy.spawn_rate_per_second = 10;
+            config.enemy.spawn_interval = 0.5;
+        })
+        .build();
+
+    app.step(3);
+    assert_eq!(app.enemy_count(), 0);
+
+    app.run_for(1.2);
+    let count = app.enemy_count();
+    assert!(
+        count > 0 && count % 10 == 0,
+        "unexpected enemy count {count}"
+    );
+}
+
+#[test]
+fn waves_stop_when_enemies_exceed_the_limit() {
+    let mut app = HeadlessBuilder::default()
+        .configure(|config| {
+            config.enemy.spawn_dummy = true;
+            config.enemy.spawn_limit = 0;
+            config.enemy.spawn_interval = 0.1;
+        })
+        .build();
+
+    app.run_for(0.5);
+
+    assert_eq!(app.enemy_count(), 1);
+}
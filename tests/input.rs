@@ -0,0 +1,326 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::config::{GameConfig, InputBinding};
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::input::gamepad::AimDevice;
+use bevy_2d_shooter::input::recording::*;
+use bevy_2d_shooter::input::*;
+use bevy_2d_shooter::ui::navigation::{MenuFocus, MenuNavigationPlugin};
+use bevy_2d_shooter::world::damage::*;
+use bevy_2d_shooter::world::enemy::Enemy;
+use bevy_2d_shooter::world::gun::ShotFired;
+use bevy_2d_shooter::world::player::Player;
+
+fn player_position(app: &mut App) -> Vec2 {
+    app.world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .translation
+        .truncate()
+}
+
+fn connect_gamepad(app: &mut App) -> Entity {
+    app.world_mut().spawn(Gamepad::default()).id()
+}
+
+fn push_stick(app: &mut App, gamepad: Entity, x: (GamepadAxis, f32), y: (GamepadAxis, f32)) {
+    let mut gamepad = app.world_mut().get_mut::<Gamepad>(gamepad).unwrap();
+    for (axis, value) in [x, y] {
+        gamepad.analog_mut().set(axis, value);
+    }
+}
+
+#[test]
+fn recordings_survive_a_text_round_trip() {
+    let recording = InputRecording {
+        frames: vec![
+            InputFrame::default(),
+            InputFrame {
+                move_vector: Vec2::new(0.70710677, -0.70710677),
+                cursor_position: Vec2::new(-12.5, 300.125),
+                actions: Actions::default().with(Action::Fire).with(Action::Melee),
+            },
+        ],
+    };
+
+    let parsed = InputRecording::parse(&recording.to_text()).unwrap();
+
+    assert_eq!(parsed, recording);
+}
+
+#[test]
+fn malformed_recordings_are_rejected() {
+    assert!(InputRecording::parse("1 0 0 0").is_err());
+    assert!(InputRecording::parse("1 0 0 0 fire").is_err());
+}
+
+#[test]
+fn playback_drives_the_player() {
+    let frames = vec![
+        InputFrame {
+            move_vector: Vec2::X,
+            ..default()
+        };
+        30
+    ];
+    let mut app = HeadlessBuilder::default().build();
+    app.insert_resource(InputSource::Playback)
+        .insert_resource(InputPlayback::new(InputRecording { frames }));
+
+    app.step(3);
+    let start = player_position(&mut app);
+    app.step(20);
+    let moved = player_position(&mut app) - start;
+
+    assert!(moved.x > 0.0, "player did not move right: {moved}");
+    assert!(moved.y.abs() < 0.01);
+}
+
+#[test]
+fn playback_heals_when_the_heal_action_is_released() {
+    let mut app = HeadlessBuilder::default()
+        .without_waves()
+        .configure(|config| config.basic.debug = true)
+        .build();
+    // past the damage cooldown the player spawns with
+    app.run_for(0.5);
+    let player = app
+        .world_mut()
+        .query_filtered::<Entity, With<Player>>()
+        .single(app.world());
+    app.world_mut().send_event(DamageEvent {
+        target: player,
+        context: DamageContext::without_attacker(50.0, DamageType::Enemy),
+        apply: true,
+    });
+    app.step(1);
+    let hurt = app.player_health().unwrap();
+
+    let frame = |actions: Actions| InputFrame {
+        actions,
+        ..default()
+    };
+    let mut frames = vec![frame(Actions::default().with(Action::Heal)); 3];
+    frames.extend(vec![frame(Actions::default()); 3]);
+    app.insert_resource(InputSource::Playback)
+        .insert_resource(InputPlayback::new(InputRecording { frames }));
+    app.step(3);
+    assert_eq!(app.player_health(), Some(hurt), "healed while held");
+    app.step(3);
+
+    assert_eq!(app.player_health(), Some(hurt + 20.0));
+}
+
+/// What the bot got done during a run.
+#[derive(Resource, Default)]
+struct BotOutcome {
+    shots: usize,
+    enemy_hits: usize,
+    last_position: Option<Vec2>,
+    distance: f32,
+}
+
+fn record_bot_outcome(
+    mut shot_events: EventReader<ShotFired>,
+    mut damage_events: EventReader<DamageEvent>,
+    enemy_query: Query<(), With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+    mut outcome: ResMut<BotOutcome>,
+) {
+    if let Ok(transform) = player_query.get_single() {
+        let position = transform.translation.truncate();
+        if let Some(last) = outcome.last_position.replace(position) {
+            outcome.distance += last.distance(position);
+        }
+    }
+    outcome.shots += shot_events.read().count();
+    outcome.enemy_hits += damage_events
+        .read()
+        .filter(|event| event.apply && enemy_query.contains(event.target))
+        .count();
+}
+
+#[test]
+fn bot_plays_unattended() {
+    let mut app = HeadlessBuilder::default()
+        .configure(|config| {
+            // a small wave that reaches the player quickly
+            config.enemy.spawn_rate_per_second = 10;
+            config.enemy.speed = 150.0;
+        })
+        .build();
+    app.insert_resource(InputSource::Bot)
+        .init_resource::<BotOutcome>()
+        .add_systems(Update, record_bot_outcome.after(DamagePhase::After));
+
+    app.run_for(8.0);
+
+    // whether the bot survives depends on balance, but it must have played
+    let outcome = app.world().resource::<BotOutcome>();
+    assert!(outcome.shots > 0, "the bot never fired");
+    assert!(outcome.enemy_hits > 0, "the bot never hit an enemy");
+    assert!(outcome.distance > 0.0, "the bot never moved");
+}
+
+#[test]
+fn rebound_keys_move_the_player() {
+    let mut config = GameConfig::default();
+    config.controls.move_right = vec![InputBinding::Key(KeyCode::KeyL)];
+    let mut app = HeadlessBuilder::new(config).build();
+
+    app.step(3);
+    let start = player_position(&mut app);
+    // the default binding no longer moves
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyD);
+    app.step(20);
+    let still = player_position(&mut app) - start;
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .release(KeyCode::KeyD);
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(KeyCode::KeyL);
+    app.step(20);
+    let moved = player_position(&mut app) - start;
+
+    assert!(
+        still.x.abs() < 0.01,
+        "player moved on an unbound key: {still}"
+    );
+    assert!(moved.x > 0.0, "player did not move right: {moved}");
+}
+
+#[test]
+fn left_stick_moves_the_player_as_far_as_it_is_pushed() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    let gamepad = connect_gamepad(&mut app);
+
+    app.step(3);
+    let start = player_position(&mut app);
+    push_stick(
+        &mut app,
+        gamepad,
+        (GamepadAxis::LeftStickX, 0.5),
+        (GamepadAxis::LeftStickY, 0.0),
+    );
+    app.step(20);
+    let moved = player_position(&mut app) - start;
+
+    let move_vector = app.world().resource::<MoveVector>().0;
+    assert!(
+        move_vector.abs_diff_eq(Vec2::new(0.5, 0.0), 1e-5),
+        "unexpected move vector: {move_vector}"
+    );
+    assert!(moved.x > 0.0, "player did not move right: {moved}");
+    assert!(moved.y.abs() < 0.01, "player moved sideways: {moved}");
+}
+
+#[test]
+fn sticks_inside_the_dead_zone_are_ignored() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    let gamepad = connect_gamepad(&mut app);
+    let dead_zone = GameConfig::default().controls.stick_dead_zone;
+
+    push_stick(
+        &mut app,
+        gamepad,
+        (GamepadAxis::LeftStickX, dead_zone / 2.0),
+        (GamepadAxis::LeftStickY, 0.0),
+    );
+    push_stick(
+        &mut app,
+        gamepad,
+        (GamepadAxis::RightStickX, dead_zone / 2.0),
+        (GamepadAxis::RightStickY, 0.0),
+    );
+    app.step(10);
+
+    assert_eq!(app.world().resource::<MoveVector>().0, Vec2::ZERO);
+    assert_eq!(*app.world().resource::<AimDevice>(), AimDevice::Mouse);
+}
+
+#[test]
+fn right_stick_aims_around_the_player() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    let gamepad = connect_gamepad(&mut app);
+
+    app.step(3);
+    push_stick(
+        &mut app,
+        gamepad,
+        (GamepadAxis::RightStickX, 0.0),
+        (GamepadAxis::RightStickY, 1.0),
+    );
+    app.step(5);
+    // the aim point stays where it is once the stick is released
+    push_stick(
+        &mut app,
+        gamepad,
+        (GamepadAxis::RightStickX, 0.0),
+        (GamepadAxis::RightStickY, 0.0),
+    );
+    app.step(5);
+
+    let aim = app.world().resource::<CursorPosition>().0 - player_position(&mut app);
+    assert_eq!(*app.world().resource::<AimDevice>(), AimDevice::Gamepad);
+    assert!(
+        aim.normalize().abs_diff_eq(Vec2::Y, 1e-3),
+        "not aiming up: {aim}"
+    );
+}
+
+#[test]
+fn left_stick_moves_the_menu_focus() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, InputPlugin, MenuNavigationPlugin));
+    let gamepad = connect_gamepad(&mut app);
+    // UI positions grow downwards
+    let mut spawn_button = |y: f32| {
+        app.world_mut()
+            .spawn((
+                Button,
+                GlobalTransform::from_xyz(0.0, y, 0.0),
+                InheritedVisibility::VISIBLE,
+            ))
+            .id()
+    };
+    let top = spawn_button(0.0);
+    let bottom = spawn_button(100.0);
+    let push_down = |app: &mut App| {
+        push_stick(
+            app,
+            gamepad,
+            (GamepadAxis::LeftStickX, 0.0),
+            (GamepadAxis::LeftStickY, -1.0),
+        );
+        app.update();
+        push_stick(
+            app,
+            gamepad,
+            (GamepadAxis::LeftStickX, 0.0),
+            (GamepadAxis::LeftStickY, 0.0),
+        );
+        app.update();
+    };
+
+    app.update();
+    push_down(&mut app);
+    let first = app.world().resource::<MenuFocus>().button;
+    push_down(&mut app);
+    let second = app.world().resource::<MenuFocus>().button;
+    // held, the stick moves the focus only once
+    push_stick(
+        &mut app,
+        gamepad,
+        (GamepadAxis::LeftStickX, 0.0),
+        (GamepadAxis::LeftStickY, 1.0),
+    );
+    app.update();
+    app.update();
+    let third = app.world().resource::<MenuFocus>().button;
+
+    assert_eq!(first, Some(top), "the first push focuses the top button");
+    assert_eq!(second, Some(bottom));
+    assert_eq!(third, Some(top));
+}
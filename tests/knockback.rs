@@ -0,0 +1,94 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::collision::CollisionLayer;
+use bevy_2d_shooter::world::damage::*;
+use bevy_2d_shooter::world::enemy::{Enemy, MoveSpeed, TargetRange};
+use bevy_2d_shooter::world::knockback::*;
+use bevy_2d_shooter::world::player::Player;
+
+const PUSH: f32 = 200.0;
+
+/// An enemy chasing the player from the right, with `resistance` to knockback.
+fn chasing_enemy(app: &mut App, resistance: f32) -> Entity {
+    let player = app
+        .world_mut()
+        .query_filtered::<&Transform, With<Player>>()
+        .single(app.world())
+        .translation;
+    app.world_mut()
+        .spawn((
+            Enemy,
+            Health::new(1000.0),
+            TargetRange(1000.0),
+            MoveSpeed(50.0),
+            KnockbackResistance(resistance),
+            Transform::from_translation(player + Vec3::new(100.0, 0.0, 0.0)),
+            RigidBody::Kinematic,
+            Collider::rectangle(16.0, 16.0),
+            CollisionLayers::new([CollisionLayer::Enemy], [CollisionLayer::Bullet]),
+        ))
+        .id()
+}
+
+/// Pushes `enemy` away from the player.
+fn push(app: &mut App, enemy: Entity) {
+    app.world_mut().send_event(DamageEvent {
+        target: enemy,
+        context: DamageContext::without_attacker(1.0, DamageType::Bullet)
+            .with_knockback(Vec2::X * PUSH),
+        apply: true,
+    });
+    app.step(1);
+}
+
+fn velocity(app: &App, enemy: Entity) -> Vec2 {
+    app.world().get::<LinearVelocity>(enemy).unwrap().0
+}
+
+/// The push the enemy is taking, before it eases out.
+fn knockback(app: &App, enemy: Entity) -> Option<Vec2> {
+    app.world()
+        .get::<Knockback>(enemy)
+        .map(|knockback| knockback.velocity)
+}
+
+#[test]
+fn knockback_suspends_the_chase_until_it_wears_off() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let enemy = chasing_enemy(&mut app, 0.0);
+    app.step(2);
+    assert!(velocity(&app, enemy).x < 0.0, "the enemy is not chasing");
+
+    push(&mut app, enemy);
+    assert_eq!(knockback(&app, enemy), Some(Vec2::X * PUSH));
+    app.step(3);
+    assert!(velocity(&app, enemy).x > 0.0, "the chase took over");
+
+    app.run_for(0.2);
+    assert_eq!(knockback(&app, enemy), None);
+    assert!(velocity(&app, enemy).x < 0.0, "the chase did not resume");
+}
+
+#[test]
+fn half_resistance_halves_the_push() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let enemy = chasing_enemy(&mut app, 0.5);
+
+    push(&mut app, enemy);
+    assert_eq!(knockback(&app, enemy), Some(Vec2::X * PUSH * 0.5));
+    assert!(velocity(&app, enemy).x > 0.0);
+}
+
+#[test]
+fn full_resistance_is_not_pushed_at_all() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let enemy = chasing_enemy(&mut app, 1.0);
+
+    push(&mut app, enemy);
+    assert_eq!(knockback(&app, enemy), None);
+    assert!(velocity(&app, enemy).x < 0.0, "the enemy stopped chasing");
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::camera::SmoothCamera;
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::gun::ShotFired;
+use bevy_2d_shooter::world::lighting::*;
+
+fn lit_app(enabled: bool) -> App {
+    let mut app = HeadlessBuilder::default()
+        .without_waves()
+        .configure(|config| config.lighting.enabled = enabled)
+        .build();
+    app.world_mut().spawn(SmoothCamera::new());
+    app.step(3);
+    app
+}
+
+fn overlay_visibility(app: &mut App) -> Visibility {
+    *app.world_mut()
+        .query_filtered::<&Visibility, With<LightingOverlay>>()
+        .single(app.world())
+}
+
+fn flash_count(app: &mut App) -> usize {
+    app.world_mut()
+        .query_filtered::<(), With<LightFade>>()
+        .iter(app.world())
+        .count()
+}
+
+fn drawn_lights(app: &mut App) -> Vec<Vec2> {
+    let material = app
+        .world_mut()
+        .query_filtered::<&MeshMaterial2d<Lighting>, With<LightingOverlay>>()
+        .single(app.world())
+        .0
+        .clone();
+    app.world()
+        .resource::<Assets<Lighting>>()
+        .get(&material)
+        .unwrap()
+        .light_positions()
+}
+
+#[test]
+fn without_lighting_the_scene_is_drawn_unlit() {
+    let mut app = lit_app(false);
+    app.world_mut().send_event(ShotFired {
+        position: Vec2::ZERO,
+    });
+    app.step(1);
+
+    assert_eq!(overlay_visibility(&mut app), Visibility::Hidden);
+    assert_eq!(flash_count(&mut app), 0);
+}
+
+#[test]
+fn shots_flash_and_the_flash_fades_out() {
+    let mut app = lit_app(true);
+    assert_eq!(overlay_visibility(&mut app), Visibility::Inherited);
+
+    app.world_mut().send_event(ShotFired {
+        position: Vec2::new(30.0, 0.0),
+    });
+    app.step(1);
+    assert_eq!(flash_count(&mut app), 1);
+    assert!(drawn_lights(&mut app).contains(&Vec2::new(30.0, 0.0)));
+
+    let duration = app
+        .world()
+        .resource::<GameConfig>()
+        .lighting
+        .muzzle_flash_light
+        .duration;
+    app.run_for(duration + 0.1);
+    assert_eq!(flash_count(&mut app), 0);
+}
+
+#[test]
+fn only_the_lights_nearest_to_the_camera_are_drawn() {
+    let mut app = lit_app(true);
+    let light = PointLight2d {
+        color: Color::WHITE,
+        radius: 50.0,
+        intensity: 1.0,
+    };
+    // farther than the player's light, which is at the camera
+    let positions: Vec<Vec2> = (0..MAX_LIGHTS + 8)
+        .map(|index| Vec2::new(1000.0 + index as f32 * 10.0, 0.0))
+        .collect();
+    for position in &positions {
+        app.world_mut()
+            .spawn((light, Transform::from_translation(position.extend(0.0))));
+    }
+    app.step(1);
+
+    let drawn = drawn_lights(&mut app);
+    assert_eq!(drawn.len(), MAX_LIGHTS);
+    assert!(drawn.contains(&positions[0]));
+    assert!(!drawn.contains(positions.last().unwrap()));
+}
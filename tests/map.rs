@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::world::map::{MapTiles, Terrain};
+
+/// 4x2 tiles of 10 pixels, water on the left column, sand next to it.
+fn tiles() -> MapTiles {
+    let column = |terrain| vec![terrain; 2];
+    MapTiles {
+        tile_size: 10.0,
+        terrain: vec![
+            column(Terrain::Water),
+            column(Terrain::Sand),
+            column(Terrain::Grass),
+            column(Terrain::Grass),
+        ],
+    }
+}
+
+#[test]
+fn tiles_are_laid_out_around_the_origin() {
+    let tiles = tiles();
+    assert_eq!(tiles.size(), UVec2::new(4, 2));
+    assert_eq!(tiles.tile_at(Vec2::new(-20.0, -10.0)), IVec2::new(0, 0));
+    assert_eq!(tiles.tile_at(Vec2::new(-0.1, 0.0)), IVec2::new(1, 1));
+    assert_eq!(tiles.tile_at(Vec2::new(19.9, 9.9)), IVec2::new(3, 1));
+}
+
+#[test]
+fn terrain_is_looked_up_by_world_position() {
+    let tiles = tiles();
+    assert_eq!(
+        tiles.terrain_at(Vec2::new(-15.0, 5.0)),
+        Some(Terrain::Water)
+    );
+    assert_eq!(tiles.terrain_at(Vec2::new(-5.0, -5.0)), Some(Terrain::Sand));
+    assert_eq!(tiles.terrain_at(Vec2::new(15.0, 0.0)), Some(Terrain::Grass));
+    assert_eq!(tiles.terrain_at(Vec2::new(-25.0, 0.0)), None);
+    assert_eq!(tiles.terrain_at(Vec2::new(0.0, 10.0)), None);
+}
@@ -0,0 +1,90 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::input::recording::*;
+use bevy_2d_shooter::input::*;
+use bevy_2d_shooter::world::collision::CollisionLayer;
+use bevy_2d_shooter::world::damage::Health;
+use bevy_2d_shooter::world::enemy::Enemy;
+use bevy_2d_shooter::world::melee::MeleeTimer;
+
+const HEALTH: f32 = 1000.0;
+
+fn enemy_at(position: Vec2, size: f32) -> impl Bundle {
+    (
+        Enemy,
+        Health::new(HEALTH),
+        Transform::from_translation(position.extend(0.0)),
+        RigidBody::Kinematic,
+        Collider::rectangle(size, size),
+        CollisionLayers::new([CollisionLayer::Enemy], [CollisionLayer::Bullet]),
+    )
+}
+
+fn was_hit(app: &App, enemy: Entity) -> bool {
+    app.world().get::<Health>(enemy).unwrap().current() < HEALTH
+}
+
+/// Swings once to the right of the player, who stands at the origin.
+fn swing_right(app: &mut App) {
+    let frame = |actions: Actions| InputFrame {
+        cursor_position: Vec2::new(100.0, 0.0),
+        actions,
+        ..default()
+    };
+    let mut frames = vec![frame(Actions::default()); 3];
+    frames.push(frame(Actions::default().with(Action::Melee)));
+    frames.extend(vec![frame(Actions::default()); 20]);
+    let count = frames.len();
+    app.insert_resource(InputSource::Playback)
+        .insert_resource(InputPlayback::new(InputRecording { frames }));
+    app.step(count);
+}
+
+#[test]
+fn swings_hit_what_the_blade_passes_over() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let in_front = app
+        .world_mut()
+        .spawn(enemy_at(Vec2::new(20.0, 0.0), 16.0))
+        .id();
+    let behind = app
+        .world_mut()
+        .spawn(enemy_at(Vec2::new(-20.0, 0.0), 16.0))
+        .id();
+    // its center is past the edge of the arc and out of reach, its body is not
+    let large = app
+        .world_mut()
+        .spawn(enemy_at(Vec2::new(14.0, 26.0), 30.0))
+        .id();
+
+    swing_right(&mut app);
+
+    assert!(was_hit(&app, in_front), "the enemy in front was not hit");
+    assert!(!was_hit(&app, behind), "the enemy behind was hit");
+    assert!(was_hit(&app, large), "the large enemy was not hit");
+}
+
+#[test]
+fn the_cooldown_runs_during_the_swing() {
+    let mut app = HeadlessBuilder::default()
+        .without_waves()
+        .configure(|config| {
+            config.melee.swing_duration = 0.15;
+            config.melee.cooldown = 0.5;
+        })
+        .build();
+    app.step(3);
+
+    // 20 frames after the swing started, 35 once these are over
+    swing_right(&mut app);
+    app.step(15);
+
+    let timer = app.world_mut().query::<&MeleeTimer>().single(app.world());
+    assert!(
+        timer.0.finished(),
+        "{:?} into the cooldown",
+        timer.0.elapsed()
+    );
+}
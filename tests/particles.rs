@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::particle::*;
+
+fn particle_app(config: GameConfig) -> App {
+    let mut app = HeadlessBuilder::new(config).without_waves().build();
+    app.step(1);
+    app
+}
+
+fn particles(app: &mut App) -> usize {
+    app.world_mut()
+        .query::<&Particle>()
+        .iter(app.world())
+        .count()
+}
+
+fn explode(app: &mut App) {
+    app.world_mut().send_event(SpawnParticles::new(
+        effects::EXPLOSION,
+        Vec2::ZERO,
+        Vec2::ZERO,
+    ));
+}
+
+#[test]
+fn bursts_emit_the_effect_count_and_particles_expire() {
+    let mut app = particle_app(GameConfig::default());
+    let count = app.world().resource::<GameConfig>().particles.effects[effects::EXPLOSION].count;
+
+    explode(&mut app);
+    app.step(2);
+    assert_eq!(particles(&mut app), count);
+
+    app.run_for(1.0);
+    assert_eq!(particles(&mut app), 0);
+}
+
+#[test]
+fn particles_are_capped_by_the_budget() {
+    let mut config = GameConfig::default();
+    config.particles.max_particles = 20;
+    let mut app = particle_app(config);
+
+    for _ in 0..10 {
+        explode(&mut app);
+    }
+    app.step(2);
+
+    assert_eq!(particles(&mut app), 20);
+}
+
+#[test]
+fn continuous_emitters_emit_their_rate() {
+    let mut config = GameConfig::default();
+    let trail = config
+        .particles
+        .effects
+        .get_mut(effects::BOSS_BULLET_TRAIL)
+        .unwrap();
+    trail.rate = 10.0;
+    trail.lifetime = [5.0, 5.0];
+    let mut app = particle_app(config);
+
+    app.world_mut().spawn((
+        ParticleEmitter::continuous(effects::BOSS_BULLET_TRAIL),
+        Transform::default(),
+    ));
+    app.run_for(1.0);
+
+    let emitted = particles(&mut app);
+    assert!((9..=10).contains(&emitted), "{emitted}");
+}
+
+#[test]
+fn disabled_particles_emit_nothing() {
+    let mut config = GameConfig::default();
+    config.particles.enabled = false;
+    let mut app = particle_app(config);
+
+    explode(&mut app);
+    app.step(2);
+
+    assert_eq!(particles(&mut app), 0);
+}
@@ -0,0 +1,76 @@
+use bevy::ecs::world::Command;
+use bevy::prelude::*;
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::input::*;
+use bevy_2d_shooter::replay::*;
+use bevy_2d_shooter::state::AppState;
+
+/// Lets the bot play for `seconds` and returns the recording of its run. `name` is
+/// the file it is saved to, one per test as they run in parallel.
+fn record_bot_run(name: &str, seconds: f32) -> Replay {
+    let mut config = GameConfig::default();
+    config.replay.record = true;
+    config.replay.path = std::env::temp_dir()
+        .join(format!("bevy-2d-shooter-{name}.replay"))
+        .to_string_lossy()
+        .into_owned();
+    let mut app = HeadlessBuilder::new(config).build();
+    app.insert_resource(InputSource::Bot);
+
+    app.run_for(seconds);
+    // leaving the game ends the recording
+    app.world_mut()
+        .resource_mut::<NextState<AppState>>()
+        .set(AppState::MainMenu);
+    app.update();
+
+    app.world_mut().remove_resource::<LastReplay>().unwrap().0
+}
+
+/// Plays `replay` back to the end and returns the first diverging tick.
+fn play(replay: Replay) -> Option<usize> {
+    let ticks = replay.ticks.len();
+    let mut app = HeadlessBuilder::default().build();
+    StartReplay(replay).apply(app.world_mut());
+
+    app.step(ticks + 3);
+
+    let player = app.world().resource::<ReplayPlayer>();
+    assert!(player.finished);
+    player.first_mismatch
+}
+
+#[test]
+fn replays_survive_a_file_round_trip() {
+    let replay = record_bot_run("round_trip", 2.0);
+    assert!(!replay.ticks.is_empty());
+
+    let parsed = Replay::from_bytes(&replay.to_bytes()).unwrap();
+
+    assert_eq!(parsed, replay);
+}
+
+#[test]
+fn truncated_replays_are_rejected() {
+    let bytes = record_bot_run("truncated", 1.0).to_bytes();
+
+    assert!(Replay::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Replay::from_bytes(b"not a replay").is_err());
+}
+
+#[test]
+fn runs_replay_exactly() {
+    let replay = record_bot_run("exact", 10.0);
+
+    assert_eq!(play(replay), None);
+}
+
+#[test]
+fn reports_the_first_diverging_tick() {
+    let mut replay = record_bot_run("diverging", 3.0);
+    replay.ticks[40].checksum ^= 1;
+    replay.ticks[80].checksum ^= 1;
+
+    assert_eq!(play(replay), Some(40));
+}
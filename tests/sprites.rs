@@ -0,0 +1,55 @@
+use bevy_2d_shooter::sprite_manifest::*;
+
+const SHEET: &str = "tile_size = 16\ncolumns = 8\nrows = 8\n";
+
+fn manifest(global_extra: &str, tiles_extra: &str) -> String {
+    let sprites: String = sprites::REQUIRED
+        .iter()
+        .enumerate()
+        .map(|(index, name)| format!("{name} = {index}\n"))
+        .collect();
+    let clips: String = clips::REQUIRED
+        .iter()
+        .map(|name| format!("{name} = {{ first = 0, length = 4, frame_duration = 0.1 }}\n"))
+        .collect();
+    let tiles: String = tiles::REQUIRED
+        .iter()
+        .enumerate()
+        .map(|(index, name)| format!("{name} = {index}\n"))
+        .collect();
+    format!(
+        "[global]\n{SHEET}[global.sprites]\n{sprites}{global_extra}[global.clips]\n{clips}\
+         [tiles]\n{SHEET}[tiles.sprites]\n{tiles}{tiles_extra}"
+    )
+}
+
+#[test]
+fn builtin_manifest_names_every_sprite_and_clip() {
+    let manifest = SpriteManifest::builtin();
+    assert_eq!(manifest.global.index(sprites::BULLET), 16);
+    assert_eq!(manifest.global.clip("player_run").first, 4);
+    assert_eq!(manifest.tiles.index(tiles::WATER), 2);
+}
+
+#[test]
+fn complete_manifest_parses() {
+    let manifest = SpriteManifest::parse(&manifest("", "")).unwrap();
+    assert_eq!(manifest.global.clip(clips::EXPLOSION).length, 4);
+}
+
+#[test]
+fn missing_names_are_reported() {
+    let text = manifest("", "").replace("gun = 1\n", "");
+    let err = SpriteManifest::parse(&text).unwrap_err();
+    assert!(err.contains("global.sprites: missing `gun`"), "{err}");
+}
+
+#[test]
+fn regions_outside_the_sheet_are_reported() {
+    let err = SpriteManifest::parse(&manifest("spare = 64\n", "")).unwrap_err();
+    assert!(err.contains("global.sprites.spare"), "{err}");
+
+    let text = manifest("", "").replace("explosion = { first = 0", "explosion = { first = 62");
+    let err = SpriteManifest::parse(&text).unwrap_err();
+    assert!(err.contains("global.clips.explosion"), "{err}");
+}
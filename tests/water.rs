@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use bevy_2d_shooter::config::GameConfig;
+use bevy_2d_shooter::headless::*;
+use bevy_2d_shooter::world::map::{MapTiles, Terrain};
+use bevy_2d_shooter::world::water::*;
+
+/// 3x2 tiles: water in the top left corner and the middle column, sand below the
+/// corner and grass on the right.
+fn tiles() -> MapTiles {
+    MapTiles {
+        tile_size: 10.0,
+        terrain: vec![
+            vec![Terrain::Sand, Terrain::Water],
+            vec![Terrain::Water, Terrain::Water],
+            vec![Terrain::Grass, Terrain::Grass],
+        ],
+    }
+}
+
+/// The red and green channels of the texel `x` across, `row` down.
+fn texel(image: &Image, x: usize, row: usize) -> [u8; 2] {
+    let width = image.width() as usize;
+    let index = (row * width + x) * 4;
+    [image.data[index], image.data[index + 1]]
+}
+
+#[test]
+fn the_mask_starts_at_the_top_of_the_map() {
+    let mask = water_mask(&tiles());
+
+    assert_eq!(mask.size(), UVec2::new(3, 2));
+    // the top row of tiles
+    assert_eq!(texel(&mask, 0, 0), [255, 255]);
+    assert_eq!(texel(&mask, 1, 0), [255, 0]);
+    assert_eq!(texel(&mask, 2, 0), [0, 0]);
+    // the bottom row
+    assert_eq!(texel(&mask, 0, 1), [0, 0]);
+    assert_eq!(texel(&mask, 1, 1), [255, 255]);
+    assert_eq!(texel(&mask, 2, 1), [0, 0]);
+}
+
+#[test]
+fn ripples_age_and_the_oldest_are_dropped() {
+    let mut app = HeadlessBuilder::default().without_waves().build();
+    app.step(3);
+    let ripples = (0..MAX_RIPPLES + 4).map(|index| Ripple {
+        position: Vec2::new(index as f32, 0.0),
+        age: 0.0,
+    });
+    app.world_mut().resource_mut::<Ripples>().0.extend(ripples);
+
+    app.step(1);
+    let kept = app.world().resource::<Ripples>().0.clone();
+    assert_eq!(kept.len(), MAX_RIPPLES);
+    assert_eq!(kept[0].position.x, 4.0, "the oldest were not dropped");
+    assert!(kept.iter().all(|ripple| ripple.age > 0.0));
+
+    let lifetime = GameConfig::default().water.ripple_lifetime;
+    app.run_for(lifetime);
+    assert!(app.world().resource::<Ripples>().0.is_empty());
+}